@@ -11,9 +11,18 @@
 #include "GameFramework/PlayerInput.h"
 #include "EntityComponent.h"
 #include "Camera/CameraActor.h"
+#include "Camera/CameraComponent.h"
 #include "Components/PrimitiveComponent.h"
 #include "Sound/SoundBase.h"
+#include "Curves/CurveFloat.h"
 #include "VisualLogger/VisualLogger.h"
+#include "DrawDebugHelpers.h"
+#include "Engine/NetDriver.h"
+#include "Engine/PackageMapClient.h"
+#include "GameFramework/WorldSettings.h"
+#include "Misc/FileHelper.h"
+#include "Misc/Paths.h"
+#include "Framework/Application/SlateApplication.h"
 
 DEFINE_LOG_CATEGORY(RustVisualLog);
 
@@ -41,11 +50,31 @@ void GetSpatialData(const AActorOpaque* actor,
 	*scale = ToVector3(Transform.GetScale3D());
 }
 
-void Log(const char* s, int32 len)
+void GetSpatialDataBulk(const AActorOpaque* const* actors, SpatialData* out, uintptr_t len)
 {
-	// TODO: Can we get rid of that allocation?
-	FString LogString = FString(len, UTF8_TO_TCHAR(s));
-	UE_LOG(LogTemp, Warning, TEXT("%s"), *LogString);
+	for (uintptr_t i = 0; i < len; ++i)
+	{
+		GetSpatialData(actors[i], &out[i].position, &out[i].rotation, &out[i].scale);
+	}
+}
+
+void Log(LogLevel level, const char* s, int32 len)
+{
+	// `s` is not null terminated, so the length has to be passed to the conversion
+	FUTF8ToTCHAR Converted(s, len);
+	FString LogString = FString(Converted.Length(), Converted.Get());
+	switch (level)
+	{
+	case LogLevel::Error:
+		UE_LOG(LogTemp, Error, TEXT("%s"), *LogString);
+		break;
+	case LogLevel::Warning:
+		UE_LOG(LogTemp, Warning, TEXT("%s"), *LogString);
+		break;
+	default:
+		UE_LOG(LogTemp, Display, TEXT("%s"), *LogString);
+		break;
+	}
 }
 
 void IterateActors(AActorOpaque** array, uint64_t* len)
@@ -61,9 +90,14 @@ void IterateActors(AActorOpaque** array, uint64_t* len)
 	*len = i;
 }
 
-void GetActionState(const char* name, uintptr_t len, ActionState state, uint32_t* out)
+void GetActionState(uint32_t player_index, const char* name, uintptr_t len, ActionState state, uint32_t* out)
 {
-	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, 0);
+	*out = false;
+	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, (int32)player_index);
+	if (PC == nullptr || PC->PlayerInput == nullptr)
+	{
+		return;
+	}
 
 	FName ActionName((int32)len, name);
 
@@ -97,10 +131,26 @@ void GetActionState(const char* name, uintptr_t len, ActionState state, uint32_t
 	*out = false;
 }
 
-void GetAxisValue(const char* name, uintptr_t len, float* value)
+void GetAxisValue(uint32_t player_index, const char* name, uintptr_t len, float* value)
 {
 	FName AxisName((int32)len, name);
-	*value = GetRustModule().GameMode->InputComponent->GetAxisValue(AxisName);
+	if (player_index == 0)
+	{
+		*value = GetRustModule().GameMode->InputComponent->GetAxisValue(AxisName);
+		return;
+	}
+
+	// The axis bindings of the game mode only receive input of the first player
+	*value = 0.0f;
+	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, (int32)player_index);
+	if (PC == nullptr || PC->PlayerInput == nullptr)
+	{
+		return;
+	}
+	for (const FInputAxisKeyMapping& Mapping : PC->PlayerInput->GetKeysForAxis(AxisName))
+	{
+		*value += PC->PlayerInput->GetKeyValue(Mapping.Key) * Mapping.Scale;
+	}
 }
 
 void SetEntityForActor(AActorOpaque* actor, Entity entity)
@@ -155,6 +205,74 @@ AActorOpaque* SpawnActor(ActorClass class_,
 	return (AActorOpaque*)GetRustModule().GameMode->GetWorld()->SpawnActor(Class, &Pos, &Rot, FActorSpawnParameters{});
 }
 
+AActorOpaque* SpawnActorFromClass(const UClassOpague* class_,
+                                  Vector3 position,
+                                  Quaternion rotation,
+                                  Vector3 scale)
+{
+	FTransform Transform(ToFQuat(rotation), ToFVector(position), ToFVector(scale));
+	return (AActorOpaque*)GetRustModule().GameMode->GetWorld()->SpawnActor(
+		(UClass*)class_, &Transform, FActorSpawnParameters{});
+}
+
+UClassOpague* LoadClass(Utf8Str path)
+{
+	UClass* Class = LoadObject<UClass>(nullptr, *ToFString(path));
+	if (Class == nullptr || !Class->IsChildOf(AActor::StaticClass()))
+	{
+		return nullptr;
+	}
+	// Rust holds on to the raw pointer, which the garbage collector knows nothing about
+	Class->AddToRoot();
+	return Class;
+}
+
+uint32_t LoadCurveKeys(Utf8Str path, CurveKey* keys, uintptr_t* len)
+{
+	UCurveFloat* Curve = LoadObject<UCurveFloat>(nullptr, *ToFString(path));
+	if (Curve == nullptr)
+	{
+		*len = 0;
+		return 0;
+	}
+	const TArray<FRichCurveKey>& Keys = Curve->FloatCurve.GetConstRefOfKeys();
+	if (keys == nullptr)
+	{
+		*len = Keys.Num();
+		return 1;
+	}
+	const uintptr_t Count = FMath::Min(*len, (uintptr_t)Keys.Num());
+	for (uintptr_t i = 0; i < Count; ++i)
+	{
+		keys[i] = CurveKey{Keys[i].Time, Keys[i].Value};
+	}
+	*len = Count;
+	return 1;
+}
+
+uint32_t RegisterName(const char* name, uintptr_t len)
+{
+	FName Name((int32)len, name);
+	return Name.GetComparisonIndex().ToUnstableInt();
+}
+
+void OnComponentChanged(const AActorOpaque* actor, Uuid uuid)
+{
+	UEntityComponent* Component = ToAActor(actor)->FindComponentByClass<UEntityComponent>();
+	if (Component != nullptr)
+	{
+		Component->OnComponentChanged.Broadcast(ToFGuid(uuid));
+	}
+}
+
+void OnComponentsChangedBulk(const ComponentChange* changes, uintptr_t len)
+{
+	for (uintptr_t i = 0; i < len; ++i)
+	{
+		OnComponentChanged(changes[i].actor, changes[i].uuid);
+	}
+}
+
 void SetViewTarget(const AActorOpaque* actor)
 {
 	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, 0);
@@ -163,10 +281,136 @@ void SetViewTarget(const AActorOpaque* actor)
 
 void GetMouseDelta(float* x, float* y)
 {
+	*x = 0.0f;
+	*y = 0.0f;
 	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, 0);
+	if (PC == nullptr)
+	{
+		return;
+	}
 	PC->GetInputMouseDelta(*x, *y);
 }
 
+void GetMouseState(MouseState* state)
+{
+	*state = MouseState{};
+	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, 0);
+	if (PC == nullptr)
+	{
+		return;
+	}
+	float X, Y;
+	if (PC->GetMousePosition(X, Y))
+	{
+		state->has_position = 1;
+		state->position = ToVector2(FVector2D(X, Y));
+	}
+	state->scroll = PC->GetInputAnalogKeyState(EKeys::MouseWheelAxis);
+}
+
+uint32_t ProjectWorldToScreen(Vector3 world, Vector2* screen)
+{
+	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, 0);
+	FVector2D Screen;
+	if (PC == nullptr || !PC->ProjectWorldLocationToScreen(ToFVector(world), Screen, false))
+	{
+		return 0;
+	}
+	*screen = ToVector2(Screen);
+	return 1;
+}
+
+void GetViewportSize(uint32_t* width, uint32_t* height, float* dpi_scale)
+{
+	*width = 0;
+	*height = 0;
+	*dpi_scale = 1.0f;
+	if (GEngine == nullptr || GEngine->GameViewport == nullptr)
+	{
+		return;
+	}
+	FVector2D Size;
+	GEngine->GameViewport->GetViewportSize(Size);
+	*width = (uint32_t)FMath::Max(Size.X, 0.0);
+	*height = (uint32_t)FMath::Max(Size.Y, 0.0);
+	*dpi_scale = GEngine->GameViewport->GetDPIScale();
+}
+
+void GetConfigBlob(RustAlloc* data)
+{
+	const FString Path = FPaths::Combine(FPaths::ProjectConfigDir(), TEXT("RustGame.toml"));
+	FString Config;
+	if (!FFileHelper::LoadFileToString(Config, *Path))
+	{
+		return;
+	}
+	auto Utf8 = FTCHARToUTF8(*Config);
+	GetRustModule().Plugin.Rust.allocate_fns.allocate(Utf8.Length(), 1, data);
+	FMemory::Memcpy(data->ptr, Utf8.Get(), data->size);
+}
+
+float GetTimeDilation()
+{
+	AWorldSettings* Settings = GetRustModule().GameMode->GetWorldSettings();
+	if (Settings == nullptr)
+	{
+		return 1.0f;
+	}
+	return Settings->GetEffectiveTimeDilation();
+}
+
+uint32_t DeprojectScreenToWorld(Vector2 screen, Vector3* origin, Vector3* direction)
+{
+	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, 0);
+	FVector Origin, Direction;
+	if (PC == nullptr || !PC->DeprojectScreenPositionToWorld(screen.x, screen.y, Origin, Direction))
+	{
+		return 0;
+	}
+	*origin = ToVector3(Origin);
+	*direction = ToVector3(Direction);
+	return 1;
+}
+
+void SetMouseCaptured(uint32_t captured)
+{
+	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, 0);
+	if (PC == nullptr)
+	{
+		return;
+	}
+	PC->SetShowMouseCursor(captured == 0);
+	if (captured)
+	{
+		PC->SetInputMode(FInputModeGameOnly());
+	}
+	else
+	{
+		PC->SetInputMode(FInputModeGameAndUI());
+	}
+}
+
+void SetCameraProjection(AActorOpaque* actor, CameraProjection projection)
+{
+	ACameraActor* Camera = Cast<ACameraActor>(ToAActor(actor));
+	if (Camera == nullptr)
+	{
+		return;
+	}
+	UCameraComponent* Component = Camera->GetCameraComponent();
+	switch (projection.mode)
+	{
+	case ProjectionMode::Perspective:
+		Component->SetProjectionMode(ECameraProjectionMode::Perspective);
+		Component->SetFieldOfView(projection.fov);
+		break;
+	case ProjectionMode::Orthographic:
+		Component->SetProjectionMode(ECameraProjectionMode::Orthographic);
+		Component->SetOrthoWidth(projection.ortho_width);
+		break;
+	}
+}
+
 void GetActorComponents(const AActorOpaque* actor, ActorComponentPtr* data, uintptr_t* len)
 {
 	TSet<UActorComponent*> Components = ToAActor(actor)->GetComponents();
@@ -214,6 +458,11 @@ uint32_t IsSimulating(const UPrimtiveOpaque* primitive)
 	return ((UPrimitiveComponent*)primitive)->IsSimulatingPhysics(FName{});
 }
 
+void SetSimulatePhysics(UPrimtiveOpaque* primitive, uint32_t simulate)
+{
+	((UPrimitiveComponent*)primitive)->SetSimulatePhysics(simulate != 0);
+}
+
 Vector3 GetVelocity(const UPrimtiveOpaque* primitive)
 {
 	return ToVector3(((UPrimitiveComponent*)primitive)->GetComponentVelocity());
@@ -224,6 +473,65 @@ void SetVelocity(UPrimtiveOpaque* primitive, Vector3 velocity)
 	((UPrimitiveComponent*)primitive)->SetPhysicsLinearVelocity(ToFVector(velocity), false, FName{});
 }
 
+Vector3 GetAngularVelocity(const UPrimtiveOpaque* primitive)
+{
+	return ToVector3(((UPrimitiveComponent*)primitive)->GetPhysicsAngularVelocityInRadians(FName{}));
+}
+
+void SetAngularVelocity(UPrimtiveOpaque* primitive, Vector3 angular_velocity)
+{
+	((UPrimitiveComponent*)primitive)->SetPhysicsAngularVelocityInRadians(ToFVector(angular_velocity), false, FName{});
+}
+
+void AddTorque(UPrimtiveOpaque* primitive, Vector3 torque)
+{
+	((UPrimitiveComponent*)primitive)->AddTorqueInRadians(ToFVector(torque), FName{}, false);
+}
+
+void AddAngularImpulse(UPrimtiveOpaque* primitive, Vector3 impulse)
+{
+	((UPrimitiveComponent*)primitive)->AddAngularImpulseInRadians(ToFVector(impulse), FName{}, false);
+}
+
+void GetCollisionResponse(const UPrimtiveOpaque* primitive, CollisionResponse* response)
+{
+	const UPrimitiveComponent* Primitive = (const UPrimitiveComponent*)primitive;
+	*response = CollisionResponse{};
+	ToCollisionChannel(Primitive->GetCollisionObjectType(), &response->object_type);
+	for (uint32_t Channel = 0; Channel <= (uint32_t)CollisionChannel::Destructible; ++Channel)
+	{
+		const ECollisionResponse Response =
+			Primitive->GetCollisionResponseToChannel(ToECollisionChannel((CollisionChannel)Channel));
+		if (Response == ECR_Block)
+		{
+			response->channels |= 1u << Channel;
+		}
+		else if (Response == ECR_Overlap)
+		{
+			response->overlap_channels |= 1u << Channel;
+		}
+	}
+}
+
+void SetCollisionResponse(UPrimtiveOpaque* primitive, CollisionResponse response)
+{
+	UPrimitiveComponent* Primitive = (UPrimitiveComponent*)primitive;
+	Primitive->SetCollisionObjectType(ToECollisionChannel(response.object_type));
+	for (uint32_t Channel = 0; Channel <= (uint32_t)CollisionChannel::Destructible; ++Channel)
+	{
+		ECollisionResponse Response = ECR_Ignore;
+		if ((response.channels & (1u << Channel)) != 0)
+		{
+			Response = ECR_Block;
+		}
+		else if ((response.overlap_channels & (1u << Channel)) != 0)
+		{
+			Response = ECR_Overlap;
+		}
+		Primitive->SetCollisionResponseToChannel(ToECollisionChannel((CollisionChannel)Channel), Response);
+	}
+}
+
 uint32_t LineTrace(Vector3 start, Vector3 end, LineTraceParams Params, HitResult* result)
 {
 	FHitResult Out;
@@ -248,41 +556,64 @@ uint32_t LineTrace(Vector3 start, Vector3 end, LineTraceParams Params, HitResult
 	return IsHit;
 }
 
-uint32_t OverlapMulti(CollisionShape shape,
-                      Vector3 position,
-                      Quaternion rotation,
-                      LineTraceParams params,
-                      uintptr_t max_results,
-                      OverlapResult** results)
+uint32_t Raycast(Vector3 start, Vector3 end, TraceParams params, HitResult* result)
 {
-	TArray<FOverlapResult> Out;
+	FHitResult Out;
 	auto CollisionParams = FCollisionQueryParams();
 	for (uintptr_t i = 0; i < params.ignored_actors_len; ++i)
 	{
 		CollisionParams.AddIgnoredActor((AActor*)params.ignored_actors[i]);
 	}
-	bool IsHit = GetRustModule().GameMode->GetWorld()->OverlapMultiByChannel(Out,
-	                                                                         ToFVector(position),
-	                                                                         ToFQuat(rotation),
-	                                                                         ECollisionChannel::ECC_MAX,
-	                                                                         ToFCollisionShape(shape),
-	                                                                         CollisionParams,
-	                                                                         FCollisionResponseParams{});
+	bool IsHit = GetRustModule().GameMode->GetWorld()->LineTraceSingleByChannel(
+		Out, ToFVector(start), ToFVector(end), ToECollisionChannel(params.channel), CollisionParams,
+		FCollisionResponseParams{});
 	if (IsHit)
 	{
-		uintptr_t Length = FGenericPlatformMath::Min(max_results, (uintptr_t)Out.Num());
-		for (uintptr_t i = 0; i < Length; ++i)
-		{
-			OverlapResult* result = results[i];
-			FOverlapResult* Hit = &Out[i];
-			result->actor = (AActorOpaque*)Hit->GetActor();
-			result->primtive = (UPrimtiveOpaque*)Hit->GetComponent();
-		}
+		result->actor = (AActorOpaque*)Out.GetActor();
+		result->primtive = (UPrimtiveOpaque*)Out.GetComponent();
+		result->distance = Out.Distance;
+		result->location = ToVector3(Out.Location);
+		result->normal = ToVector3(Out.Normal);
+		result->impact_location = ToVector3(Out.ImpactPoint);
+		result->impact_normal = ToVector3(Out.ImpactNormal);
+		result->pentration_depth = Out.PenetrationDepth;
+		result->start_penetrating = Out.bStartPenetrating;
 	}
 
 	return IsHit;
 }
 
+uintptr_t OverlapMulti(CollisionShape shape,
+                       Vector3 position,
+                       Quaternion rotation,
+                       TraceParams params,
+                       uintptr_t max_results,
+                       OverlapResult* results)
+{
+	TArray<FOverlapResult> Out;
+	auto CollisionParams = FCollisionQueryParams();
+	for (uintptr_t i = 0; i < params.ignored_actors_len; ++i)
+	{
+		CollisionParams.AddIgnoredActor((AActor*)params.ignored_actors[i]);
+	}
+	// The return value only reports blocking overlaps, `Out` also contains the touching ones
+	GetRustModule().GameMode->GetWorld()->OverlapMultiByChannel(Out,
+	                                                            ToFVector(position),
+	                                                            ToFQuat(rotation),
+	                                                            ToECollisionChannel(params.channel),
+	                                                            ToFCollisionShape(shape),
+	                                                            CollisionParams,
+	                                                            FCollisionResponseParams{});
+	uintptr_t Length = FGenericPlatformMath::Min(max_results, (uintptr_t)Out.Num());
+	for (uintptr_t i = 0; i < Length; ++i)
+	{
+		FOverlapResult* Overlap = &Out[i];
+		results[i].actor = (AActorOpaque*)Overlap->GetActor();
+		results[i].primtive = (UPrimtiveOpaque*)Overlap->GetComponent();
+	}
+	return Length;
+}
+
 void VisualLogSegment(const AActorOpaque* actor, Vector3 start, Vector3 end, Color color)
 {
 	UE_VLOG_SEGMENT(ToAActor(actor), RustVisualLog, Log, ToFVector(start), ToFVector(end), ToFColor(color), TEXT(""));
@@ -354,6 +685,46 @@ uint32_t Sweep(Vector3 start,
 		result->impact_normal = ToVector3(Out.ImpactNormal);
 		result->pentration_depth = Out.PenetrationDepth;
 		result->start_penetrating = Out.bStartPenetrating;
+		result->time = Out.Time;
+	}
+
+	return IsHit;
+}
+
+uint32_t SweepByChannel(Vector3 start,
+                        Vector3 end,
+                        Quaternion rotation,
+                        TraceParams params,
+                        CollisionShape shape,
+                        HitResult* result)
+{
+	FHitResult Out;
+	auto CollisionParams = FCollisionQueryParams();
+	CollisionParams.bFindInitialOverlaps = true;
+	for (uintptr_t i = 0; i < params.ignored_actors_len; ++i)
+	{
+		CollisionParams.AddIgnoredActor((AActor*)params.ignored_actors[i]);
+	}
+	bool IsHit = GetRustModule().GameMode->GetWorld()->SweepSingleByChannel(
+		Out,
+		ToFVector(start),
+		ToFVector(end),
+		ToFQuat(rotation),
+		ToECollisionChannel(params.channel),
+		ToFCollisionShape(shape),
+		CollisionParams, FCollisionResponseParams{});
+	if (IsHit)
+	{
+		result->actor = (AActorOpaque*)Out.GetActor();
+		result->primtive = (UPrimtiveOpaque*)Out.GetComponent();
+		result->distance = Out.Distance;
+		result->location = ToVector3(Out.Location);
+		result->normal = ToVector3(Out.Normal);
+		result->impact_location = ToVector3(Out.ImpactPoint);
+		result->impact_normal = ToVector3(Out.ImpactNormal);
+		result->pentration_depth = Out.PenetrationDepth;
+		result->start_penetrating = Out.bStartPenetrating;
+		result->time = Out.Time;
 	}
 
 	return IsHit;
@@ -401,6 +772,7 @@ uint32_t SweepMulti(Vector3 start,
 			results[i].impact_normal = ToVector3(Hit.ImpactNormal);
 			results[i].pentration_depth = Hit.PenetrationDepth;
 			results[i].start_penetrating = Hit.bStartPenetrating;
+			results[i].time = Hit.Time;
 		}
 	}
 	return Length;
@@ -607,7 +979,25 @@ void PlaySoundAtLocation(const USoundBaseOpague* sound, Vector3 location, Quater
                          const SoundSettings* settings)
 {
 	auto World = GetRustModule().GameMode->GetWorld();
-	UGameplayStatics::PlaySoundAtLocation(World, (USoundBase*)sound, ToFVector(location), ToFQuat(rotation).Rotator());
+	UGameplayStatics::PlaySoundAtLocation(World, (USoundBase*)sound, ToFVector(location), ToFQuat(rotation).Rotator(),
+	                                      settings->volume, settings->pitch);
+}
+
+void PlaySound2d(const USoundBaseOpague* sound, const SoundSettings* settings)
+{
+	auto World = GetRustModule().GameMode->GetWorld();
+	UGameplayStatics::PlaySound2D(World, (USoundBase*)sound, settings->volume, settings->pitch);
+}
+
+USoundBaseOpague* LoadSound(Utf8Str path)
+{
+	USoundBase* Sound = LoadObject<USoundBase>(nullptr, *ToFString(path));
+	if (Sound != nullptr)
+	{
+		// Rust holds on to the raw pointer, which the garbage collector knows nothing about
+		Sound->AddToRoot();
+	}
+	return Sound;
 }
 
 void GetActorName(const AActorOpaque* actor, RustAlloc* data)
@@ -618,6 +1008,137 @@ void GetActorName(const AActorOpaque* actor, RustAlloc* data)
 	FMemory::Memcpy(data->ptr, Utf8.Get(), data->size);
 }
 
+void GetActorClassName(const AActorOpaque* actor, RustAlloc* data)
+{
+	FString Name = ToAActor(actor)->GetClass()->GetName();
+	auto Utf8 = FTCHARToUTF8(*Name);
+	GetRustModule().Plugin.Rust.allocate_fns.allocate(Utf8.Length(), 1, data);
+	FMemory::Memcpy(data->ptr, Utf8.Get(), data->size);
+}
+
+void GetActorTags(const AActorOpaque* actor, RustAlloc* data, uintptr_t* len)
+{
+	const TArray<FName>& Tags = ToAActor(actor)->Tags;
+	if (data == nullptr)
+	{
+		*len = Tags.Num();
+		return;
+	}
+	*len = FMath::Min(*len, (uintptr_t)Tags.Num());
+	for (uintptr_t i = 0; i < *len; ++i)
+	{
+		auto Utf8 = FTCHARToUTF8(*Tags[i].ToString());
+		GetRustModule().Plugin.Rust.allocate_fns.allocate(Utf8.Length(), 1, &data[i]);
+		FMemory::Memcpy(data[i].ptr, Utf8.Get(), data[i].size);
+	}
+}
+
+uint32_t GetActorNetGuid(const AActorOpaque* actor, uint64_t* out)
+{
+	AActor* Actor = ToAActor(actor);
+	UNetDriver* NetDriver = Actor->GetNetDriver();
+	if (NetDriver == nullptr || !NetDriver->GuidCache.IsValid())
+	{
+		return 0;
+	}
+	const FNetworkGUID Guid = NetDriver->GuidCache->GetNetGUID(Actor);
+	if (!Guid.IsValid())
+	{
+		return 0;
+	}
+	*out = (uint64_t)Guid.Value;
+	return 1;
+}
+
+NetRole GetActorNetRole(const AActorOpaque* actor)
+{
+	switch (ToAActor(actor)->GetLocalRole())
+	{
+	case ROLE_SimulatedProxy:
+		return NetRole::SimulatedProxy;
+	case ROLE_AutonomousProxy:
+		return NetRole::AutonomousProxy;
+	default:
+		return NetRole::Authority;
+	}
+}
+
+void GetActorBounds(const AActorOpaque* actor, Vector3* origin, Vector3* extent)
+{
+	FVector Origin, Extent;
+	ToAActor(actor)->GetActorBounds(true, Origin, Extent);
+	*origin = ToVector3(Origin);
+	*extent = ToVector3(Extent);
+}
+
+uint32_t GetSocketTransform(const AActorOpaque* actor, const char* name, uintptr_t len, SpatialData* out)
+{
+	FName SocketName((int32)len, name);
+	TInlineComponentArray<USceneComponent*> Components;
+	ToAActor(actor)->GetComponents(Components);
+	for (USceneComponent* Component : Components)
+	{
+		if (Component->DoesSocketExist(SocketName))
+		{
+			const FTransform Transform = Component->GetSocketTransform(SocketName, RTS_World);
+			out->position = ToVector3(Transform.GetTranslation());
+			out->rotation = ToQuaternion(Transform.GetRotation());
+			out->scale = ToVector3(Transform.GetScale3D());
+			return 1;
+		}
+	}
+	return 0;
+}
+
+void SetActorHidden(AActorOpaque* actor, uint32_t hidden)
+{
+	ToAActor(actor)->SetActorHiddenInGame(hidden != 0);
+}
+
+uint32_t IsActorHidden(const AActorOpaque* actor)
+{
+	return ToAActor(actor)->IsHidden();
+}
+
+void AttachToActor(AActorOpaque* child, AActorOpaque* parent, const char* socket, uintptr_t len)
+{
+	const FName SocketName = len == 0 ? NAME_None : FName((int32)len, socket);
+	ToAActor(child)->AttachToActor(ToAActor(parent), FAttachmentTransformRules::KeepWorldTransform, SocketName);
+}
+
+void DetachFromActor(AActorOpaque* actor)
+{
+	ToAActor(actor)->DetachFromActor(FDetachmentTransformRules::KeepWorldTransform);
+}
+
+void SetActorLocationTeleport(AActorOpaque* actor, Vector3 position, Quaternion rotation)
+{
+	ToAActor(actor)->SetActorLocationAndRotation(
+		ToFVector(position), ToFQuat(rotation), false, nullptr, ETeleportType::TeleportPhysics);
+}
+
+void SetMaterialScalar(AActorOpaque* actor, const char* name, uintptr_t len, float value)
+{
+	const FName ParameterName((int32)len, name);
+	TInlineComponentArray<UPrimitiveComponent*> Components;
+	ToAActor(actor)->GetComponents(Components);
+	for (UPrimitiveComponent* Component : Components)
+	{
+		Component->SetScalarParameterValueOnMaterials(ParameterName, value);
+	}
+}
+
+void SetMaterialVector(AActorOpaque* actor, const char* name, uintptr_t len, Vector3 value)
+{
+	const FName ParameterName((int32)len, name);
+	TInlineComponentArray<UPrimitiveComponent*> Components;
+	ToAActor(actor)->GetComponents(Components);
+	for (UPrimitiveComponent* Component : Components)
+	{
+		Component->SetVectorParameterValueOnMaterials(ParameterName, ToFVector(value));
+	}
+}
+
 void RegisterActorOnOverlap(AActorOpaque* actor)
 {
 	auto GameMode = GetRustModule().GameMode;
@@ -642,3 +1163,97 @@ void DestroyActor(const AActorOpaque* actor)
 	// TODO: What do we do if we can't destroy the actor?
 	ToAActor(actor)->Destroy();
 }
+
+void PollCollisionEvents(CollisionEvent* events, uintptr_t* len)
+{
+	auto GameMode = GetRustModule().GameMode;
+	if (GameMode == nullptr)
+	{
+		*len = 0;
+		return;
+	}
+	TArray<CollisionEvent>& Pending = GameMode->PendingCollisionEvents;
+	if (events == nullptr)
+	{
+		*len = Pending.Num();
+		return;
+	}
+	uintptr_t Count = FGenericPlatformMath::Min(*len, (uintptr_t)Pending.Num());
+	for (uintptr_t Idx = 0; Idx < Count; ++Idx)
+	{
+		events[Idx] = Pending[Idx];
+	}
+	Pending.RemoveAt(0, Count);
+	*len = Count;
+}
+
+void GetGamepadState(uint32_t index, GamepadState* state)
+{
+	*state = GamepadState{};
+	APlayerController* PC = UGameplayStatics::GetPlayerController(GetRustModule().GameMode, (int32)index);
+	if (PC == nullptr || PC->PlayerInput == nullptr)
+	{
+		return;
+	}
+	// Slate only knows whether any gamepad is attached, not which player it belongs to
+	if (!FSlateApplication::IsInitialized() || !FSlateApplication::Get().IsGamepadAttached())
+	{
+		return;
+	}
+	UPlayerInput* Input = PC->PlayerInput;
+	state->connected = 1;
+	state->left_x = Input->GetKeyValue(EKeys::Gamepad_LeftX);
+	state->left_y = Input->GetKeyValue(EKeys::Gamepad_LeftY);
+	state->right_x = Input->GetKeyValue(EKeys::Gamepad_RightX);
+	state->right_y = Input->GetKeyValue(EKeys::Gamepad_RightY);
+	state->left_trigger = Input->GetKeyValue(EKeys::Gamepad_LeftTriggerAxis);
+	state->right_trigger = Input->GetKeyValue(EKeys::Gamepad_RightTriggerAxis);
+
+	const TPair<FKey, GamepadButton> Buttons[] = {
+		{EKeys::Gamepad_FaceButton_Bottom, GamepadButton::FaceBottom},
+		{EKeys::Gamepad_FaceButton_Right, GamepadButton::FaceRight},
+		{EKeys::Gamepad_FaceButton_Left, GamepadButton::FaceLeft},
+		{EKeys::Gamepad_FaceButton_Top, GamepadButton::FaceTop},
+		{EKeys::Gamepad_LeftShoulder, GamepadButton::LeftShoulder},
+		{EKeys::Gamepad_RightShoulder, GamepadButton::RightShoulder},
+		{EKeys::Gamepad_LeftThumbstick, GamepadButton::LeftThumb},
+		{EKeys::Gamepad_RightThumbstick, GamepadButton::RightThumb},
+		{EKeys::Gamepad_Special_Right, GamepadButton::Start},
+		{EKeys::Gamepad_Special_Left, GamepadButton::Back},
+		{EKeys::Gamepad_DPad_Up, GamepadButton::DPadUp},
+		{EKeys::Gamepad_DPad_Down, GamepadButton::DPadDown},
+		{EKeys::Gamepad_DPad_Left, GamepadButton::DPadLeft},
+		{EKeys::Gamepad_DPad_Right, GamepadButton::DPadRight},
+	};
+	for (const auto& Button : Buttons)
+	{
+		if (Input->IsPressed(Button.Key))
+		{
+			state->buttons |= 1u << (uint32_t)Button.Value;
+		}
+	}
+}
+
+// Unreal draws debug shapes with a negative lifetime for a single frame
+static float ToDebugDrawLifeTime(float duration)
+{
+	return duration > 0.0f ? duration : -1.0f;
+}
+
+void DebugDrawLine(Vector3 start, Vector3 end, Color color, float thickness, float duration)
+{
+	DrawDebugLine(GetRustModule().GameMode->GetWorld(), ToFVector(start), ToFVector(end), ToFColor(color), false,
+	              ToDebugDrawLifeTime(duration), 0, thickness);
+}
+
+void DebugDrawSphere(Vector3 center, float radius, Color color, float duration)
+{
+	DrawDebugSphere(GetRustModule().GameMode->GetWorld(), ToFVector(center), radius, 16, ToFColor(color), false,
+	                ToDebugDrawLifeTime(duration));
+}
+
+void DebugDrawBox(Vector3 center, Vector3 extent, Quaternion rotation, Color color, float duration)
+{
+	DrawDebugBox(GetRustModule().GameMode->GetWorld(), ToFVector(center), ToFVector(extent), ToFQuat(rotation),
+	             ToFColor(color), false, ToDebugDrawLifeTime(duration));
+}
@@ -42,6 +42,8 @@ ARustGameModeBase::ARustGameModeBase()
 
 void ARustGameModeBase::OnActorSpawnedHandler(AActor* actor)
 {
+	// Runtime spawned actors need to be unregistered as well when they are destroyed
+	actor->OnDestroyed.AddUniqueDynamic(this, &ARustGameModeBase::OnActorDestroyed);
 	EventType Type = EventType::ActorSpawned;
 	ActorSpawnedEvent Event;
 	Event.actor = (AActorOpaque*)actor;
@@ -55,6 +57,12 @@ void ARustGameModeBase::OnActorBeginOverlap(AActor* OverlappedActor, AActor* Oth
 	Event.overlapped_actor = (AActorOpaque*)OverlappedActor;
 	Event.other = (AActorOpaque*)OtherActor;
 	GetRustModule().Plugin.Rust.unreal_event(&Type, (void*)&Event);
+
+	CollisionEvent Collision = {};
+	Collision.self_actor = (AActorOpaque*)OverlappedActor;
+	Collision.other = (AActorOpaque*)OtherActor;
+	Collision.phase = CollisionPhase::Begin;
+	PendingCollisionEvents.Add(Collision);
 }
 
 void ARustGameModeBase::OnActorEndOverlap(AActor* OverlappedActor, AActor* OtherActor)
@@ -64,6 +72,12 @@ void ARustGameModeBase::OnActorEndOverlap(AActor* OverlappedActor, AActor* Other
 	Event.overlapped_actor = (AActorOpaque*)OverlappedActor;
 	Event.other = (AActorOpaque*)OtherActor;
 	GetRustModule().Plugin.Rust.unreal_event(&Type, (void*)&Event);
+
+	CollisionEvent Collision = {};
+	Collision.self_actor = (AActorOpaque*)OverlappedActor;
+	Collision.other = (AActorOpaque*)OtherActor;
+	Collision.phase = CollisionPhase::End;
+	PendingCollisionEvents.Add(Collision);
 }
 
 void ARustGameModeBase::OnActorHit(AActor* SelfActor, AActor* OtherActor, FVector NormalImpulse, const FHitResult& Hit)
@@ -74,14 +88,24 @@ void ARustGameModeBase::OnActorHit(AActor* SelfActor, AActor* OtherActor, FVecto
 	Event.other = (AActorOpaque*)OtherActor;
 	Event.normal_impulse = ToVector3(NormalImpulse);
 	GetRustModule().Plugin.Rust.unreal_event(&Type, (void*)&Event);
+
+	FVector RelativeVelocity = SelfActor->GetVelocity();
+	if (OtherActor != nullptr)
+	{
+		RelativeVelocity -= OtherActor->GetVelocity();
+	}
+	CollisionEvent Collision = {};
+	Collision.self_actor = (AActorOpaque*)SelfActor;
+	Collision.other = (AActorOpaque*)OtherActor;
+	Collision.phase = CollisionPhase::Hit;
+	Collision.impact_velocity = ToVector3(RelativeVelocity);
+	Collision.impact_point = ToVector3(Hit.ImpactPoint);
+	PendingCollisionEvents.Add(Collision);
 }
 
 void ARustGameModeBase::OnActorDestroyed(AActor* Actor)
 {
-	EventType Type = EventType::ActorDestroy;
-	ActorDestroyEvent Event;
-	Event.actor = (AActorOpaque*)Actor;
-	GetRustModule().Plugin.Rust.unreal_event(&Type, (void*)&Event);
+	GetRustModule().Plugin.Rust.unregister_actor((AActorOpaque*)Actor);
 }
 
 void ARustGameModeBase::PostLogin(APlayerController* NewPlayer)
@@ -129,7 +153,16 @@ void ARustGameModeBase::StartPlay()
 	for (TActorIterator<AActor> ActorItr(GetWorld()); ActorItr; ++ActorItr)
 	{
 		AActor* Actor = *ActorItr;
-		Actor->OnDestroyed.AddUniqueDynamic(this, &ARustGameModeBase::OnActorDestroyed);
 		OnActorSpawnedHandler(Actor);
 	}
 }
+
+void ARustGameModeBase::EndPlay(const EEndPlayReason::Type EndPlayReason)
+{
+	FRustPluginModule& Module = GetRustModule();
+	if (Module.Plugin.IsLoaded() && Module.Plugin.Rust.end_play() == ResultCode::Panic)
+	{
+		Module.Exit();
+	}
+	Super::EndPlay(EndPlayReason);
+}
@@ -70,8 +70,17 @@ bool FPlugin::TryLoad()
 	                                          *FString::Printf(
 		                                          TEXT("%s-%i"), *PluginFileName(),
 		                                          FDateTime::Now().ToUnixTimestamp()));
+	// Components that the old module wants to keep across the reload, see `Module::preserve_state_on_reload`
+	TArray<uint8> ReloadState;
 	if (this->IsLoaded())
 	{
+		uintptr_t Len = 0;
+		if (Rust.prepare_reload(nullptr, &Len) == ResultCode::Success && Len > 0)
+		{
+			ReloadState.SetNumUninitialized(Len);
+			Rust.prepare_reload(ReloadState.GetData(), &Len);
+			ReloadState.SetNum(Len);
+		}
 		FPlatformProcess::FreeDllHandle(this->Handle);
 		this->Handle = nullptr;
 		// This is leaky. If we close the editor this will not delete the file
@@ -105,6 +114,10 @@ bool FPlugin::TryLoad()
 	this->TargetPath = LocalTargetPath;
 	NeedsInit = true;
 	CallEntryPoints();
+	if (ReloadState.Num() > 0)
+	{
+		Rust.finish_reload(ReloadState.GetData(), ReloadState.Num());
+	}
 	return true;
 }
 
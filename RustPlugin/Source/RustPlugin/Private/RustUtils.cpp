@@ -8,6 +8,8 @@ UnrealBindings CreateBindings()
 {
 	SoundFns sound_fns;
 	sound_fns.play_sound_at_location = PlaySoundAtLocation;
+	sound_fns.play_sound_2d = PlaySound2d;
+	sound_fns.load_sound = LoadSound;
 	
 	EditorComponentFns editor_component_fns;
 	editor_component_fns.get_editor_component_bool = &GetEditorComponentBool;
@@ -28,9 +30,19 @@ UnrealBindings CreateBindings()
 	 physics_fns.sweep = &Sweep;
 	 physics_fns.sweep_multi = &SweepMulti;
 	 physics_fns.get_collision_shape = &GetCollisionShape;
+	 physics_fns.raycast = &Raycast;
+	 physics_fns.sweep_by_channel = &SweepByChannel;
+	 physics_fns.get_angular_velocity = &GetAngularVelocity;
+	 physics_fns.set_angular_velocity = &SetAngularVelocity;
+	 physics_fns.add_torque = &AddTorque;
+	 physics_fns.add_angular_impulse = &AddAngularImpulse;
+	 physics_fns.get_collision_response = &GetCollisionResponse;
+	 physics_fns.set_collision_response = &SetCollisionResponse;
+	 physics_fns.set_simulate_physics = &SetSimulatePhysics;
 
 	ActorFns actor_fns = {};
 	actor_fns.get_spatial_data = &GetSpatialData;
+	actor_fns.get_spatial_data_bulk = &GetSpatialDataBulk;
 	actor_fns.set_spatial_data = &SetSpatialData;
 	actor_fns.set_entity_for_actor = &SetEntityForActor;
 	actor_fns.set_view_target = &SetViewTarget;
@@ -44,6 +56,20 @@ UnrealBindings CreateBindings()
 	actor_fns.register_actor_on_hit = &RegisterActorOnHit;
 	actor_fns.get_root_component = &GetRootComponent;
 	actor_fns.destroy_actor = &DestroyActor;
+	actor_fns.poll_collision_events = &PollCollisionEvents;
+	actor_fns.get_actor_class_name = &GetActorClassName;
+	actor_fns.get_actor_tags = &GetActorTags;
+	actor_fns.get_actor_net_guid = &GetActorNetGuid;
+	actor_fns.get_actor_net_role = &GetActorNetRole;
+	actor_fns.get_actor_bounds = &GetActorBounds;
+	actor_fns.get_socket_transform = &GetSocketTransform;
+	actor_fns.set_actor_hidden = &SetActorHidden;
+	actor_fns.is_actor_hidden = &IsActorHidden;
+	actor_fns.attach_to_actor = &AttachToActor;
+	actor_fns.detach_from_actor = &DetachFromActor;
+	actor_fns.set_actor_location_teleport = &SetActorLocationTeleport;
+	actor_fns.set_material_scalar = &SetMaterialScalar;
+	actor_fns.set_material_vector = &SetMaterialVector;
 	
 	UnrealBindings b = {};
 	b.actor_fns = actor_fns;
@@ -55,7 +81,25 @@ UnrealBindings CreateBindings()
 	b.get_action_state = &GetActionState;
 	b.get_axis_value = &GetAxisValue;
 	b.spawn_actor = &SpawnActor;
+	b.spawn_actor_from_class = &SpawnActorFromClass;
+	b.load_class = &LoadClass;
+	b.load_curve_keys = &LoadCurveKeys;
+	b.register_name = &RegisterName;
+	b.on_component_changed = &OnComponentChanged;
+	b.on_components_changed_bulk = &OnComponentsChangedBulk;
 	b.get_mouse_delta = &GetMouseDelta;
+	b.get_mouse_state = &GetMouseState;
+	b.set_mouse_captured = &SetMouseCaptured;
+	b.project_world_to_screen = &ProjectWorldToScreen;
+	b.deproject_screen_to_world = &DeprojectScreenToWorld;
+	b.get_viewport_size = &GetViewportSize;
+	b.get_config_blob = &GetConfigBlob;
+	b.get_time_dilation = &GetTimeDilation;
+	b.get_gamepad_state = &GetGamepadState;
+	b.set_camera_projection = &SetCameraProjection;
+	b.debug_draw_line = &DebugDrawLine;
+	b.debug_draw_sphere = &DebugDrawSphere;
+	b.debug_draw_box = &DebugDrawBox;
 	b.visual_log_segment = &VisualLogSegment;
 	b.visual_log_capsule = &VisualLogCapsule;
 	b.visual_log_location = &VisualLogLocation;
@@ -72,6 +116,14 @@ Quaternion ToQuaternion(FQuat q)
 	return r;
 }
 
+Vector2 ToVector2(FVector2D v)
+{
+	Vector2 r;
+	r.x = v.X;
+	r.y = v.Y;
+	return r;
+}
+
 Vector3 ToVector3(FVector v)
 {
 	Vector3 r;
@@ -91,6 +143,20 @@ FQuat ToFQuat(Quaternion q)
 	return FQuat(q.x, q.y, q.z, q.w);
 }
 
+Rotator ToRotator(FRotator r)
+{
+	Rotator Out;
+	Out.pitch = r.Pitch;
+	Out.yaw = r.Yaw;
+	Out.roll = r.Roll;
+	return Out;
+}
+
+FRotator ToFRotator(Rotator r)
+{
+	return FRotator(r.pitch, r.yaw, r.roll);
+}
+
 AActor* ToAActor(const AActorOpaque* actor)
 {
 	return (AActor*)actor;
@@ -153,6 +219,63 @@ FCollisionShape ToFCollisionShape(CollisionShape Shape)
 	abort();
 }
 
+ECollisionChannel ToECollisionChannel(CollisionChannel Channel)
+{
+	switch (Channel)
+	{
+	case CollisionChannel::WorldStatic:
+		return ECollisionChannel::ECC_WorldStatic;
+	case CollisionChannel::WorldDynamic:
+		return ECollisionChannel::ECC_WorldDynamic;
+	case CollisionChannel::Pawn:
+		return ECollisionChannel::ECC_Pawn;
+	case CollisionChannel::Visibility:
+		return ECollisionChannel::ECC_Visibility;
+	case CollisionChannel::Camera:
+		return ECollisionChannel::ECC_Camera;
+	case CollisionChannel::PhysicsBody:
+		return ECollisionChannel::ECC_PhysicsBody;
+	case CollisionChannel::Vehicle:
+		return ECollisionChannel::ECC_Vehicle;
+	case CollisionChannel::Destructible:
+		return ECollisionChannel::ECC_Destructible;
+	}
+	return ECollisionChannel::ECC_Visibility;
+}
+
+bool ToCollisionChannel(ECollisionChannel Channel, CollisionChannel* Out)
+{
+	switch (Channel)
+	{
+	case ECollisionChannel::ECC_WorldStatic:
+		*Out = CollisionChannel::WorldStatic;
+		return true;
+	case ECollisionChannel::ECC_WorldDynamic:
+		*Out = CollisionChannel::WorldDynamic;
+		return true;
+	case ECollisionChannel::ECC_Pawn:
+		*Out = CollisionChannel::Pawn;
+		return true;
+	case ECollisionChannel::ECC_Visibility:
+		*Out = CollisionChannel::Visibility;
+		return true;
+	case ECollisionChannel::ECC_Camera:
+		*Out = CollisionChannel::Camera;
+		return true;
+	case ECollisionChannel::ECC_PhysicsBody:
+		*Out = CollisionChannel::PhysicsBody;
+		return true;
+	case ECollisionChannel::ECC_Vehicle:
+		*Out = CollisionChannel::Vehicle;
+		return true;
+	case ECollisionChannel::ECC_Destructible:
+		*Out = CollisionChannel::Destructible;
+		return true;
+	default:
+		return false;
+	}
+}
+
 FString ToFString(Utf8Str Str)
 {
 	if(Str.len == 0)
@@ -21,6 +21,25 @@ enum class ActorComponentType : uint32_t {
   Primitive,
 };
 
+/// Mirrors the common `ECollisionChannel`s in unreal
+enum class CollisionChannel : uint32_t {
+  WorldStatic = 0,
+  WorldDynamic = 1,
+  Pawn = 2,
+  Visibility = 3,
+  Camera = 4,
+  PhysicsBody = 5,
+  Vehicle = 6,
+  Destructible = 7,
+};
+
+enum class CollisionPhase : uint32_t {
+  Begin = 0,
+  End = 1,
+  /// A blocking hit, the only phase with impact data
+  Hit = 2,
+};
+
 enum class CollisionShapeType : uint32_t {
   Box,
   Capsule,
@@ -35,6 +54,52 @@ enum class EventType : uint32_t {
   ActorDestroy = 4,
 };
 
+enum class GamepadAxis : uint32_t {
+  LeftX = 0,
+  LeftY = 1,
+  RightX = 2,
+  RightY = 3,
+  LeftTrigger = 4,
+  RightTrigger = 5,
+};
+
+/// The value of each variant is the bit index inside `GamepadState::buttons`
+enum class GamepadButton : uint32_t {
+  FaceBottom = 0,
+  FaceRight = 1,
+  FaceLeft = 2,
+  FaceTop = 3,
+  LeftShoulder = 4,
+  RightShoulder = 5,
+  LeftThumb = 6,
+  RightThumb = 7,
+  Start = 8,
+  Back = 9,
+  DPadUp = 10,
+  DPadDown = 11,
+  DPadLeft = 12,
+  DPadRight = 13,
+};
+
+/// Maps to the unreal log verbosity of the same name
+enum class LogLevel : uint8_t {
+  Display,
+  Warning,
+  Error,
+};
+
+/// The local role of a replicated actor. Actors that are not replicated are `Authority`.
+enum class NetRole : uint32_t {
+  Authority = 0,
+  SimulatedProxy = 1,
+  AutonomousProxy = 2,
+};
+
+enum class ProjectionMode : uint32_t {
+  Perspective = 0,
+  Orthographic = 1,
+};
+
 enum class ReflectionType : uint32_t {
   Float,
   Vector3,
@@ -43,11 +108,20 @@ enum class ReflectionType : uint32_t {
   UClass,
   USound,
   Composite,
+  Entity,
 };
 
 enum class ResultCode : uint8_t {
   Success = 0,
   Panic = 1,
+  /// A pointer was null or a value was out of range
+  InvalidArgument = 2,
+  /// Called before the module was loaded
+  NotInitialized = 3,
+  /// The actor has no entity
+  ActorNotFound = 4,
+  /// No component is registered with that UUID
+  NotFound = 5,
 };
 
 enum class UObjectType : uint32_t {
@@ -69,18 +143,19 @@ struct Quaternion {
   float w;
 };
 
-struct Color {
-  uint8_t r;
-  uint8_t g;
-  uint8_t b;
-  uint8_t a;
-};
+using UClassOpague = void;
 
 struct Utf8Str {
   const char *ptr;
   uintptr_t len;
 };
 
+/// A key of a float curve asset
+struct CurveKey {
+  float time;
+  float value;
+};
+
 struct Uuid {
   uint32_t a;
   uint32_t b;
@@ -88,8 +163,65 @@ struct Uuid {
   uint32_t d;
 };
 
+/// One change that is reported by `UnrealBindings::on_components_changed_bulk`
+struct ComponentChange {
+  const AActorOpaque *actor;
+  Uuid uuid;
+};
+
+struct Vector2 {
+  float x;
+  float y;
+};
+
+/// `position` is the cursor position in viewport pixels, and is only valid if `has_position` is
+/// 1. `scroll` is the mouse wheel movement of the current frame.
+struct MouseState {
+  uint32_t has_position;
+  Vector2 position;
+  float scroll;
+};
+
+struct RustAlloc {
+  uint8_t *ptr;
+  uintptr_t size;
+  uintptr_t align;
+};
+
+struct GamepadState {
+  uint32_t connected;
+  float left_x;
+  float left_y;
+  float right_x;
+  float right_y;
+  float left_trigger;
+  float right_trigger;
+  uint32_t buttons;
+};
+
+struct CameraProjection {
+  ProjectionMode mode;
+  /// Horizontal field of view in degrees, only used for `ProjectionMode::Perspective`
+  float fov;
+  /// Width of the view in world units, only used for `ProjectionMode::Orthographic`
+  float ortho_width;
+};
+
+struct Color {
+  uint8_t r;
+  uint8_t g;
+  uint8_t b;
+  uint8_t a;
+};
+
 using UObjectOpague = void;
 
+struct SpatialData {
+  Vector3 position;
+  Quaternion rotation;
+  Vector3 scale;
+};
+
 struct Entity {
   uint64_t id;
 };
@@ -99,12 +231,14 @@ struct ActorComponentPtr {
   void *ptr;
 };
 
-using UClassOpague = void;
-
-struct RustAlloc {
-  uint8_t *ptr;
-  uintptr_t size;
-  uintptr_t align;
+struct CollisionEvent {
+  AActorOpaque *self_actor;
+  AActorOpaque *other;
+  CollisionPhase phase;
+  /// Velocity of `self_actor` relative to `other` at the time of the hit. Zero for overlaps.
+  Vector3 impact_velocity;
+  /// Zero for overlaps
+  Vector3 impact_point;
 };
 
 using UPrimtiveOpaque = void;
@@ -124,6 +258,14 @@ struct HitResult {
   Vector3 impact_location;
   float pentration_depth;
   uint32_t start_penetrating;
+  /// Time of impact along a sweep in `[0, 1]`
+  float time;
+};
+
+struct TraceParams {
+  AActorOpaque *const *ignored_actors;
+  uintptr_t ignored_actors_len;
+  CollisionChannel channel;
 };
 
 struct CollisionBox {
@@ -157,6 +299,15 @@ struct OverlapResult {
   UPrimtiveOpaque *primtive;
 };
 
+/// `channels` has a bit set for every `CollisionChannel` that the primitive blocks and
+/// `overlap_channels` for every channel that it overlaps. Blocking wins if a channel is in both,
+/// channels in neither are ignored.
+struct CollisionResponse {
+  CollisionChannel object_type;
+  uint32_t channels;
+  uint32_t overlap_channels;
+};
+
 using USoundBaseOpague = void;
 
 struct SoundSettings {
@@ -192,8 +343,56 @@ using IsMoveableFn = uint32_t(*)(const AActorOpaque *actor);
 
 using DestroyActorFn = void(*)(const AActorOpaque *actor);
 
+/// If `events` is null, `len` will be set to the number of pending events. Otherwise up to `len`
+/// events are written and removed from the queue, and `len` is set to the number of written events.
+using PollCollisionEventsFn = void(*)(CollisionEvent *events, uintptr_t *len);
+
+/// Writes the name of the actor's `UClass`, e.g. `BP_Enemy_C` for blueprints
+using GetActorClassNameFn = void(*)(const AActorOpaque *actor, RustAlloc *data);
+
+/// Like `RetrieveUuids`, the first call with a null `data` writes the number of tags into `len`.
+/// The second call allocates a string for each of the first `len` tags.
+using GetActorTagsFn = void(*)(const AActorOpaque *actor, RustAlloc *data, uintptr_t *len);
+
+/// Returns 0 if the actor has no network GUID, e.g. because it is not replicated
+using GetActorNetGuidFn = uint32_t(*)(const AActorOpaque *actor, uint64_t *out);
+
+using GetActorNetRoleFn = NetRole(*)(const AActorOpaque *actor);
+
+/// The world space bounding box of all colliding components of the actor
+using GetActorBoundsFn = void(*)(const AActorOpaque *actor, Vector3 *origin, Vector3 *extent);
+
+/// Writes the world transform of the socket or bone `name` into `out`. Returns 1 if any scene
+/// component of the actor has that socket, 0 otherwise.
+using GetSocketTransformFn = uint32_t(*)(const AActorOpaque *actor, const char *name, uintptr_t len, SpatialData *out);
+
+using SetActorHiddenFn = void(*)(AActorOpaque *actor, uint32_t hidden);
+
+using IsActorHiddenFn = uint32_t(*)(const AActorOpaque *actor);
+
+/// Attaches the root component of `child` to the root component of `parent`, at the socket `name`
+/// if `len` is not 0. The world transform of `child` is kept.
+using AttachToActorFn = void(*)(AActorOpaque *child, AActorOpaque *parent, const char *socket, uintptr_t len);
+
+/// Does nothing if `actor` is not attached, the world transform is kept
+using DetachFromActorFn = void(*)(AActorOpaque *actor);
+
+/// Moves the actor without sweeping, and physics treats the move as a teleport instead of
+/// deriving a velocity from it
+using SetActorLocationTeleportFn = void(*)(AActorOpaque *actor, Vector3 position, Quaternion rotation);
+
+/// Sets the parameter `name` on the materials of all primitive components of the actor. Unreal
+/// creates dynamic material instances for them on first use.
+using SetMaterialScalarFn = void(*)(AActorOpaque *actor, const char *name, uintptr_t len, float value);
+
+/// Same as `SetMaterialScalarFn` for vector parameters, the alpha is always 1
+using SetMaterialVectorFn = void(*)(AActorOpaque *actor, const char *name, uintptr_t len, Vector3 value);
+
 struct ActorFns {
   GetSpatialDataFn get_spatial_data;
+  /// Writes the transform of `actors[i]` into `out[i]` for all `len` actors, which saves a call
+  /// per actor compared to `get_spatial_data`. Null if the engine plugin predates the bulk api.
+  void (*get_spatial_data_bulk)(const AActorOpaque *const *actors, SpatialData *out, uintptr_t len);
   SetSpatialDataFn set_spatial_data;
   SetEntityForActorFn set_entity_for_actor;
   GetActorComponentsFn get_actor_components;
@@ -207,6 +406,20 @@ struct ActorFns {
   SetOwnerFn set_owner;
   IsMoveableFn is_moveable;
   DestroyActorFn destroy_actor;
+  PollCollisionEventsFn poll_collision_events;
+  GetActorClassNameFn get_actor_class_name;
+  GetActorTagsFn get_actor_tags;
+  GetActorNetGuidFn get_actor_net_guid;
+  GetActorNetRoleFn get_actor_net_role;
+  GetActorBoundsFn get_actor_bounds;
+  GetSocketTransformFn get_socket_transform;
+  SetActorHiddenFn set_actor_hidden;
+  IsActorHiddenFn is_actor_hidden;
+  AttachToActorFn attach_to_actor;
+  DetachFromActorFn detach_from_actor;
+  SetActorLocationTeleportFn set_actor_location_teleport;
+  SetMaterialScalarFn set_material_scalar;
+  SetMaterialVectorFn set_material_vector;
 };
 
 using GetVelocityFn = Vector3(*)(const UPrimtiveOpaque *primitive);
@@ -227,10 +440,31 @@ using SweepFn = uint32_t(*)(Vector3 start, Vector3 end, Quaternion rotation, Lin
 
 using SweepMultiFn = uint32_t(*)(Vector3 start, Vector3 end, Quaternion rotation, LineTraceParams params, CollisionShape collision_shape, uintptr_t max_results, HitResult *results);
 
-using OverlapMultiFn = uint32_t(*)(CollisionShape collision_shape, Vector3 position, Quaternion rotation, LineTraceParams params, uintptr_t max_results, OverlapResult **result);
+/// Writes up to `max_results` overlaps with `params.channel` into `results` and returns how many
+/// were written. An actor shows up once for every overlapping primitive.
+using OverlapMultiFn = uintptr_t(*)(CollisionShape collision_shape, Vector3 position, Quaternion rotation, TraceParams params, uintptr_t max_results, OverlapResult *results);
 
 using GetCollisionShapeFn = uint32_t(*)(const UPrimtiveOpaque *primitive, CollisionShape *shape);
 
+using RaycastFn = uint32_t(*)(Vector3 start, Vector3 end, TraceParams params, HitResult *result);
+
+using SweepByChannelFn = uint32_t(*)(Vector3 start, Vector3 end, Quaternion rotation, TraceParams params, CollisionShape collision_shape, HitResult *result);
+
+/// Angular velocities are in radians per second
+using GetAngularVelocityFn = Vector3(*)(const UPrimtiveOpaque *primitive);
+
+using SetAngularVelocityFn = void(*)(UPrimtiveOpaque *primitive, Vector3 angular_velocity);
+
+using AddTorqueFn = void(*)(UPrimtiveOpaque *primitive, Vector3 torque);
+
+using AddAngularImpulseFn = void(*)(UPrimtiveOpaque *primitive, Vector3 impulse);
+
+using GetCollisionResponseFn = void(*)(const UPrimtiveOpaque *primitive, CollisionResponse *response);
+
+using SetCollisionResponseFn = void(*)(UPrimtiveOpaque *primitive, CollisionResponse response);
+
+using SetSimulatePhysicsFn = void(*)(UPrimtiveOpaque *primitive, uint32_t simulate);
+
 struct PhysicsFns {
   GetVelocityFn get_velocity;
   SetVelocityFn set_velocity;
@@ -243,20 +477,86 @@ struct PhysicsFns {
   SweepMultiFn sweep_multi;
   OverlapMultiFn overlap_multi;
   GetCollisionShapeFn get_collision_shape;
+  RaycastFn raycast;
+  SweepByChannelFn sweep_by_channel;
+  GetAngularVelocityFn get_angular_velocity;
+  SetAngularVelocityFn set_angular_velocity;
+  AddTorqueFn add_torque;
+  AddAngularImpulseFn add_angular_impulse;
+  GetCollisionResponseFn get_collision_response;
+  SetCollisionResponseFn set_collision_response;
+  SetSimulatePhysicsFn set_simulate_physics;
 };
 
-using LogFn = void(*)(const char*, int32_t);
+/// `s` is UTF-8 with `len` bytes and is not null terminated
+using LogFn = void(*)(LogLevel level, const char *s, int32_t len);
 
 using IterateActorsFn = void(*)(AActorOpaque **array, uint64_t *len);
 
-using GetActionStateFn = void(*)(const char *name, uintptr_t len, ActionState state, uint32_t *out);
+/// `player_index` is the index of the local player, 0 for single player games
+using GetActionStateFn = void(*)(uint32_t player_index, const char *name, uintptr_t len, ActionState state, uint32_t *out);
 
-using GetAxisValueFn = void(*)(const char *name, uintptr_t len, float *value);
+using GetAxisValueFn = void(*)(uint32_t player_index, const char *name, uintptr_t len, float *value);
 
 using SpawnActorFn = AActorOpaque*(*)(ActorClass actor_class, Vector3 position, Quaternion rotation, Vector3 scale);
 
+/// Spawns an actor of any class, for example a blueprint class returned by `LoadClassFn`
+using SpawnActorFromClassFn = AActorOpaque*(*)(const UClassOpague *class, Vector3 position, Quaternion rotation, Vector3 scale);
+
+/// Loads the class at `path`, e.g. `/Game/BP_Enemy.BP_Enemy_C`. Returns null if there is no
+/// actor class at `path`. Loaded classes are never garbage collected.
+using LoadClassFn = UClassOpague*(*)(Utf8Str path);
+
+/// Copies the keys of the `UCurveFloat` at `path` into `keys`, sorted by time. Call it with a null
+/// `keys` to get the number of keys in `len`. Returns 0 if there is no float curve at `path`.
+using LoadCurveKeysFn = uint32_t(*)(Utf8Str path, CurveKey *keys, uintptr_t *len);
+
+/// Adds `name` to the name table of unreal if necessary and returns its id. The same string
+/// always returns the same id.
+using RegisterNameFn = uint32_t(*)(const char *name, uintptr_t len);
+
+/// Tells unreal that the component `uuid` of the entity of `actor` changed this frame
+using OnComponentChangedFn = void(*)(const AActorOpaque *actor, Uuid uuid);
+
 using GetMouseDeltaFn = void(*)(float *x, float *y);
 
+using GetMouseStateFn = void(*)(MouseState *state);
+
+/// Hides the cursor and locks it to the viewport while `captured` is 1
+using SetMouseCapturedFn = void(*)(uint32_t captured);
+
+/// Uses the camera of the first local player. Returns 0 if `world` is behind the camera.
+using ProjectWorldToScreenFn = uint32_t(*)(Vector3 world, Vector2 *screen);
+
+/// `direction` is normalized. Returns 0 if there is no local player.
+using DeprojectScreenToWorldFn = uint32_t(*)(Vector2 screen, Vector3 *origin, Vector3 *direction);
+
+/// The size of the game viewport in pixels. Writes 0 for the size if there is no viewport or it is
+/// minimized, `dpi_scale` is 1 in that case.
+using GetViewportSizeFn = void(*)(uint32_t *width, uint32_t *height, float *dpi_scale);
+
+/// Allocates the contents of the game config into `data`. Leaves `data` empty if there is no
+/// config.
+using GetConfigBlobFn = void(*)(RustAlloc *data);
+
+/// The effective time dilation of the world, 1 is normal speed. The `dt` that is passed to
+/// `TickFn` is already scaled by it.
+using GetTimeDilationFn = float(*)();
+
+/// Fills `state` for the gamepad of the local player at `index`. `connected` is set to 0 if there
+/// is no such player or if no gamepad is attached at all.
+using GetGamepadStateFn = void(*)(uint32_t index, GamepadState *state);
+
+/// Only has an effect on camera actors
+using SetCameraProjectionFn = void(*)(AActorOpaque *actor, CameraProjection projection);
+
+/// A `duration` <= 0 only draws for a single frame
+using DebugDrawLineFn = void(*)(Vector3 start, Vector3 end, Color color, float thickness, float duration);
+
+using DebugDrawSphereFn = void(*)(Vector3 center, float radius, Color color, float duration);
+
+using DebugDrawBoxFn = void(*)(Vector3 center, Vector3 extent, Quaternion rotation, Color color, float duration);
+
 using VisualLogSegmentFn = void(*)(const AActorOpaque *owner, Vector3 start, Vector3 end, Color color);
 
 using VisualLogCapsuleFn = void(*)(Utf8Str category, const AActorOpaque *owner, Vector3 position, Quaternion rotation, float half_height, float radius, Color color);
@@ -286,8 +586,15 @@ struct EditorComponentFns {
 
 using PlaySoundAtLocationFn = void(*)(const USoundBaseOpague *sound, Vector3 location, Quaternion rotation, const SoundSettings *settings);
 
+using PlaySound2dFn = void(*)(const USoundBaseOpague *sound, const SoundSettings *settings);
+
+/// Returns null if there is no sound asset at `path`. Loaded sounds are never garbage collected.
+using LoadSoundFn = USoundBaseOpague*(*)(Utf8Str path);
+
 struct SoundFns {
   PlaySoundAtLocationFn play_sound_at_location;
+  PlaySound2dFn play_sound_2d;
+  LoadSoundFn load_sound;
 };
 
 struct UnrealBindings {
@@ -298,7 +605,27 @@ struct UnrealBindings {
   GetActionStateFn get_action_state;
   GetAxisValueFn get_axis_value;
   SpawnActorFn spawn_actor;
+  SpawnActorFromClassFn spawn_actor_from_class;
+  LoadClassFn load_class;
+  LoadCurveKeysFn load_curve_keys;
+  RegisterNameFn register_name;
+  OnComponentChangedFn on_component_changed;
+  /// Same as `on_component_changed` for all `len` changes of a frame at once. Null if the
+  /// engine plugin predates the bulk api.
+  void (*on_components_changed_bulk)(const ComponentChange *changes, uintptr_t len);
   GetMouseDeltaFn get_mouse_delta;
+  GetMouseStateFn get_mouse_state;
+  SetMouseCapturedFn set_mouse_captured;
+  ProjectWorldToScreenFn project_world_to_screen;
+  DeprojectScreenToWorldFn deproject_screen_to_world;
+  GetViewportSizeFn get_viewport_size;
+  GetConfigBlobFn get_config_blob;
+  GetTimeDilationFn get_time_dilation;
+  GetGamepadStateFn get_gamepad_state;
+  SetCameraProjectionFn set_camera_projection;
+  DebugDrawLineFn debug_draw_line;
+  DebugDrawSphereFn debug_draw_sphere;
+  DebugDrawBoxFn debug_draw_box;
   VisualLogSegmentFn visual_log_segment;
   VisualLogCapsuleFn visual_log_capsule;
   VisualLogLocationFn visual_log_location;
@@ -308,12 +635,47 @@ struct UnrealBindings {
 
 using RetrieveUuids = void(*)(Uuid *ptr, uintptr_t *len);
 
+struct ComponentField {
+  Utf8Str name;
+  ReflectionType ty;
+};
+
+struct ComponentInfo {
+  Utf8Str name;
+  ComponentField *fields;
+  uintptr_t len;
+};
+
+/// Called twice, first with `info.fields` set to null, which only writes the name and the number
+/// of fields into `info`. The second call fills `info.fields` with up to `info.len` fields.
+/// Returns 0 if `uuid` is not a registered component.
+using RetrieveComponentInfo = uint32_t(*)(Uuid uuid, ComponentInfo *info);
+
+/// Called before a hot reload. The first call with a null `data` writes the size of the state
+/// into `len`, the second call copies up to `len` bytes into `data`.
+using PrepareReloadFn = ResultCode(*)(uint8_t *data, uintptr_t *len);
+
+/// Called on the newly loaded module with the state from `PrepareReloadFn`
+using FinishReloadFn = ResultCode(*)(const uint8_t *data, uintptr_t len);
+
 using TickFn = ResultCode(*)(float dt);
 
 using BeginPlayFn = ResultCode(*)();
 
+using EndPlayFn = ResultCode(*)();
+
 using UnrealEventFn = void(*)(const EventType *ty, const void *data);
 
+using UnregisterActorFn = ResultCode(*)(AActorOpaque *actor);
+
+/// Removes the component `uuid` from the entity of `actor`. Succeeds if the entity does not have
+/// the component.
+using RemoveComponentFn = ResultCode(*)(AActorOpaque *actor, Uuid uuid);
+
+/// Inserts the default value of the component `uuid` into the entity of `actor`, replacing the
+/// existing one. Returns `InvalidArgument` if the component was registered without a default.
+using AddComponentFn = ResultCode(*)(AActorOpaque *actor, Uuid uuid);
+
 using IsEditorComponentFn = uint32_t(*)(Uuid uuid);
 
 using NumberOfFieldsFn = uint32_t(*)(Uuid uuid, uint32_t *out);
@@ -355,9 +717,16 @@ struct AllocateFns {
 
 struct RustBindings {
   RetrieveUuids retrieve_uuids;
+  RetrieveComponentInfo retrieve_component_info;
+  PrepareReloadFn prepare_reload;
+  FinishReloadFn finish_reload;
   TickFn tick;
   BeginPlayFn begin_play;
+  EndPlayFn end_play;
   UnrealEventFn unreal_event;
+  UnregisterActorFn unregister_actor;
+  RemoveComponentFn remove_component;
+  AddComponentFn add_component;
   ReflectionFns reflection_fns;
   AllocateFns allocate_fns;
 };
@@ -388,25 +757,79 @@ struct ActorDestroyEvent {
   AActorOpaque *actor;
 };
 
+/// Mirrors `FRotator`, in degrees. Uses the same convention as `unreal_api::math::QuatExt`.
+struct Rotator {
+  float pitch;
+  float yaw;
+  float roll;
+};
+
 extern "C" {
 
 extern void TickActor(AActorOpaque *actor, float dt);
 
-extern void Log(const char *s, int32_t len);
+extern void Log(LogLevel level, const char *s, int32_t len);
 
 extern void IterateActors(AActorOpaque **array, uint64_t *len);
 
-extern void GetActionState(const char *name, uintptr_t len, ActionState state, uint32_t *out);
+extern void GetActionState(uint32_t player_index,
+                           const char *name,
+                           uintptr_t len,
+                           ActionState state,
+                           uint32_t *out);
 
-extern void GetAxisValue(const char *name, uintptr_t len, float *value);
+extern void GetAxisValue(uint32_t player_index, const char *name, uintptr_t len, float *value);
 
 extern AActorOpaque *SpawnActor(ActorClass actor_class,
                                 Vector3 position,
                                 Quaternion rotation,
                                 Vector3 scale);
 
+extern AActorOpaque *SpawnActorFromClass(const UClassOpague *class_,
+                                         Vector3 position,
+                                         Quaternion rotation,
+                                         Vector3 scale);
+
+extern UClassOpague *LoadClass(Utf8Str path);
+
+extern uint32_t LoadCurveKeys(Utf8Str path, CurveKey *keys, uintptr_t *len);
+
+extern uint32_t RegisterName(const char *name, uintptr_t len);
+
+extern void OnComponentChanged(const AActorOpaque *actor, Uuid uuid);
+
+extern void OnComponentsChangedBulk(const ComponentChange *changes, uintptr_t len);
+
 extern void GetMouseDelta(float *x, float *y);
 
+extern void GetMouseState(MouseState *state);
+
+extern void SetMouseCaptured(uint32_t captured);
+
+extern uint32_t ProjectWorldToScreen(Vector3 world, Vector2 *screen);
+
+extern void GetViewportSize(uint32_t *width, uint32_t *height, float *dpi_scale);
+
+extern void GetConfigBlob(RustAlloc *data);
+
+extern float GetTimeDilation();
+
+extern uint32_t DeprojectScreenToWorld(Vector2 screen, Vector3 *origin, Vector3 *direction);
+
+extern void GetGamepadState(uint32_t index, GamepadState *state);
+
+extern void SetCameraProjection(AActorOpaque *actor, CameraProjection projection);
+
+extern void DebugDrawLine(Vector3 start, Vector3 end, Color color, float thickness, float duration);
+
+extern void DebugDrawSphere(Vector3 center, float radius, Color color, float duration);
+
+extern void DebugDrawBox(Vector3 center,
+                         Vector3 extent,
+                         Quaternion rotation,
+                         Color color,
+                         float duration);
+
 extern void VisualLogSegment(const AActorOpaque *owner, Vector3 start, Vector3 end, Color color);
 
 extern void VisualLogCapsule(Utf8Str category,
@@ -467,6 +890,8 @@ extern void GetSpatialData(const AActorOpaque *actor,
                            Quaternion *rotation,
                            Vector3 *scale);
 
+extern void GetSpatialDataBulk(const AActorOpaque *const *actors, SpatialData *out, uintptr_t len);
+
 extern void SetEntityForActor(AActorOpaque *name, Entity entity);
 
 extern void GetActorComponents(const AActorOpaque *actor, ActorComponentPtr *data, uintptr_t *len);
@@ -481,22 +906,68 @@ extern uint32_t IsMoveable(const AActorOpaque *actor);
 
 extern void GetActorName(const AActorOpaque *actor, RustAlloc *data);
 
+extern void GetActorClassName(const AActorOpaque *actor, RustAlloc *data);
+
+extern void GetActorTags(const AActorOpaque *actor, RustAlloc *data, uintptr_t *len);
+
+extern uint32_t GetActorNetGuid(const AActorOpaque *actor, uint64_t *out);
+
+extern NetRole GetActorNetRole(const AActorOpaque *actor);
+
+extern void GetActorBounds(const AActorOpaque *actor, Vector3 *origin, Vector3 *extent);
+
+extern uint32_t GetSocketTransform(const AActorOpaque *actor,
+                                   const char *name,
+                                   uintptr_t len,
+                                   SpatialData *out);
+
 extern void DestroyActor(const AActorOpaque *actor);
 
+extern void SetActorHidden(AActorOpaque *actor, uint32_t hidden);
+
+extern uint32_t IsActorHidden(const AActorOpaque *actor);
+
+extern void AttachToActor(AActorOpaque *child,
+                          AActorOpaque *parent,
+                          const char *socket,
+                          uintptr_t len);
+
+extern void DetachFromActor(AActorOpaque *actor);
+
+extern void SetActorLocationTeleport(AActorOpaque *actor, Vector3 position, Quaternion rotation);
+
+extern void SetMaterialScalar(AActorOpaque *actor, const char *name, uintptr_t len, float value);
+
+extern void SetMaterialVector(AActorOpaque *actor, const char *name, uintptr_t len, Vector3 value);
+
 extern void SetViewTarget(const AActorOpaque *actor);
 
+extern void PollCollisionEvents(CollisionEvent *events, uintptr_t *len);
+
 extern Vector3 GetVelocity(const UPrimtiveOpaque *primitive);
 
 extern void SetVelocity(UPrimtiveOpaque *primitive, Vector3 velocity);
 
 extern uint32_t IsSimulating(const UPrimtiveOpaque *primitive);
 
+extern void SetSimulatePhysics(UPrimtiveOpaque *primitive, uint32_t simulate);
+
 extern void AddForce(UPrimtiveOpaque *actor, Vector3 force);
 
 extern void AddImpulse(UPrimtiveOpaque *actor, Vector3 force);
 
+extern Vector3 GetAngularVelocity(const UPrimtiveOpaque *primitive);
+
+extern void SetAngularVelocity(UPrimtiveOpaque *primitive, Vector3 angular_velocity);
+
+extern void AddTorque(UPrimtiveOpaque *primitive, Vector3 torque);
+
+extern void AddAngularImpulse(UPrimtiveOpaque *primitive, Vector3 impulse);
+
 extern uint32_t LineTrace(Vector3 start, Vector3 end, LineTraceParams params, HitResult *result);
 
+extern uint32_t Raycast(Vector3 start, Vector3 end, TraceParams params, HitResult *result);
+
 extern Vector3 GetBoundingBoxExtent(const UPrimtiveOpaque *primitive);
 
 extern uint32_t Sweep(Vector3 start,
@@ -506,6 +977,13 @@ extern uint32_t Sweep(Vector3 start,
                       CollisionShape collision_shape,
                       HitResult *result);
 
+extern uint32_t SweepByChannel(Vector3 start,
+                               Vector3 end,
+                               Quaternion rotation,
+                               TraceParams params,
+                               CollisionShape collision_shape,
+                               HitResult *result);
+
 extern uint32_t SweepMulti(Vector3 start,
                            Vector3 end,
                            Quaternion rotation,
@@ -514,18 +992,26 @@ extern uint32_t SweepMulti(Vector3 start,
                            uintptr_t max_results,
                            HitResult *results);
 
-extern uint32_t OverlapMulti(CollisionShape collision_shape,
-                             Vector3 position,
-                             Quaternion rotation,
-                             LineTraceParams params,
-                             uintptr_t max_results,
-                             OverlapResult **result);
+extern uintptr_t OverlapMulti(CollisionShape collision_shape,
+                              Vector3 position,
+                              Quaternion rotation,
+                              TraceParams params,
+                              uintptr_t max_results,
+                              OverlapResult *results);
 
 extern uint32_t GetCollisionShape(const UPrimtiveOpaque *primitive, CollisionShape *shape);
 
+extern void GetCollisionResponse(const UPrimtiveOpaque *primitive, CollisionResponse *response);
+
+extern void SetCollisionResponse(UPrimtiveOpaque *primitive, CollisionResponse response);
+
 extern void PlaySoundAtLocation(const USoundBaseOpague *sound,
                                 Vector3 location,
                                 Quaternion rotation,
                                 const SoundSettings *settings);
 
+extern void PlaySound2d(const USoundBaseOpague *sound, const SoundSettings *settings);
+
+extern USoundBaseOpague *LoadSound(Utf8Str path);
+
 } // extern "C"
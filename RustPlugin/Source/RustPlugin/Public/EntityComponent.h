@@ -34,6 +34,8 @@ public:
 };
 
 
+DECLARE_DYNAMIC_MULTICAST_DELEGATE_OneParam(FOnRustComponentChanged, FGuid, Uuid);
+
 UCLASS(Blueprintable, meta=(BlueprintSpawnableComponent))
 class RUSTPLUGIN_API UEntityComponent : public UActorComponent
 {
@@ -47,6 +49,10 @@ public:
 	UPROPERTY(EditAnywhere, Category="Rust")
 	TMap<FString, FDynamicRustComponent> Components;
 
+	// Fires at the end of a frame in which an observed Rust component of this entity changed
+	UPROPERTY(BlueprintAssignable, Category="Rust")
+	FOnRustComponentChanged OnComponentChanged;
+
 public:
 	UFUNCTION(BlueprintCallable, Category="Rust|Utilities", meta=(Keywords = "entity"))
 	virtual FEntity GetEntity();
@@ -5,6 +5,7 @@
 #include "CoreMinimal.h"
 #include "GameFramework/GameModeBase.h"
 #include "Containers/Map.h"
+#include "Bindings.h"
 #include "RustGameModeBase.generated.h"
 
 class FRustPluginModule;
@@ -25,6 +26,7 @@ class RUSTPLUGIN_API ARustGameModeBase : public AGameModeBase
 	ARustGameModeBase();
 	~ARustGameModeBase();
 	virtual void StartPlay();
+	virtual void EndPlay(const EEndPlayReason::Type EndPlayReason) override;
 	virtual void Tick(float Dt);
 	UPlayerInput *PlayerInput;
 	int32 Handle;
@@ -34,6 +36,9 @@ class RUSTPLUGIN_API ARustGameModeBase : public AGameModeBase
 public:
 	UPROPERTY(EditAnywhere, Category = Game)
 	TArray<TSubclassOf<AActor>> RegisteredClasses;
+
+	// Overlap events that have not been polled from Rust yet
+	TArray<CollisionEvent> PendingCollisionEvents;
 	
 	UFUNCTION()
 	void OnActorBeginOverlap(AActor* OverlappedActor, AActor* OtherActor);
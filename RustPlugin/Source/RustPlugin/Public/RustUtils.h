@@ -16,6 +16,8 @@ extern struct FLogCategoryRustVisualLog : public FLogCategory<ELogVerbosity::Log
 
 Quaternion ToQuaternion(FQuat q);
 
+Vector2 ToVector2(FVector2D v);
+
 Vector3 ToVector3(FVector v);
 
 FVector ToFVector(Vector3 v);
@@ -24,6 +26,9 @@ FColor ToFColor(Color c);
 // W, X, Y, Z
 FQuat ToFQuat(Quaternion q);
 
+Rotator ToRotator(FRotator r);
+FRotator ToFRotator(Rotator r);
+
 AActor* ToAActor(const AActorOpaque* actor);
 AActor* ToAActor(AActorOpaque* actor);
 
@@ -34,6 +39,9 @@ UnrealBindings CreateBindings();
 FRustPluginModule& GetRustModule();
 
 FCollisionShape ToFCollisionShape(CollisionShape Shape);
+ECollisionChannel ToECollisionChannel(CollisionChannel Channel);
+// Returns false for channels that have no `CollisionChannel`, like custom trace channels
+bool ToCollisionChannel(ECollisionChannel Channel, CollisionChannel* Out);
 
 
 FString ToFString(Utf8Str Str);
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use bevy_ecs::prelude::*;
+use unreal_api::Component;
+use unreal_api::{
+    core::{run_if_running, CoreStage, Frame},
+    module::Module,
+    plugin::Plugin,
+    register_components,
+    timer::Timer,
+};
+
+#[derive(Debug, Clone)]
+struct Ability {
+    cooldown: Timer,
+}
+
+impl Ability {
+    fn new(cooldown: f32) -> Self {
+        let mut timer = Timer::from_seconds(cooldown, false);
+        // Abilities are ready right away
+        timer.tick(timer.duration());
+        Self { cooldown: timer }
+    }
+
+    fn is_ready(&self) -> bool {
+        self.cooldown.finished()
+    }
+}
+
+/// Named abilities of an entity, each with its own cooldown in seconds. Cooldowns are ticked
+/// with `Frame::dt` in `CoreStage::PreUpdate` and do not advance while the game is paused.
+#[derive(Default, Debug, Component)]
+#[uuid = "f4a81c3e-9b2d-4e67-a5c0-2d8e6b1f7a39"]
+pub struct AbilityComponent {
+    #[reflect(skip)]
+    abilities: HashMap<String, Ability>,
+}
+
+impl AbilityComponent {
+    pub fn with_ability(mut self, name: impl Into<String>, cooldown: f32) -> Self {
+        self.add_ability(name, cooldown);
+        self
+    }
+
+    /// Replaces an ability with the same name, the new ability is ready right away
+    pub fn add_ability(&mut self, name: impl Into<String>, cooldown: f32) {
+        self.abilities.insert(name.into(), Ability::new(cooldown));
+    }
+
+    pub fn remove_ability(&mut self, name: &str) -> bool {
+        self.abilities.remove(name).is_some()
+    }
+
+    /// Starts the cooldown and returns true if the ability is ready. False for unknown abilities.
+    pub fn try_activate(&mut self, name: &str) -> bool {
+        match self.abilities.get_mut(name) {
+            Some(ability) if ability.is_ready() => {
+                ability.cooldown.reset();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// False for unknown abilities
+    pub fn is_ready(&self, name: &str) -> bool {
+        self.abilities.get(name).is_some_and(Ability::is_ready)
+    }
+
+    /// Seconds until the ability is ready again, 0 if it is ready and `None` if it is unknown
+    pub fn remaining_cooldown(&self, name: &str) -> Option<f32> {
+        self.abilities
+            .get(name)
+            .map(|ability| ability.cooldown.remaining().max(0.0))
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        for ability in self.abilities.values_mut() {
+            ability.cooldown.tick(dt);
+        }
+    }
+}
+
+fn tick_cooldowns(frame: Res<Frame>, mut query: Query<&mut AbilityComponent>) {
+    for mut abilities in query.iter_mut() {
+        abilities.tick(frame.dt);
+    }
+}
+
+/// Opt-in abilities, adds the `AbilityComponent`
+pub struct AbilityPlugin;
+
+impl Plugin for AbilityPlugin {
+    fn build(&self, module: &mut Module) {
+        register_components! {
+            AbilityComponent,
+            => module
+        };
+        module.register_default_component::<AbilityComponent>();
+
+        module.add_system_set_to_stage(
+            CoreStage::PreUpdate,
+            SystemSet::new()
+                .with_run_criteria(run_if_running)
+                .with_system(tick_cooldowns),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cooldown_blocks_until_it_expires() {
+        let mut abilities = AbilityComponent::default().with_ability("dash", 1.0);
+        assert!(abilities.is_ready("dash"));
+        assert_eq!(abilities.remaining_cooldown("dash"), Some(0.0));
+
+        assert!(abilities.try_activate("dash"));
+        assert!(!abilities.is_ready("dash"));
+        assert!(!abilities.try_activate("dash"));
+
+        abilities.tick(0.6);
+        assert!(!abilities.try_activate("dash"));
+        assert!((abilities.remaining_cooldown("dash").unwrap() - 0.4).abs() < 1e-5);
+
+        abilities.tick(0.6);
+        assert!(abilities.is_ready("dash"));
+        assert_eq!(abilities.remaining_cooldown("dash"), Some(0.0));
+        assert!(abilities.try_activate("dash"));
+    }
+
+    #[test]
+    fn unknown_abilities_never_activate() {
+        let mut abilities = AbilityComponent::default();
+        assert!(!abilities.try_activate("dash"));
+        assert!(!abilities.is_ready("dash"));
+        assert_eq!(abilities.remaining_cooldown("dash"), None);
+
+        abilities.add_ability("dash", 1.0);
+        assert!(abilities.remove_ability("dash"));
+        assert!(!abilities.try_activate("dash"));
+    }
+
+    #[test]
+    fn cooldowns_tick_with_the_frame() {
+        let mut world = World::new();
+        world.insert_resource(Frame {
+            dt: 0.5,
+            ..Default::default()
+        });
+        let mut abilities = AbilityComponent::default().with_ability("dash", 1.0);
+        assert!(abilities.try_activate("dash"));
+        let entity = world.spawn().insert(abilities).id();
+
+        let mut stage = SystemStage::single_threaded().with_system(tick_cooldowns);
+        stage.run(&mut world);
+        assert!(!world
+            .get::<AbilityComponent>(entity)
+            .unwrap()
+            .is_ready("dash"));
+        stage.run(&mut world);
+        assert!(world
+            .get::<AbilityComponent>(entity)
+            .unwrap()
+            .is_ready("dash"));
+    }
+}
@@ -0,0 +1,162 @@
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+use unreal_api::Component;
+use unreal_api::{
+    core::{run_if_running, CoreStage},
+    module::Module,
+    plugin::Plugin,
+    register_components,
+};
+
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+#[uuid = "9a4c2e71-3d5b-4f08-b6e2-7c1d8f3a5e90"]
+pub struct HealthComponent {
+    /// Always in `[0, max]`
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Default for HealthComponent {
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl HealthComponent {
+    /// Starts at full health
+    pub fn new(max: f32) -> Self {
+        let max = max.max(0.0);
+        Self { current: max, max }
+    }
+
+    /// Returns the damage that was actually taken, which is less than `amount` if the entity
+    /// had less health left. Negative amounts are ignored.
+    pub fn damage(&mut self, amount: f32) -> f32 {
+        let before = self.current;
+        self.current = (self.current - amount.max(0.0)).clamp(0.0, self.max);
+        before - self.current
+    }
+
+    /// Never heals above `max`. Returns the health that was actually restored.
+    pub fn heal(&mut self, amount: f32) -> f32 {
+        let before = self.current;
+        self.current = (self.current + amount.max(0.0)).clamp(0.0, self.max);
+        self.current - before
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/// Send this event to damage `target`. Damage events are applied in `CoreStage::Update`, events
+/// for entities without a `HealthComponent` are ignored.
+#[derive(Debug, Copy, Clone)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    pub instigator: Option<Entity>,
+}
+
+/// Sent once when the health of `entity` drops to 0. Dead entities are not despawned, that is
+/// up to the game.
+#[derive(Debug, Copy, Clone)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    /// The instigator of the damage that killed the entity
+    pub instigator: Option<Entity>,
+}
+
+fn apply_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+    mut query: Query<&mut HealthComponent>,
+) {
+    for event in damage_events.iter() {
+        let Ok(mut health) = query.get_mut(event.target) else {
+            continue;
+        };
+        // Dead entities can not die again
+        if health.is_dead() {
+            continue;
+        }
+        health.damage(event.amount);
+        if health.is_dead() {
+            death_events.send(DeathEvent {
+                entity: event.target,
+                instigator: event.instigator,
+            });
+        }
+    }
+}
+
+/// Opt-in health, adds the `HealthComponent` and the `DamageEvent` and `DeathEvent` events
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, module: &mut Module) {
+        register_components! {
+            HealthComponent,
+            => module
+        };
+        module.register_serializable_component::<HealthComponent>();
+        module.register_default_component::<HealthComponent>();
+
+        module
+            .add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_system_set_to_stage(
+                CoreStage::Update,
+                SystemSet::new()
+                    .with_run_criteria(run_if_running)
+                    .with_system(apply_damage),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_is_clamped() {
+        let mut health = HealthComponent::new(100.0);
+        assert_eq!(health.damage(30.0), 30.0);
+        assert_eq!(health.damage(-10.0), 0.0);
+        assert_eq!(health.heal(50.0), 30.0);
+        assert_eq!(health.current, 100.0);
+        assert_eq!(health.damage(250.0), 100.0);
+        assert_eq!(health.current, 0.0);
+        assert!(health.is_dead());
+        assert_eq!(HealthComponent::new(-5.0).max, 0.0);
+    }
+
+    #[test]
+    fn lethal_damage_sends_one_death_event() {
+        let mut world = World::new();
+        world.init_resource::<Events<DamageEvent>>();
+        world.init_resource::<Events<DeathEvent>>();
+        let target = world.spawn().insert(HealthComponent::new(50.0)).id();
+        let instigator = world.spawn().id();
+        let mut stage = SystemStage::single_threaded().with_system(apply_damage);
+        let mut damage = |world: &mut World, amount| {
+            world.send_event(DamageEvent {
+                target,
+                amount,
+                instigator: Some(instigator),
+            });
+            stage.run(world);
+        };
+
+        damage(&mut world, 20.0);
+        damage(&mut world, 40.0);
+        damage(&mut world, 40.0);
+
+        let events = world.resource::<Events<DeathEvent>>();
+        let deaths: Vec<_> = events.get_reader().iter(events).copied().collect();
+        assert_eq!(deaths.len(), 1);
+        assert_eq!(deaths[0].entity, target);
+        assert_eq!(deaths[0].instigator, Some(instigator));
+        assert_eq!(world.get::<HealthComponent>(target).unwrap().current, 0.0);
+    }
+}
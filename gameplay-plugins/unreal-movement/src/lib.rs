@@ -1,14 +1,19 @@
-use bevy_ecs::{prelude::*, query::WorldQuery};
+use bevy_ecs::{prelude::*, query::WorldQuery, system::Command};
+use serde::{Deserialize, Serialize};
 use unreal_api::api::{SweepHit, SweepParams, UnrealApi};
 use unreal_api::Component;
 use unreal_api::{
-    core::{ActorComponent, CoreStage, Frame, TransformComponent},
+    core::{
+        run_if_running, ActorComponent, CoreLabel, CoreStage, Frame, PlayerInputComponent,
+        TransformComponent,
+    },
     ffi,
-    input::Input,
+    input::{Input, PlayerIndex},
     log::LogCategory,
-    math::{Quat, Vec3, Vec3Swizzles},
+    math::{damp, Quat, Vec2, Vec3, Vec3Swizzles},
     module::Module,
-    physics::PhysicsComponent,
+    net::{has_authority, NetRoleComponent},
+    physics::{CollisionShape, PhysicsComponent},
     plugin::Plugin,
     register_components,
 };
@@ -16,8 +21,73 @@ fn project_onto_plane(dir: Vec3, normal: Vec3) -> Vec3 {
     dir - normal * Vec3::dot(dir, normal)
 }
 
-#[derive(Debug, Copy, Clone)]
+/// What `move_and_slide` ran into on the way
+#[derive(Default, Debug, Copy, Clone)]
+pub struct SlideResult {
+    /// Same as the new position of the transform
+    pub position: Vec3,
+    /// Surfaces that are steep enough to not count as floor or ceiling
+    pub hit_wall: bool,
+    pub hit_floor: bool,
+    pub hit_ceiling: bool,
+}
+
+/// Moves `transform` by `delta`, sliding along everything it hits on the way. Every hit uses up
+/// one slide, the rest of the movement is dropped once `max_slides` hits were resolved.
+pub fn move_and_slide(
+    api: &UnrealApi,
+    transform: &mut TransformComponent,
+    delta: Vec3,
+    shape: CollisionShape,
+    params: SweepParams,
+    max_slides: u32,
+) -> SlideResult {
+    // Keeps the shape slightly away from surfaces so the next sweep does not start inside them
+    const SKIN: f32 = 0.1;
+    // Surfaces with a normal closer to up or down than this are floors or ceilings
+    const FLOOR_COS: f32 = 0.7;
+
+    let mut result = SlideResult::default();
+    let mut remaining = delta;
+    for _ in 0..=max_slides {
+        if remaining.length_squared() < f32::EPSILON {
+            break;
+        }
+        let start = transform.position;
+        let Some(hit) = api.sweep(
+            start,
+            start + remaining,
+            transform.rotation,
+            shape,
+            params.clone(),
+        ) else {
+            transform.position += remaining;
+            break;
+        };
+
+        if hit.normal.z > FLOOR_COS {
+            result.hit_floor = true;
+        } else if hit.normal.z < -FLOOR_COS {
+            result.hit_ceiling = true;
+        } else {
+            result.hit_wall = true;
+        }
+
+        if hit.started_penetrating {
+            transform.position += hit.normal * (hit.penetration_depth + SKIN);
+        } else {
+            transform.position = hit.location + hit.normal * SKIN;
+            remaining *= 1.0 - hit.time;
+        }
+        remaining = project_onto_plane(remaining, hit.normal);
+    }
+    result.position = transform.position;
+    result
+}
+
+#[derive(Default, Debug, Copy, Clone)]
 pub enum MovementState {
+    #[default]
     Walking,
     Falling,
     Gliding,
@@ -33,19 +103,201 @@ impl PlayerInput {
     pub const JUMP: &'static str = "Jump";
 }
 
-impl Default for MovementState {
-    fn default() -> Self {
-        Self::Walking
-    }
-}
-
-#[derive(Default, Debug, Component)]
+/// Describes how an entity is moving, mostly for animations. If the entity also has a simulating
+/// `PhysicsComponent`, the physics velocity is authoritative and is copied into `velocity` every
+/// frame. Use `VelocityQuery` to read and write the velocity without caring about the source.
+#[derive(Debug, Component, Serialize, Deserialize)]
 #[uuid = "fc8bd668-fc0a-4ab7-8b3d-f0f22bb539e2"]
 pub struct MovementComponent {
     pub velocity: Vec3,
     pub is_falling: bool,
     pub is_flying: bool,
     pub view: Quat,
+    /// Normal of the ground below the entity, zero if it is falling. Only updated for
+    /// entities with a `GroundDetectionComponent`.
+    pub ground_normal: Vec3,
+    #[reflect(skip)]
+    #[serde(skip)]
+    pub ground_actor: Option<Entity>,
+    /// The following fields are only used by entities with a `SimpleMovementComponent`
+    pub acceleration: f32,
+    pub max_speed: f32,
+    /// How quickly the entity comes to a stop without input, per second
+    pub friction: f32,
+    pub gravity: f32,
+}
+
+impl Default for MovementComponent {
+    fn default() -> Self {
+        Self {
+            velocity: Vec3::ZERO,
+            is_falling: false,
+            is_flying: false,
+            view: Quat::IDENTITY,
+            ground_normal: Vec3::ZERO,
+            ground_actor: None,
+            acceleration: 2048.0,
+            max_speed: 600.0,
+            friction: 8.0,
+            gravity: 981.0,
+        }
+    }
+}
+
+impl MovementComponent {
+    /// Sets the vertical velocity to `strength` if the entity is on the ground. Returns false
+    /// while falling. Use `VelocityQueryItem::try_jump` for entities that may simulate physics.
+    pub fn try_jump(&mut self, strength: f32) -> bool {
+        if self.is_falling {
+            return false;
+        }
+        self.velocity.z = strength;
+        self.is_falling = true;
+        true
+    }
+}
+
+/// Opts an entity into `integrate_simple_movement`, which moves it along
+/// `PlayerInputComponent::direction`. Entities with a simulating `PhysicsComponent` are moved
+/// by setting the physics velocity, all others by changing the `TransformComponent` directly.
+/// Gravity is only applied to the latter, unreal takes care of it for simulating primitives.
+#[derive(Default, Debug, Component)]
+#[uuid = "b0f5d3c2-8a4e-4c7b-9e1f-6d2a7c5b8e04"]
+pub struct SimpleMovementComponent;
+
+/// A push that slows down over time, e.g. from an explosion. The velocity is added to the
+/// position every frame and shrinks by a factor of `exp(-decay * dt)`. The component removes
+/// itself once the velocity drops below `KnockbackComponent::MIN_SPEED`. Entities with a
+/// simulating `PhysicsComponent` are pushed through their physics velocity instead, which gets
+/// the change of the knockback every frame, so the knockback part of it decays the same way.
+/// Insert it with `AddKnockback` to stack it with a knockback that is still running.
+#[derive(Debug, Component, Clone)]
+#[uuid = "6f1a3c8e-2b4d-4e97-a5c0-9d7e3f2b1a48"]
+pub struct KnockbackComponent {
+    pub velocity: Vec3,
+    /// Per second
+    pub decay: f32,
+    /// The part of the physics velocity that comes from this knockback
+    #[reflect(skip)]
+    applied: Vec3,
+}
+
+impl Default for KnockbackComponent {
+    fn default() -> Self {
+        Self::new(Vec3::ZERO, 8.0)
+    }
+}
+
+impl KnockbackComponent {
+    /// Below this speed the knockback is removed
+    pub const MIN_SPEED: f32 = 1.0;
+
+    pub fn new(velocity: Vec3, decay: f32) -> Self {
+        Self {
+            velocity,
+            decay,
+            applied: Vec3::ZERO,
+        }
+    }
+
+    /// Stacks another knockback on top of the current one
+    pub fn add(&mut self, velocity: Vec3) {
+        self.velocity += velocity;
+    }
+}
+
+/// Adds a knockback to `entity`. Unlike inserting a `KnockbackComponent`, which replaces the
+/// current one, the velocity is added to a knockback that is still running, which keeps its
+/// `decay`.
+pub struct AddKnockback {
+    pub entity: Entity,
+    pub velocity: Vec3,
+    /// Only used if the entity has no knockback yet
+    pub decay: f32,
+}
+
+impl Command for AddKnockback {
+    fn write(self, world: &mut World) {
+        let Some(mut entity) = world.get_entity_mut(self.entity) else {
+            return;
+        };
+        match entity.get_mut::<KnockbackComponent>() {
+            Some(mut knockback) => knockback.add(self.velocity),
+            None => {
+                entity.insert(KnockbackComponent::new(self.velocity, self.decay));
+            }
+        }
+    }
+}
+
+/// Updates `MovementComponent::is_falling` and the ground fields with a sphere sweep straight
+/// down from the position of the entity, in `CoreStage::PreUpdate` before any movement system.
+/// The entity only counts as standing when it touches the ground, that is when the ground is
+/// closer than `GroundDetectionComponent::CONTACT_DISTANCE` or than the distance the entity falls
+/// in this frame, and it is then snapped onto the ground. Entities that move upward, e.g. right
+/// after a jump, are always falling, so `MovementComponent::try_jump` can't trigger again.
+#[derive(Debug, Component, Clone)]
+#[uuid = "3e6c7a9b-1f0d-4f0e-9b7a-2c8e5d4f6a13"]
+pub struct GroundDetectionComponent {
+    /// How far below the position the ground is still detected
+    pub trace_distance: f32,
+    pub radius: f32,
+}
+
+impl GroundDetectionComponent {
+    /// Gaps to the ground up to this size still count as standing on it
+    pub const CONTACT_DISTANCE: f32 = 2.0;
+}
+
+impl Default for GroundDetectionComponent {
+    fn default() -> Self {
+        Self {
+            trace_distance: 100.0,
+            radius: 30.0,
+        }
+    }
+}
+
+/// First person look. Turns `Input::mouse_delta` into a yaw and pitch, which are written to
+/// `MovementComponent::view` every frame.
+#[derive(Debug, Component)]
+#[uuid = "d813f3a7-b3a4-4234-b205-f42ffb144f22"]
+pub struct MouseLookComponent {
+    /// Radians per unit of `Input::mouse_delta`
+    pub sensitivity: f32,
+    /// In radians, wrapped into `[-PI, PI)`
+    pub yaw: f32,
+    /// In radians, clamped to +-`MouseLookComponent::MAX_PITCH`
+    pub pitch: f32,
+}
+
+impl MouseLookComponent {
+    pub const MAX_PITCH: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+    pub fn add_mouse_delta(&mut self, delta: Vec2) {
+        use std::f32::consts::{PI, TAU};
+        self.yaw = (self.yaw + delta.x * self.sensitivity + PI).rem_euclid(TAU) - PI;
+        // The mouse Y axis of unreal is positive when moving the mouse up, which looks up
+        self.pitch = f32::clamp(
+            self.pitch + delta.y * self.sensitivity,
+            -Self::MAX_PITCH,
+            Self::MAX_PITCH,
+        );
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::from_rotation_z(self.yaw) * Quat::from_rotation_y(-self.pitch)
+    }
+}
+
+impl Default for MouseLookComponent {
+    fn default() -> Self {
+        Self {
+            sensitivity: 0.005,
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
 }
 
 #[derive(Default, Debug, Component)]
@@ -108,7 +360,8 @@ pub enum MovementHit {
 }
 
 pub struct FloorHit {
-    pub entity: Entity,
+    /// `None` if the floor is not known to Rust
+    pub entity: Option<Entity>,
     pub impact_location: Vec3,
 }
 
@@ -129,12 +382,12 @@ fn do_walking(
             + movement.physics.get_collision_shape().extent().z
             + movement.config.walk_offset;
 
-        if input.is_action_pressed(PlayerInput::JUMP) {
+        if input.player_just_pressed(movement.player_index(), PlayerInput::JUMP) {
             movement.controller.vertical_velocity.z += movement.config.jump_velocity;
             return Some(MovementState::Falling);
         }
-        let phys = query.get(hit.entity).ok();
-        let velocity = phys.map(|p| p.velocity).unwrap_or_default();
+        let phys = hit.entity.and_then(|entity| query.get(entity).ok());
+        let velocity = phys.map(|p| p.velocity()).unwrap_or_default();
         movement.do_movement(velocity, dt, api);
         None
     } else {
@@ -151,7 +404,7 @@ fn do_falling(
     let is_downwards = movement.controller.vertical_velocity.z < 0.0;
     if movement.find_floor(api).is_some() && is_downwards {
         Some(MovementState::Walking)
-    } else if input.is_action_pressed(PlayerInput::JUMP) {
+    } else if input.player_just_pressed(movement.player_index(), PlayerInput::JUMP) {
         Some(MovementState::Gliding)
     } else {
         movement.controller.vertical_velocity +=
@@ -171,7 +424,7 @@ fn do_gliding(
 
     if movement.find_floor(api).is_some() && is_downwards {
         Some(MovementState::Walking)
-    } else if input.is_action_pressed(PlayerInput::JUMP) {
+    } else if input.player_just_pressed(movement.player_index(), PlayerInput::JUMP) {
         Some(MovementState::Falling)
     } else {
         movement.controller.vertical_velocity += movement.config.gravity_dir
@@ -189,6 +442,62 @@ fn do_gliding(
     }
 }
 
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub struct VelocityQuery {
+    movement: Option<&'static mut MovementComponent>,
+    physics: Option<&'static mut PhysicsComponent>,
+}
+
+impl<'w> VelocityQueryItem<'w> {
+    fn simulating_physics(&self) -> Option<&PhysicsComponent> {
+        self.physics
+            .as_deref()
+            .filter(|physics| physics.is_active())
+    }
+
+    pub fn get(&self) -> Vec3 {
+        match (self.simulating_physics(), self.movement.as_deref()) {
+            (Some(physics), _) => physics.velocity(),
+            (None, Some(movement)) => movement.velocity,
+            (None, None) => Vec3::ZERO,
+        }
+    }
+
+    pub fn set(&mut self, velocity: Vec3) {
+        if self.simulating_physics().is_some() {
+            if let Some(physics) = self.physics.as_mut() {
+                physics.set_velocity(velocity);
+            }
+        }
+        if let Some(movement) = self.movement.as_mut() {
+            movement.velocity = velocity;
+        }
+    }
+
+    /// Same as `MovementComponent::try_jump`, but entities with a simulating `PhysicsComponent`
+    /// get an upwards impulse of `strength` instead, which unreal scales by their mass. Entities
+    /// without a `MovementComponent` never jump because there is no `is_falling` to check.
+    pub fn try_jump(&mut self, strength: f32) -> bool {
+        let simulating = self.simulating_physics().is_some();
+        let Some(movement) = self.movement.as_mut() else {
+            return false;
+        };
+        if simulating {
+            if movement.is_falling {
+                return false;
+            }
+            movement.is_falling = true;
+            if let Some(physics) = self.physics.as_mut() {
+                physics.add_impulse(Vec3::Z * strength);
+            }
+            true
+        } else {
+            movement.try_jump(strength)
+        }
+    }
+}
+
 #[derive(WorldQuery)]
 #[world_query(mutable)]
 pub struct MovementQuery {
@@ -198,8 +507,17 @@ pub struct MovementQuery {
     physics: &'static PhysicsComponent,
     controller: &'static mut CharacterControllerComponent,
     config: &'static CharacterConfigComponent,
+    /// Entities without a `PlayerInputComponent` are controlled by player 0
+    player_input: Option<&'static PlayerInputComponent>,
+    net_role: Option<&'static NetRoleComponent>,
 }
 impl<'w> MovementQueryItem<'w> {
+    pub fn player_index(&self) -> PlayerIndex {
+        self.player_input
+            .map(|input| input.player_index)
+            .unwrap_or_default()
+    }
+
     pub fn try_step_up(&self, move_result: &SweepHit, api: &UnrealApi) -> Option<StepUpResult> {
         let params = SweepParams::default()
             // Don't test against ourselves
@@ -225,7 +543,7 @@ impl<'w> MovementQueryItem<'w> {
             // Nope we started in penetration which means the object in front of us is higher than
             // we can step
             // Or there could not be enough space on top of the object we are trying to step on
-            if hit.start_in_penentration {
+            if hit.started_penetrating {
                 return None;
             }
             unreal_api::log::visual_log_shape(
@@ -269,7 +587,7 @@ impl<'w> MovementQueryItem<'w> {
             params,
         )
         .and_then(|hit| {
-            if hit.start_in_penentration {
+            if hit.started_penetrating {
                 let new_location = hit.location + hit.normal * (hit.penetration_depth + 2.0);
                 unreal_api::log::visual_log_location(
                     MovementLog::PENETRATION,
@@ -336,7 +654,7 @@ impl<'w> MovementQueryItem<'w> {
                 params,
             ) {
                 // Lazy: lets just set the vertical_velocity to zero if we hit something
-                if !hit.start_in_penentration {
+                if !hit.started_penetrating {
                     self.controller.vertical_velocity = Vec3::ZERO;
                 }
             }
@@ -348,7 +666,7 @@ impl<'w> MovementQueryItem<'w> {
         if self.controller.horizontal_velocity.length() > 0.2 {
             let velocity_dir = self.controller.horizontal_velocity.normalize_or_zero();
             let target_rot = Quat::from_rotation_z(f32::atan2(velocity_dir.y, velocity_dir.x));
-            self.transform.rotation = Quat::lerp(self.transform.rotation, target_rot, dt * 10.0);
+            self.transform.rotation = damp(self.transform.rotation, target_rot, 10.0, dt);
         }
     }
     pub fn movement_hit(&self, velocity: Vec3, dt: f32, api: &UnrealApi) -> Option<MovementHit> {
@@ -411,13 +729,20 @@ fn character_control_system(
     phys: Query<&PhysicsComponent>,
 ) {
     let api = &api;
-    let forward = input
-        .get_axis_value(PlayerInput::MOVE_FORWARD)
-        .unwrap_or(0.0);
-    let right = input.get_axis_value(PlayerInput::MOVE_RIGHT).unwrap_or(0.0);
-    let player_input = Vec3::new(forward, right, 0.0).normalize_or_zero();
-
     for mut movement in query.iter_mut() {
+        // Proxies are moved by replication
+        if !has_authority(movement.net_role) {
+            continue;
+        }
+        let player = movement.player_index();
+        let forward = input
+            .player_axis_value(player, PlayerInput::MOVE_FORWARD)
+            .unwrap_or(0.0);
+        let right = input
+            .player_axis_value(player, PlayerInput::MOVE_RIGHT)
+            .unwrap_or(0.0);
+        let player_input = Vec3::new(forward, right, 0.0).normalize_or_zero();
+
         let mut input_dir = movement.controller.camera_view * player_input;
         input_dir.z = 0.0;
         movement.controller.horizontal_velocity =
@@ -449,6 +774,142 @@ fn update_movement_component(
     }
 }
 
+fn detect_ground(
+    api: Res<UnrealApi>,
+    frame: Res<Frame>,
+    mut query: Query<(
+        Entity,
+        &mut TransformComponent,
+        &GroundDetectionComponent,
+        &mut MovementComponent,
+    )>,
+) {
+    for (entity, mut transform, detection, mut movement) in query.iter_mut() {
+        if movement.velocity.z > 0.0 {
+            movement.is_falling = true;
+            movement.ground_normal = Vec3::ZERO;
+            movement.ground_actor = None;
+            continue;
+        }
+        let params = SweepParams::default().add_ignored_entity(entity);
+        let hit = api.sweep(
+            transform.position,
+            transform.position - Vec3::Z * detection.trace_distance,
+            Quat::IDENTITY,
+            CollisionShape::Sphere {
+                radius: detection.radius,
+            },
+            params,
+        );
+        let reach = f32::max(
+            GroundDetectionComponent::CONTACT_DISTANCE,
+            -movement.velocity.z * frame.dt,
+        );
+        let contact = hit.filter(|hit| transform.position.z - hit.location.z <= reach);
+        movement.is_falling = contact.is_none();
+        movement.ground_normal = contact.as_ref().map_or(Vec3::ZERO, |hit| hit.impact_normal);
+        movement.ground_actor = contact.as_ref().and_then(|hit| hit.entity);
+        if let Some(hit) = contact {
+            if transform.position != hit.location {
+                transform.position = hit.location;
+            }
+        }
+    }
+}
+
+fn update_mouse_look(
+    input: Res<Input>,
+    mut query: Query<(&mut MouseLookComponent, &mut MovementComponent)>,
+) {
+    let delta = input.mouse_delta();
+    for (mut look, mut movement) in query.iter_mut() {
+        look.add_mouse_delta(delta);
+        movement.view = look.rotation();
+    }
+}
+
+type SimpleMovementItem<'a> = (
+    &'a PlayerInputComponent,
+    &'a mut MovementComponent,
+    &'a mut TransformComponent,
+    Option<&'a mut PhysicsComponent>,
+    Option<&'a NetRoleComponent>,
+);
+
+fn integrate_simple_movement(
+    frame: Res<Frame>,
+    mut query: Query<SimpleMovementItem, With<SimpleMovementComponent>>,
+) {
+    let dt = frame.dt;
+    for (input, mut movement, mut transform, physics, role) in query.iter_mut() {
+        if !has_authority(role) {
+            continue;
+        }
+        let direction = Vec3::new(input.direction.x, input.direction.y, 0.0).clamp_length_max(1.0);
+        let mut horizontal = Vec3::new(movement.velocity.x, movement.velocity.y, 0.0);
+        if direction != Vec3::ZERO {
+            horizontal = (horizontal + direction * movement.acceleration * dt)
+                .clamp_length_max(movement.max_speed);
+        } else {
+            horizontal *= f32::max(0.0, 1.0 - movement.friction * dt);
+        }
+
+        let simulating = physics.as_ref().is_some_and(|physics| physics.is_active());
+        // Unreal already applies gravity to simulating primitives. Standing entities only lose
+        // their downward velocity, a jump that starts on the ground still slows down.
+        let vertical = if simulating {
+            movement.velocity.z
+        } else if movement.is_falling || movement.velocity.z > 0.0 {
+            movement.velocity.z - movement.gravity * dt
+        } else {
+            0.0
+        };
+        movement.velocity = horizontal + Vec3::Z * vertical;
+
+        match physics {
+            Some(mut physics) if simulating => physics.set_velocity(movement.velocity),
+            _ => transform.position += movement.velocity * dt,
+        }
+    }
+}
+
+fn apply_knockback(
+    mut commands: Commands,
+    frame: Res<Frame>,
+    mut query: Query<(
+        Entity,
+        &mut KnockbackComponent,
+        &mut TransformComponent,
+        Option<&mut PhysicsComponent>,
+    )>,
+) {
+    let dt = frame.dt;
+    for (entity, mut knockback, mut transform, physics) in query.iter_mut() {
+        match physics {
+            Some(mut physics) if physics.is_active() => {
+                // The physics velocity still holds what was added in the last frames
+                let velocity = physics.velocity() + knockback.velocity - knockback.applied;
+                physics.set_velocity(velocity);
+                knockback.applied = knockback.velocity;
+            }
+            _ => transform.position += knockback.velocity * dt,
+        }
+        let falloff = f32::exp(-knockback.decay * dt);
+        knockback.velocity *= falloff;
+        if knockback.velocity.length() < KnockbackComponent::MIN_SPEED {
+            commands.entity(entity).remove::<KnockbackComponent>();
+        }
+    }
+}
+
+fn sync_physics_velocity(mut query: Query<(&PhysicsComponent, &mut MovementComponent)>) {
+    for (physics, mut movement) in query.iter_mut() {
+        if physics.is_active() {
+            movement.velocity = physics.velocity();
+        }
+    }
+}
+
 pub struct MovementPlugin;
 
 impl Plugin for MovementPlugin {
@@ -456,14 +917,450 @@ impl Plugin for MovementPlugin {
         register_components! {
             MovementComponent,
             CharacterConfigComponent,
+            GroundDetectionComponent,
+            SimpleMovementComponent,
+            MouseLookComponent,
+            KnockbackComponent,
             => module
         };
+        module.register_serializable_component::<MovementComponent>();
+        module.register_default_component::<MovementComponent>();
+        module.register_default_component::<SimpleMovementComponent>();
+        module.register_default_component::<MouseLookComponent>();
+        module.register_default_component::<KnockbackComponent>();
 
+        // The ground state is fresh before any movement system runs
+        module.add_system_set_to_stage(
+            CoreStage::PreUpdate,
+            SystemSet::new()
+                .with_run_criteria(run_if_running)
+                .with_system(detect_ground.after(CoreLabel::DownloadTransform)),
+        );
         module.add_system_set_to_stage(
             CoreStage::Update,
             SystemSet::new()
+                .with_run_criteria(run_if_running)
+                .with_system(update_mouse_look.before(character_control_system))
                 .with_system(character_control_system)
-                .with_system(update_movement_component.after(character_control_system)),
+                .with_system(integrate_simple_movement.after(sync_physics_velocity))
+                .with_system(update_movement_component.after(character_control_system))
+                .with_system(sync_physics_velocity.after(update_movement_component))
+                .with_system(apply_knockback.after(integrate_simple_movement)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::IntoSystemDescriptor, system::SystemState};
+    use unreal_api::{assert_vec_eq, core::UnrealPtr, net::NetRole, testing};
+
+    fn run_system<Params>(world: &mut World, system: impl IntoSystemDescriptor<Params>) {
+        SystemStage::single_threaded()
+            .with_system(system)
+            .run(world);
+    }
+
+    fn ground_hit(actor: testing::Ptr) -> Option<ffi::HitResult> {
+        Some(ffi::HitResult {
+            actor: actor as *mut ffi::AActorOpaque,
+            impact_normal: Vec3::Z.into(),
+            normal: Vec3::Z.into(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn ground_detection_sets_falling() {
+        testing::install();
+        let mut world = World::new();
+        world.insert_resource(UnrealApi::default());
+        world.insert_resource(Frame::default());
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                TransformComponent::default(),
+                GroundDetectionComponent::default(),
+                MovementComponent::default(),
+            ))
+            .id();
+
+        // The ground is an actor that is not known to Rust
+        let ground = testing::spawn_actor();
+        testing::with(|unreal| {
+            unreal.sweep = Some(Box::new(move |_, _| ground_hit(ground)));
+        });
+        run_system(&mut world, detect_ground);
+        let movement = world.get::<MovementComponent>(entity).unwrap();
+        assert!(!movement.is_falling);
+        assert_eq!(movement.ground_normal, Vec3::Z);
+        assert_eq!(movement.ground_actor, None);
+
+        testing::with(|unreal| unreal.sweep = None);
+        run_system(&mut world, detect_ground);
+        let movement = world.get::<MovementComponent>(entity).unwrap();
+        assert!(movement.is_falling);
+        assert_eq!(movement.ground_normal, Vec3::ZERO);
+    }
+
+    fn walker(world: &mut World, dt: f32) -> Entity {
+        world.insert_resource(Frame {
+            dt,
+            ..Default::default()
+        });
+        world
+            .spawn()
+            .insert_bundle((
+                PlayerInputComponent {
+                    direction: Vec3::X,
+                    ..Default::default()
+                },
+                MovementComponent::default(),
+                SimpleMovementComponent,
+                TransformComponent::default(),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn simple_movement_ramps_up_to_max_speed() {
+        let mut world = World::new();
+        let entity = walker(&mut world, 0.1);
+
+        run_system(&mut world, integrate_simple_movement);
+        let movement = world.get::<MovementComponent>(entity).unwrap();
+        assert!((movement.velocity.x - 204.8).abs() < 1e-3);
+        let transform = world.get::<TransformComponent>(entity).unwrap();
+        assert!((transform.position.x - 20.48).abs() < 1e-3);
+
+        for _ in 0..5 {
+            run_system(&mut world, integrate_simple_movement);
+        }
+        let movement = world.get::<MovementComponent>(entity).unwrap();
+        assert!((movement.velocity.x - movement.max_speed).abs() < 1e-3);
+        assert_eq!(movement.velocity.z, 0.0);
+    }
+
+    #[test]
+    fn simulating_entities_get_no_extra_gravity() {
+        testing::install();
+        let mut world = World::new();
+        let entity = walker(&mut world, 0.1);
+        let (_, primitive) = testing::spawn_physics_actor();
+        let mut movement = world.get_mut::<MovementComponent>(entity).unwrap();
+        movement.is_falling = true;
+        movement.velocity = Vec3::new(0.0, 0.0, -100.0);
+        world
+            .entity_mut(entity)
+            .insert(PhysicsComponent::new(UnrealPtr::from_raw(primitive)));
+
+        run_system(&mut world, integrate_simple_movement);
+
+        let movement = world.get::<MovementComponent>(entity).unwrap();
+        assert_eq!(movement.velocity.z, -100.0);
+        let physics = world.get::<PhysicsComponent>(entity).unwrap();
+        assert_eq!(physics.velocity().z, -100.0);
+        let transform = world.get::<TransformComponent>(entity).unwrap();
+        assert_eq!(transform.position, Vec3::ZERO);
+    }
+
+    #[test]
+    fn mouse_look_clamps_pitch_and_accumulates_yaw() {
+        testing::install();
+        let mut world = World::new();
+        world.insert_resource(Input::default());
+        let look = MouseLookComponent {
+            sensitivity: 0.01,
+            ..Default::default()
+        };
+        let entity = world
+            .spawn()
+            .insert_bundle((look, MovementComponent::default()))
+            .id();
+        let mut look_with = |delta: Vec2| {
+            testing::with(|unreal| unreal.mouse_delta = delta);
+            world.resource_mut::<Input>().update();
+            run_system(&mut world, update_mouse_look);
+        };
+
+        look_with(Vec2::new(10.0, 20.0));
+        look_with(Vec2::new(15.0, 1000.0));
+
+        let look = world.get::<MouseLookComponent>(entity).unwrap();
+        assert!((look.yaw - 0.25).abs() < 1e-6);
+        assert_eq!(look.pitch, MouseLookComponent::MAX_PITCH);
+        let movement = world.get::<MovementComponent>(entity).unwrap();
+        assert_vec_eq!(movement.view, look.rotation(), 1e-6);
+        // Looking up tilts the forward vector up
+        assert!((movement.view * Vec3::X).z > 0.99);
+    }
+
+    #[test]
+    fn jumping_requires_ground() {
+        let mut movement = MovementComponent::default();
+        assert!(movement.try_jump(500.0));
+        assert_eq!(movement.velocity.z, 500.0);
+        assert!(movement.is_falling);
+
+        movement.velocity.z = 100.0;
+        assert!(!movement.try_jump(500.0));
+        assert_eq!(movement.velocity.z, 100.0);
+    }
+
+    #[test]
+    fn simulating_entities_jump_with_an_impulse() {
+        testing::install();
+        let mut world = World::new();
+        let (_, primitive) = testing::spawn_physics_actor();
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                MovementComponent::default(),
+                PhysicsComponent::new(UnrealPtr::from_raw(primitive)),
+            ))
+            .id();
+        let mut state = SystemState::<Query<VelocityQuery>>::new(&mut world);
+        testing::take_calls();
+
+        let mut query = state.get_mut(&mut world);
+        let mut velocity = query.get_mut(entity).unwrap();
+        assert!(velocity.try_jump(500.0));
+        assert!(!velocity.try_jump(500.0));
+        assert_eq!(
+            testing::take_calls(),
+            vec![testing::Call::AddImpulse {
+                primitive,
+                impulse: Vec3::Z * 500.0,
+            }]
+        );
+        assert!(world.get::<MovementComponent>(entity).unwrap().is_falling);
+    }
+
+    #[test]
+    fn knockback_decays_and_is_removed() {
+        let mut world = World::new();
+        world.insert_resource(Frame {
+            dt: 0.1,
+            ..Default::default()
+        });
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                TransformComponent::default(),
+                KnockbackComponent::new(Vec3::X * 100.0, 8.0),
+            ))
+            .id();
+
+        run_system(&mut world, apply_knockback);
+        let transform = world.get::<TransformComponent>(entity).unwrap();
+        assert!((transform.position.x - 10.0).abs() < 1e-4);
+        let knockback = world.get::<KnockbackComponent>(entity).unwrap();
+        assert!((knockback.velocity.x - 100.0 * f32::exp(-0.8)).abs() < 1e-3);
+
+        for _ in 0..10 {
+            run_system(&mut world, apply_knockback);
+        }
+        assert!(world.get::<KnockbackComponent>(entity).is_none());
+    }
+
+    #[test]
+    fn knockback_pushes_simulating_entities_through_physics() {
+        testing::install();
+        let mut world = World::new();
+        world.insert_resource(Frame {
+            dt: 0.1,
+            ..Default::default()
+        });
+        let (_, primitive) = testing::spawn_physics_actor();
+        testing::with(|unreal| unreal.primitive_mut(primitive).velocity = Vec3::Z * 10.0);
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                TransformComponent::default(),
+                KnockbackComponent::new(Vec3::X * 100.0, 8.0),
+                PhysicsComponent::new(UnrealPtr::from_raw(primitive)),
+            ))
+            .id();
+
+        run_system(&mut world, apply_knockback);
+        let physics = world.get::<PhysicsComponent>(entity).unwrap();
+        assert_eq!(physics.velocity(), Vec3::new(100.0, 0.0, 10.0));
+
+        // Only the knockback part of the velocity decays
+        run_system(&mut world, apply_knockback);
+        let physics = world.get::<PhysicsComponent>(entity).unwrap();
+        assert_vec_eq!(
+            physics.velocity(),
+            Vec3::new(100.0 * f32::exp(-0.8), 0.0, 10.0),
+            1e-3
         );
+        assert!(world.get::<KnockbackComponent>(entity).is_some());
+
+        for _ in 0..10 {
+            run_system(&mut world, apply_knockback);
+        }
+        assert!(world.get::<KnockbackComponent>(entity).is_none());
+        let physics = world.get::<PhysicsComponent>(entity).unwrap();
+        assert!(physics.velocity().x < KnockbackComponent::MIN_SPEED * 2.5);
+        assert_eq!(physics.velocity().z, 10.0);
+        let transform = world.get::<TransformComponent>(entity).unwrap();
+        assert_eq!(transform.position, Vec3::ZERO);
+    }
+
+    #[test]
+    fn knockbacks_stack() {
+        let mut world = World::new();
+        let entity = world.spawn().id();
+        AddKnockback {
+            entity,
+            velocity: Vec3::X * 100.0,
+            decay: 4.0,
+        }
+        .write(&mut world);
+        AddKnockback {
+            entity,
+            velocity: Vec3::Y * 50.0,
+            decay: 8.0,
+        }
+        .write(&mut world);
+
+        let knockback = world.get::<KnockbackComponent>(entity).unwrap();
+        assert_eq!(knockback.velocity, Vec3::new(100.0, 50.0, 0.0));
+        assert_eq!(knockback.decay, 4.0);
+    }
+
+    #[test]
+    fn move_and_slide_slides_along_a_wall() {
+        testing::install();
+        // A wall at x = 100 that faces -X
+        testing::with(|unreal| {
+            unreal.sweep = Some(Box::new(|start: Vec3, end: Vec3| {
+                if start.x > 100.0 || end.x <= 100.0 {
+                    return None;
+                }
+                let time = (100.0 - start.x) / (end.x - start.x);
+                let location = start.lerp(end, time);
+                Some(ffi::HitResult {
+                    location: location.into(),
+                    impact_location: location.into(),
+                    normal: (-Vec3::X).into(),
+                    impact_normal: (-Vec3::X).into(),
+                    time,
+                    ..Default::default()
+                })
+            }));
+        });
+        let api = UnrealApi::default();
+        let mut transform = TransformComponent::default();
+
+        let result = move_and_slide(
+            &api,
+            &mut transform,
+            Vec3::new(200.0, 100.0, 0.0),
+            CollisionShape::Sphere { radius: 10.0 },
+            SweepParams::default(),
+            4,
+        );
+
+        assert!(result.hit_wall);
+        assert!(!result.hit_floor && !result.hit_ceiling);
+        // Stops in front of the wall and keeps the part of the movement along it
+        assert_vec_eq!(transform.position, Vec3::new(99.9, 100.0, 0.0), 1e-3);
+        assert_eq!(result.position, transform.position);
+
+        // Without slides left the movement along the wall is dropped
+        let mut transform = TransformComponent::default();
+        move_and_slide(
+            &api,
+            &mut transform,
+            Vec3::new(200.0, 100.0, 0.0),
+            CollisionShape::Sphere { radius: 10.0 },
+            SweepParams::default(),
+            0,
+        );
+        assert_vec_eq!(transform.position, Vec3::new(99.9, 50.0, 0.0), 1e-3);
+    }
+
+    #[test]
+    fn proxies_are_not_integrated() {
+        let mut world = World::new();
+        let authority = walker(&mut world, 0.1);
+        world.entity_mut(authority).insert(NetRoleComponent {
+            role: NetRole::Authority,
+        });
+        let proxy = walker(&mut world, 0.1);
+        world.entity_mut(proxy).insert(NetRoleComponent {
+            role: NetRole::SimulatedProxy,
+        });
+
+        run_system(&mut world, integrate_simple_movement);
+
+        let position = |entity| world.get::<TransformComponent>(entity).unwrap().position;
+        assert!(position(authority).x > 0.0);
+        assert_eq!(position(proxy), Vec3::ZERO);
+        let movement = world.get::<MovementComponent>(proxy).unwrap();
+        assert_eq!(movement.velocity, Vec3::ZERO);
+    }
+
+    #[test]
+    fn jumps_come_back_down_and_land() {
+        testing::install();
+        let mut world = World::new();
+        world.insert_resource(UnrealApi::default());
+        let entity = walker(&mut world, 1.0 / 60.0);
+        world
+            .entity_mut(entity)
+            .insert(GroundDetectionComponent::default())
+            .get_mut::<PlayerInputComponent>()
+            .unwrap()
+            .direction = Vec3::ZERO;
+
+        // A floor at z = 0 that is not known to Rust
+        let floor = testing::spawn_actor();
+        testing::with(|unreal| {
+            unreal.sweep = Some(Box::new(move |start, end| {
+                (end.z <= 0.0).then(|| ffi::HitResult {
+                    location: Vec3::new(start.x, start.y, 0.0).into(),
+                    time: start.z / (start.z - end.z),
+                    ..ground_hit(floor).unwrap()
+                })
+            }));
+        });
+        let mut stage = SystemStage::single_threaded()
+            .with_system(detect_ground.before(integrate_simple_movement))
+            .with_system(integrate_simple_movement);
+        stage.run(&mut world);
+        let mut movement = world.get_mut::<MovementComponent>(entity).unwrap();
+        assert!(!movement.is_falling);
+        assert!(movement.try_jump(500.0));
+
+        // Still touching the ground, but the jump already started
+        run_system(&mut world, detect_ground);
+        let mut movement = world.get_mut::<MovementComponent>(entity).unwrap();
+        assert!(movement.is_falling);
+        assert!(!movement.try_jump(500.0));
+
+        let mut apex: f32 = 0.0;
+        let mut landed_after = None;
+        for frame in 1..=120 {
+            stage.run(&mut world);
+            let height = world.get::<TransformComponent>(entity).unwrap().position.z;
+            apex = apex.max(height);
+            let movement = world.get::<MovementComponent>(entity).unwrap();
+            if !movement.is_falling {
+                landed_after = Some(frame);
+                break;
+            }
+        }
+
+        // v^2 / 2g, gravity slows the jump down on the way up
+        assert!((apex - 127.4).abs() < 5.0, "apex {}", apex);
+        let landed_after = landed_after.expect("the jump never landed");
+        assert!((55..=65).contains(&landed_after), "{}", landed_after);
+        let transform = world.get::<TransformComponent>(entity).unwrap();
+        assert_eq!(transform.position.z, 0.0);
+        let movement = world.get::<MovementComponent>(entity).unwrap();
+        assert_eq!(movement.velocity.z, 0.0);
     }
 }
@@ -5,24 +5,32 @@ use proc_macro2::Span;
 use quote::quote;
 use syn::*;
 
-#[derive(Debug, FromField)]
-#[darling(attributes(reflect))]
-pub struct ReflectField {
-    ident: Option<Ident>,
-    ty: Type,
-    #[darling(default)]
-    skip: bool,
-}
-#[derive(Debug, FromDeriveInput)]
-#[darling(attributes(reflect))]
-pub struct ReflectEditor {
-    #[darling(default)]
-    editor: bool,
+// `darling(default)` expands to code that trips this lint on newer clippy versions
+#[allow(clippy::manual_unwrap_or_default)]
+mod attributes {
+    use darling::{FromDeriveInput, FromField};
+    use syn::{Ident, Type};
+
+    #[derive(Debug, FromField)]
+    #[darling(attributes(reflect))]
+    pub struct ReflectField {
+        pub ident: Option<Ident>,
+        pub ty: Type,
+        #[darling(default)]
+        pub skip: bool,
+    }
+    #[derive(Debug, FromDeriveInput)]
+    #[darling(attributes(reflect))]
+    pub struct ReflectEditor {
+        #[darling(default)]
+        pub editor: bool,
+    }
 }
+use attributes::{ReflectEditor, ReflectField};
 
 pub fn reflect_derive(ast: &DeriveInput) -> proc_macro2::TokenStream {
     let is_editor_component =
-        ReflectEditor::from_derive_input(ast).map_or(false, |reflect| reflect.editor);
+        ReflectEditor::from_derive_input(ast).is_ok_and(|reflect| reflect.editor);
 
     if let Data::Struct(data) = &ast.data {
         let literal_name = LitStr::new(&ast.ident.to_string(), Span::call_site());
@@ -96,6 +104,23 @@ pub fn reflect_derive(ast: &DeriveInput) -> proc_macro2::TokenStream {
                             Some(ty)
                         })
                 }
+                fn set_field_value(&self, world: &mut unreal_api::World, entity: unreal_api::Entity, idx: u32, value: unreal_api::registry::ReflectValue) -> bool {
+                    match idx {
+                        #(
+                            #field_indices => match (
+                                <#field_types as unreal_api::registry::ReflectStatic>::from_reflect_value(value),
+                                world.get_mut::<#self_ty>(entity),
+                            ) {
+                                (Some(value), Some(mut component)) => {
+                                    component.#field_idents = value;
+                                    true
+                                }
+                                _ => false,
+                            },
+                        )*
+                        _ => false,
+                    }
+                }
             }
         } else {
             quote!()
@@ -150,6 +175,13 @@ pub fn reflect_derive(ast: &DeriveInput) -> proc_macro2::TokenStream {
 
                 #field_methods
 
+                fn remove_component(&self, world: &mut unreal_api::World, entity: unreal_api::Entity) -> bool {
+                    world
+                        .get_entity_mut(entity)
+                        .and_then(|mut entity_mut| entity_mut.remove::<#self_ty>())
+                        .is_some()
+                }
+
                 fn get_value(&self) -> unreal_api::registry::ReflectValue {
                     unreal_api::registry::ReflectValue::Composite
                 }
@@ -1,21 +1,67 @@
 use std::collections::HashMap;
 
 use crate::ffi;
-use glam::{Quat, Vec3};
+use glam::{Quat, Vec2, Vec3};
 
-use crate::core::ActorPtr;
-use crate::ecs::entity::Entity;
+use crate::core::{ActorAttachComponent, ActorPtr, PreviousTransform, TransformComponent};
+use crate::ecs::{
+    entity::Entity,
+    system::{Query, Res, SystemParam},
+    world::World,
+};
 use crate::module::bindings;
-use crate::physics::CollisionShape;
+use crate::name::FName;
+use crate::physics::{CollisionChannel, CollisionShape, PhysicsComponent};
+use crate::spatial::SpatialHash;
 
 #[derive(Default)]
 pub struct UnrealApi {
-    // TODO: Implement unregister.
     pub actor_to_entity: HashMap<ActorPtr, Entity>,
     pub entity_to_actor: HashMap<Entity, ActorPtr>,
 }
 
-#[derive(Default)]
+/// Queries over the positions of all entities with a `TransformComponent`. Uses the
+/// `SpatialHash` to find candidates if it was inserted.
+#[derive(SystemParam)]
+pub struct SpatialQueries<'w, 's> {
+    transforms: Query<'w, 's, (Entity, &'static TransformComponent)>,
+    spatial_hash: Option<Res<'w, SpatialHash>>,
+}
+
+impl<'w, 's> SpatialQueries<'w, 's> {
+    /// The closest entity within `max_radius` of `origin` for which `filter` returns true, and
+    /// its distance. `exclude` is never returned, e.g. to skip the entity that is searching.
+    pub fn nearest(
+        &self,
+        origin: Vec3,
+        max_radius: f32,
+        exclude: Option<Entity>,
+        filter: impl Fn(Entity) -> bool,
+    ) -> Option<(Entity, f32)> {
+        let max_distance_squared = max_radius * max_radius;
+        let candidates: Box<dyn Iterator<Item = (Entity, &TransformComponent)>> =
+            match &self.spatial_hash {
+                // Entities that moved into range during this frame are only found in the next
+                // frame, but the distance is always computed from the current position
+                Some(spatial_hash) => Box::new(
+                    spatial_hash
+                        .query_radius(origin, max_radius)
+                        .filter_map(|entity| self.transforms.get(entity).ok()),
+                ),
+                None => Box::new(self.transforms.iter()),
+            };
+        candidates
+            .filter(|&(entity, _)| Some(entity) != exclude)
+            .map(|(entity, transform)| (entity, transform.position.distance_squared(origin)))
+            .filter(|&(entity, distance_squared)| {
+                distance_squared <= max_distance_squared && filter(entity)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, distance_squared)| (entity, distance_squared.sqrt()))
+    }
+}
+
+#[derive(Default, Clone)]
 pub struct SweepParams {
     pub ignored_entities: Vec<Entity>,
 }
@@ -29,8 +75,8 @@ impl SweepParams {
 
 #[derive(Debug)]
 pub struct SweepHit {
-    /// The entity that was hit
-    pub entity: Entity,
+    /// The entity that was hit. `None` if the actor that was hit is not known to Rust.
+    pub entity: Option<Entity>,
     /// Location in world space of the actual contact of the trace shape (box, sphere, ray, etc) with the impacted object.
     pub impact_location: Vec3,
     /// Normal of the hit in world space, for the object that was hit by the sweep, if any
@@ -41,7 +87,9 @@ pub struct SweepHit {
     pub location: Vec3,
     /// Normal of the hit in world space, for the object that was swept.
     pub normal: Vec3,
-    pub start_in_penentration: bool,
+    pub started_penetrating: bool,
+    /// Time of impact along the sweep in `[0, 1]`. Always 0 if the sweep `started_penetrating`.
+    pub time: f32,
 }
 
 #[derive(Default)]
@@ -56,19 +104,180 @@ impl LineTraceParams {
     }
 }
 
+#[derive(Default)]
+pub struct TraceParams {
+    pub ignored_entities: Vec<Entity>,
+    pub channel: CollisionChannel,
+}
+
+impl TraceParams {
+    pub fn add_ignored_entity(mut self, entity: Entity) -> Self {
+        self.ignored_entities.push(entity);
+        self
+    }
+
+    pub fn with_channel(mut self, channel: CollisionChannel) -> Self {
+        self.channel = channel;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct RayHit {
+    /// The entity that was hit. `None` if the actor that was hit is not known to Rust.
+    pub actor: Option<Entity>,
+    /// Location in world space where the ray hit the object
+    pub impact_point: Vec3,
+    /// Normal of the hit in world space
+    pub impact_normal: Vec3,
+    /// Distance from `start` to the `impact_point`
+    pub distance: f32,
+}
+
 #[derive(Debug)]
 pub struct LineTraceHit {
-    /// The entity that was hit
-    pub entity: Entity,
+    /// The entity that was hit. `None` if the actor that was hit is not known to Rust.
+    pub entity: Option<Entity>,
     pub location: Vec3,
     pub normal: Vec3,
 }
 
+/// Looks up the entity that was registered for `actor`. Returns `None` for null pointers and
+/// for actors that are not known to Rust.
+pub fn entity_for_actor(world: &World, actor: *mut ffi::AActorOpaque) -> Option<Entity> {
+    world.get_resource::<UnrealApi>()?.entity(actor)
+}
+
+/// Projects `world` onto the viewport of the first local player, in pixels. `None` if the point
+/// is behind the camera.
+pub fn project_to_screen(world: Vec3) -> Option<Vec2> {
+    let mut screen = ffi::Vector2::default();
+    let visible = unsafe { (bindings().project_world_to_screen)(world.into(), &mut screen) };
+    (visible == 1).then(|| screen.into())
+}
+
+/// The ray that goes from the camera of the first local player through the pixel `screen`, as
+/// origin and normalized direction. Useful for clicking on things in the world.
+pub fn deproject_screen_to_world(screen: Vec2) -> Option<(Vec3, Vec3)> {
+    let mut origin = ffi::Vector3::default();
+    let mut direction = ffi::Vector3::default();
+    let found = unsafe {
+        (bindings().deproject_screen_to_world)(screen.into(), &mut origin, &mut direction)
+    };
+    (found == 1).then(|| (origin.into(), direction.into()))
+}
+
+/// The reverse of `entity_for_actor`
+pub fn actor_for_entity(world: &World, entity: Entity) -> Option<ActorPtr> {
+    world.get_resource::<UnrealApi>()?.actor(entity)
+}
+
+/// Moves `entity` and its actor right away, unlike setting the `TransformComponent`, which is
+/// only uploaded in `CoreStage::PostUpdate` and treated as regular movement. The actor is not
+/// swept, the `PreviousTransform` snaps to the target so nothing is interpolated, and the
+/// velocity of the `PhysicsComponent` is zeroed. Velocities of gameplay components, like the
+/// `MovementComponent`, are up to the caller. Returns false if the entity does not exist or has
+/// no `TransformComponent`.
+pub fn teleport(world: &mut World, entity: Entity, position: Vec3, rotation: Quat) -> bool {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return false;
+    };
+    let transform = match entity_mut.get_mut::<TransformComponent>() {
+        Some(mut transform) => {
+            transform.position = position;
+            transform.rotation = rotation;
+            transform.clone()
+        }
+        None => return false,
+    };
+    if let Some(mut previous) = entity_mut.get_mut::<PreviousTransform>() {
+        previous.0 = transform;
+    }
+    if let Some(mut physics) = entity_mut.get_mut::<PhysicsComponent>() {
+        physics.set_velocity(Vec3::ZERO);
+        physics.set_angular_velocity(Vec3::ZERO);
+    }
+    if let Some(actor) = actor_for_entity(world, entity) {
+        actor.teleport(position, rotation);
+    }
+    true
+}
+
+/// Attaches the actor of `child` to the actor of `parent` in unreal, optionally at a socket of
+/// the parent, and inserts an `ActorAttachComponent` on `child`. A child that is already attached
+/// is detached first. Returns false if either entity has no actor.
+pub fn attach_actor(
+    world: &mut World,
+    child: Entity,
+    parent: Entity,
+    socket: Option<FName>,
+) -> bool {
+    let (Some(child_actor), Some(parent_actor)) = (
+        actor_for_entity(world, child),
+        actor_for_entity(world, parent),
+    ) else {
+        log::warn!(
+            "Unable to attach {:?} to {:?}, both need an actor",
+            child,
+            parent
+        );
+        return false;
+    };
+    detach_actor(world, child);
+    child_actor.attach_to(parent_actor, socket.as_ref());
+    world
+        .entity_mut(child)
+        .insert(ActorAttachComponent { parent, socket });
+    true
+}
+
+/// Detaches the actor of `child` from its parent in unreal and removes the
+/// `ActorAttachComponent`. Returns false if `child` was not attached with `attach_actor`.
+pub fn detach_actor(world: &mut World, child: Entity) -> bool {
+    let attached = world
+        .get_entity_mut(child)
+        .and_then(|mut entity| entity.remove::<ActorAttachComponent>())
+        .is_some();
+    if attached {
+        if let Some(actor) = actor_for_entity(world, child) {
+            actor.detach();
+        }
+    }
+    attached
+}
+
 impl UnrealApi {
+    pub fn entity(&self, actor: *mut ffi::AActorOpaque) -> Option<Entity> {
+        if actor.is_null() {
+            return None;
+        }
+        self.actor_to_entity.get(&ActorPtr(actor)).copied()
+    }
+
+    pub fn actor(&self, entity: Entity) -> Option<ActorPtr> {
+        self.entity_to_actor.get(&entity).copied()
+    }
+
     pub fn register_actor(&mut self, actor: ActorPtr, entity: Entity) {
         self.actor_to_entity.insert(actor, entity);
         self.entity_to_actor.insert(entity, actor);
     }
+
+    /// Removes the mapping for `actor` and returns the entity it was registered to. Returns
+    /// `None` if the actor was never registered.
+    pub fn unregister_actor(&mut self, actor: &ActorPtr) -> Option<Entity> {
+        let entity = self.actor_to_entity.remove(actor)?;
+        self.entity_to_actor.remove(&entity);
+        Some(entity)
+    }
+
+    /// Removes the mapping for `entity` and returns the actor it was registered to. Returns
+    /// `None` if the entity has no actor.
+    pub fn unregister_entity(&mut self, entity: &Entity) -> Option<ActorPtr> {
+        let actor = self.entity_to_actor.remove(entity)?;
+        self.actor_to_entity.remove(&actor);
+        Some(actor)
+    }
     pub fn sweep(
         &self,
         start: Vec3,
@@ -98,20 +307,66 @@ impl UnrealApi {
                 &mut hit,
             ) == 1
             {
-                let entity = self
-                    .actor_to_entity
-                    .get(&ActorPtr(hit.actor))
-                    .copied()
-                    .expect("We hit an unknown actor. Please create an issue.");
+                Some(SweepHit {
+                    entity: self.entity(hit.actor),
+                    impact_location: hit.impact_location.into(),
+                    location: hit.location.into(),
+                    normal: hit.normal.into(),
+                    penetration_depth: hit.pentration_depth,
+                    started_penetrating: hit.start_penetrating == 1,
+                    impact_normal: hit.impact_normal.into(),
+                    time: hit.time,
+                })
+            } else {
+                None
+            }
+        }
+    }
 
+    /// Sweeps an upright capsule from `start` to `end` against `params.channel`.
+    pub fn sweep_capsule(
+        &self,
+        start: Vec3,
+        end: Vec3,
+        half_height: f32,
+        radius: f32,
+        params: TraceParams,
+    ) -> Option<SweepHit> {
+        let ignored_actors: Vec<_> = params
+            .ignored_entities
+            .iter()
+            .filter_map(|entity| self.entity_to_actor.get(entity))
+            .map(|actor| actor.0)
+            .collect();
+        let params = ffi::TraceParams {
+            ignored_actors: ignored_actors.as_ptr(),
+            ignored_actors_len: ignored_actors.len(),
+            channel: params.channel,
+        };
+        let shape = CollisionShape::Capsule {
+            half_height,
+            radius,
+        };
+        let mut hit = ffi::HitResult::default();
+        unsafe {
+            if (bindings().physics_fns.sweep_by_channel)(
+                start.into(),
+                end.into(),
+                Quat::IDENTITY.into(),
+                params,
+                shape.into(),
+                &mut hit,
+            ) == 1
+            {
                 Some(SweepHit {
-                    entity,
+                    entity: self.entity(hit.actor),
                     impact_location: hit.impact_location.into(),
                     location: hit.location.into(),
                     normal: hit.normal.into(),
                     penetration_depth: hit.pentration_depth,
-                    start_in_penentration: hit.start_penetrating == 1,
+                    started_penetrating: hit.start_penetrating == 1,
                     impact_normal: hit.impact_normal.into(),
+                    time: hit.time,
                 })
             } else {
                 None
@@ -119,6 +374,120 @@ impl UnrealApi {
         }
     }
 
+    /// Every entity with a primitive that overlaps `shape` on `params.channel`, each entity only
+    /// once. Overlapping actors that are not known to Rust are skipped.
+    pub fn overlap(
+        &self,
+        position: Vec3,
+        rotation: Quat,
+        shape: CollisionShape,
+        params: TraceParams,
+    ) -> Vec<Entity> {
+        const MAX_OVERLAPS: usize = 256;
+        let ignored_actors: Vec<_> = params
+            .ignored_entities
+            .iter()
+            .filter_map(|entity| self.entity_to_actor.get(entity))
+            .map(|actor| actor.0)
+            .collect();
+        let params = ffi::TraceParams {
+            ignored_actors: ignored_actors.as_ptr(),
+            ignored_actors_len: ignored_actors.len(),
+            channel: params.channel,
+        };
+        let mut overlaps: Vec<ffi::OverlapResult> = Vec::with_capacity(MAX_OVERLAPS);
+        unsafe {
+            let len = (bindings().physics_fns.overlap_multi)(
+                shape.into(),
+                position.into(),
+                rotation.into(),
+                params,
+                MAX_OVERLAPS,
+                overlaps.as_mut_ptr(),
+            );
+            overlaps.set_len(len.min(MAX_OVERLAPS));
+        }
+        let mut entities: Vec<Entity> = Vec::with_capacity(overlaps.len());
+        for overlap in overlaps {
+            if let Some(entity) = self.entity(overlap.actor) {
+                if !entities.contains(&entity) {
+                    entities.push(entity);
+                }
+            }
+        }
+        entities
+    }
+
+    pub fn overlap_sphere(&self, center: Vec3, radius: f32, params: TraceParams) -> Vec<Entity> {
+        self.overlap(
+            center,
+            Quat::IDENTITY,
+            CollisionShape::Sphere { radius },
+            params,
+        )
+    }
+
+    /// `extent` is the half size of the box
+    pub fn overlap_box(
+        &self,
+        center: Vec3,
+        extent: Vec3,
+        rotation: Quat,
+        params: TraceParams,
+    ) -> Vec<Entity> {
+        self.overlap(
+            center,
+            rotation,
+            CollisionShape::Box {
+                half_extent: extent,
+            },
+            params,
+        )
+    }
+
+    pub fn raycast(&self, start: Vec3, end: Vec3, params: TraceParams) -> Option<RayHit> {
+        let ignored_actors: Vec<_> = params
+            .ignored_entities
+            .iter()
+            .filter_map(|entity| self.entity_to_actor.get(entity))
+            .map(|actor| actor.0)
+            .collect();
+        let params = ffi::TraceParams {
+            ignored_actors: ignored_actors.as_ptr(),
+            ignored_actors_len: ignored_actors.len(),
+            channel: params.channel,
+        };
+        let mut hit = ffi::HitResult::default();
+        unsafe {
+            if (bindings().physics_fns.raycast)(start.into(), end.into(), params, &mut hit) == 1 {
+                Some(RayHit {
+                    actor: self.entity(hit.actor),
+                    impact_point: hit.impact_location.into(),
+                    impact_normal: hit.impact_normal.into(),
+                    distance: hit.distance,
+                })
+            } else {
+                None
+            }
+        }
+    }
+
+    /// True if a ray from the actor of `from` to the actor of `to` is not blocked, or only by
+    /// the actor of `to` itself. The actor of `from` is always ignored. False if either entity
+    /// has no actor.
+    pub fn has_line_of_sight(&self, from: Entity, to: Entity, mut params: TraceParams) -> bool {
+        let (Some(from_actor), Some(to_actor)) = (self.actor(from), self.actor(to)) else {
+            return false;
+        };
+        params.ignored_entities.push(from);
+        let start = from_actor.get_transform().position;
+        let end = to_actor.get_transform().position;
+        match self.raycast(start, end, params) {
+            Some(hit) => hit.actor == Some(to),
+            None => true,
+        }
+    }
+
     pub fn line_trace(
         &self,
         start: Vec3,
@@ -139,13 +508,8 @@ impl UnrealApi {
         unsafe {
             if (bindings().physics_fns.line_trace)(start.into(), end.into(), params, &mut hit) == 1
             {
-                let entity = self
-                    .actor_to_entity
-                    .get(&ActorPtr(hit.actor))
-                    .copied()
-                    .expect("We hit an unknown actor. Please create an issue.");
                 Some(LineTraceHit {
-                    entity,
+                    entity: self.entity(hit.actor),
                     location: hit.location.into(),
                     normal: hit.normal.into(),
                 })
@@ -155,3 +519,383 @@ impl UnrealApi {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::system::SystemState;
+    use crate::testing;
+
+    fn hit(actor: *mut ffi::AActorOpaque, location: Vec3, normal: Vec3) -> ffi::HitResult {
+        ffi::HitResult {
+            actor,
+            distance: location.length(),
+            location: location.into(),
+            normal: normal.into(),
+            impact_location: location.into(),
+            impact_normal: normal.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn raycast_resolves_entity() {
+        testing::install();
+        let actor = testing::spawn_actor();
+        let entity = Entity::from_raw(7);
+        let mut api = UnrealApi::default();
+        api.register_actor(ActorPtr(actor), entity);
+        testing::with(|unreal| {
+            unreal.raycast = Some(Box::new(move |_, _| {
+                Some(hit(actor, Vec3::new(0.0, 0.0, 50.0), Vec3::Z))
+            }))
+        });
+
+        let ray = api
+            .raycast(Vec3::ZERO, Vec3::Z * 100.0, TraceParams::default())
+            .unwrap();
+        assert_eq!(ray.actor, Some(entity));
+        assert_eq!(ray.impact_point, Vec3::new(0.0, 0.0, 50.0));
+        assert_eq!(ray.impact_normal, Vec3::Z);
+        assert_eq!(ray.distance, 50.0);
+    }
+
+    #[test]
+    fn raycast_without_hit() {
+        testing::install();
+        let api = UnrealApi::default();
+        assert!(api
+            .raycast(Vec3::ZERO, Vec3::Z, TraceParams::default())
+            .is_none());
+    }
+
+    #[test]
+    fn raycast_unknown_actor() {
+        testing::install();
+        let actor = testing::spawn_actor();
+        testing::with(|unreal| {
+            unreal.raycast = Some(Box::new(move |_, _| Some(hit(actor, Vec3::X, Vec3::X))))
+        });
+        let ray = UnrealApi::default()
+            .raycast(Vec3::ZERO, Vec3::X * 2.0, TraceParams::default())
+            .unwrap();
+        assert_eq!(ray.actor, None);
+    }
+
+    #[test]
+    fn sweep_capsule_hits() {
+        testing::install();
+        let actor = testing::spawn_actor();
+        let entity = Entity::from_raw(3);
+        let mut api = UnrealApi::default();
+        api.register_actor(ActorPtr(actor), entity);
+
+        // The capsule starts inside of the actor
+        testing::with(|unreal| {
+            unreal.sweep = Some(Box::new(move |_, _| {
+                Some(ffi::HitResult {
+                    start_penetrating: 1,
+                    pentration_depth: 4.0,
+                    time: 0.0,
+                    ..hit(actor, Vec3::ZERO, Vec3::X)
+                })
+            }))
+        });
+        let overlap = api
+            .sweep_capsule(
+                Vec3::ZERO,
+                Vec3::X * 100.0,
+                90.0,
+                40.0,
+                TraceParams::default(),
+            )
+            .unwrap();
+        assert!(overlap.started_penetrating);
+        assert_eq!(overlap.penetration_depth, 4.0);
+        assert_eq!(overlap.entity, Some(entity));
+
+        testing::with(|unreal| {
+            unreal.sweep = Some(Box::new(move |_, _| {
+                Some(ffi::HitResult {
+                    time: 0.5,
+                    ..hit(actor, Vec3::X * 50.0, -Vec3::X)
+                })
+            }))
+        });
+        let hit = api
+            .sweep_capsule(
+                Vec3::ZERO,
+                Vec3::X * 100.0,
+                90.0,
+                40.0,
+                TraceParams::default(),
+            )
+            .unwrap();
+        assert!(!hit.started_penetrating);
+        assert_eq!(hit.time, 0.5);
+        assert_eq!(hit.impact_location, Vec3::X * 50.0);
+        assert_eq!(hit.impact_normal, -Vec3::X);
+    }
+
+    #[test]
+    fn sweep_capsule_unknown_actor() {
+        testing::install();
+        let actor = testing::spawn_actor();
+        testing::with(|unreal| {
+            unreal.sweep = Some(Box::new(move |_, _| Some(hit(actor, Vec3::X, -Vec3::X))))
+        });
+        let hit = UnrealApi::default()
+            .sweep_capsule(
+                Vec3::ZERO,
+                Vec3::X * 2.0,
+                90.0,
+                40.0,
+                TraceParams::default(),
+            )
+            .unwrap();
+        assert_eq!(hit.entity, None);
+    }
+
+    #[test]
+    fn entity_for_actor_lookup() {
+        let mut world = World::new();
+        let actor = 0x10 as *mut ffi::AActorOpaque;
+        // No `UnrealApi` yet
+        assert_eq!(entity_for_actor(&world, actor), None);
+
+        world.insert_resource(UnrealApi::default());
+        let entity = world.spawn().id();
+        world
+            .resource_mut::<UnrealApi>()
+            .register_actor(ActorPtr(actor), entity);
+        assert_eq!(entity_for_actor(&world, actor), Some(entity));
+        assert_eq!(actor_for_entity(&world, entity), Some(ActorPtr(actor)));
+        assert_eq!(entity_for_actor(&world, std::ptr::null_mut()), None);
+
+        world
+            .resource_mut::<UnrealApi>()
+            .unregister_actor(&ActorPtr(actor));
+        assert_eq!(entity_for_actor(&world, actor), None);
+        assert_eq!(actor_for_entity(&world, entity), None);
+    }
+
+    #[test]
+    fn nearest_entity() {
+        let mut world = World::new();
+        let entities: Vec<_> = [1.0, 3.0, 6.0, -2.0]
+            .into_iter()
+            .map(|x| {
+                world
+                    .spawn()
+                    .insert(TransformComponent {
+                        position: Vec3::X * x,
+                        ..Default::default()
+                    })
+                    .id()
+            })
+            .collect();
+        let mut state = SystemState::<SpatialQueries>::new(&mut world);
+        let queries = state.get(&world);
+
+        assert_eq!(
+            queries.nearest(Vec3::ZERO, 5.0, None, |_| true),
+            Some((entities[0], 1.0))
+        );
+        assert_eq!(
+            queries.nearest(Vec3::ZERO, 5.0, Some(entities[0]), |_| true),
+            Some((entities[3], 2.0))
+        );
+        assert_eq!(
+            queries.nearest(Vec3::ZERO, 5.0, Some(entities[0]), |e| e != entities[3]),
+            Some((entities[1], 3.0))
+        );
+        assert_eq!(queries.nearest(Vec3::X * 6.5, 0.4, None, |_| true), None);
+    }
+
+    #[test]
+    fn projection_behind_the_camera() {
+        testing::install();
+        // A camera at the origin that looks along X
+        testing::with(|unreal| {
+            unreal.project = Some(Box::new(|world: Vec3| {
+                (world.x > 0.0).then(|| Vec2::new(world.y, world.z) / world.x)
+            }))
+        });
+        assert_eq!(
+            project_to_screen(Vec3::new(2.0, 4.0, -2.0)),
+            Some(Vec2::new(2.0, -1.0))
+        );
+        assert_eq!(project_to_screen(Vec3::new(-2.0, 4.0, -2.0)), None);
+    }
+
+    #[test]
+    fn overlaps_are_resolved_and_deduplicated() {
+        testing::install();
+        let first = testing::spawn_actor();
+        let second = testing::spawn_actor();
+        let unknown = testing::spawn_actor();
+        let mut api = UnrealApi::default();
+        api.register_actor(ActorPtr(first), Entity::from_raw(1));
+        api.register_actor(ActorPtr(second), Entity::from_raw(2));
+        // An actor shows up once for every overlapping primitive
+        testing::with(|unreal| unreal.overlaps = vec![first, unknown, second, first]);
+
+        let expected = vec![Entity::from_raw(1), Entity::from_raw(2)];
+        assert_eq!(
+            api.overlap_sphere(Vec3::ZERO, 100.0, TraceParams::default()),
+            expected
+        );
+        assert_eq!(
+            api.overlap_box(
+                Vec3::ZERO,
+                Vec3::ONE,
+                Quat::IDENTITY,
+                TraceParams::default()
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn attach_resolves_both_actors() {
+        testing::install();
+        let mut world = World::new();
+        let mut api = UnrealApi::default();
+        let weapon_actor = testing::spawn_actor();
+        let character_actor = testing::spawn_actor();
+        let weapon = world.spawn().id();
+        let character = world.spawn().id();
+        let unregistered = world.spawn().id();
+        api.register_actor(ActorPtr(weapon_actor as _), weapon);
+        api.register_actor(ActorPtr(character_actor as _), character);
+        world.insert_resource(api);
+
+        assert!(attach_actor(
+            &mut world,
+            weapon,
+            character,
+            Some(FName::from("api_test_hand_r"))
+        ));
+        assert_eq!(
+            testing::take_calls(),
+            vec![testing::Call::AttachToActor {
+                child: weapon_actor,
+                parent: character_actor,
+                socket: Some("api_test_hand_r".into()),
+            }]
+        );
+        let attach = world.get::<ActorAttachComponent>(weapon).unwrap();
+        assert_eq!(attach.parent, character);
+
+        // Attaching again detaches first
+        assert!(attach_actor(&mut world, weapon, character, None));
+        assert_eq!(
+            testing::take_calls(),
+            vec![
+                testing::Call::DetachFromActor {
+                    actor: weapon_actor
+                },
+                testing::Call::AttachToActor {
+                    child: weapon_actor,
+                    parent: character_actor,
+                    socket: None,
+                },
+            ]
+        );
+
+        assert!(!attach_actor(&mut world, weapon, unregistered, None));
+        assert!(detach_actor(&mut world, weapon));
+        assert!(!detach_actor(&mut world, weapon));
+        assert_eq!(
+            testing::take_calls(),
+            vec![testing::Call::DetachFromActor {
+                actor: weapon_actor
+            }]
+        );
+    }
+
+    #[test]
+    fn line_of_sight_blocked_and_clear() {
+        testing::install();
+        let mut world = World::new();
+        let mut api = UnrealApi::default();
+        let (guard_actor, player_actor, wall) = testing::with(|unreal| {
+            let guard = unreal.spawn_actor(Default::default());
+            let player = unreal.spawn_actor(Default::default());
+            unreal.actor_mut(player).position = Vec3::X * 100.0;
+            (guard, player, unreal.new_ptr())
+        });
+        let guard = world.spawn().id();
+        let player = world.spawn().id();
+        api.register_actor(ActorPtr(guard_actor as _), guard);
+        api.register_actor(ActorPtr(player_actor as _), player);
+
+        testing::with(|unreal| {
+            unreal.raycast = Some(Box::new(move |start, end| {
+                assert_eq!((start, end), (Vec3::ZERO, Vec3::X * 100.0));
+                Some(hit(wall as _, Vec3::X * 50.0, -Vec3::X))
+            }))
+        });
+        assert!(!api.has_line_of_sight(guard, player, TraceParams::default()));
+
+        // Hitting the target itself does not block
+        testing::with(|unreal| {
+            unreal.raycast = Some(Box::new(move |_, _| {
+                Some(hit(player_actor as _, Vec3::X * 100.0, -Vec3::X))
+            }))
+        });
+        assert!(api.has_line_of_sight(guard, player, TraceParams::default()));
+
+        testing::with(|unreal| unreal.raycast = None);
+        assert!(api.has_line_of_sight(guard, player, TraceParams::default()));
+        let nobody = world.spawn().id();
+        assert!(!api.has_line_of_sight(guard, nobody, TraceParams::default()));
+    }
+
+    #[test]
+    fn teleport_resets_velocity_and_interpolation() {
+        testing::install();
+        let mut world = World::new();
+        let mut api = UnrealApi::default();
+        let (actor, primitive) = testing::spawn_physics_actor();
+        let mut physics = PhysicsComponent::new(crate::core::UnrealPtr::from_raw(primitive));
+        physics.set_velocity(Vec3::X * 500.0);
+        physics.set_angular_velocity(Vec3::Z);
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                TransformComponent::default(),
+                PreviousTransform(TransformComponent::default()),
+                physics,
+            ))
+            .id();
+        api.register_actor(ActorPtr(actor as _), entity);
+        world.insert_resource(api);
+        testing::take_calls();
+
+        let position = Vec3::new(100.0, 200.0, 300.0);
+        let rotation = Quat::from_rotation_z(1.0);
+        assert!(teleport(&mut world, entity, position, rotation));
+
+        let transform = world.get::<TransformComponent>(entity).unwrap();
+        assert_eq!(transform.position, position);
+        assert_eq!(transform.rotation, rotation);
+        let previous = world.get::<PreviousTransform>(entity).unwrap();
+        assert_eq!(previous.0.position, position);
+        assert_eq!(previous.0.rotation, rotation);
+        let physics = world.get::<PhysicsComponent>(entity).unwrap();
+        assert_eq!(physics.velocity(), Vec3::ZERO);
+        assert_eq!(physics.angular_velocity(), Vec3::ZERO);
+        assert_eq!(
+            testing::take_calls(),
+            vec![testing::Call::SetActorLocationTeleport {
+                actor,
+                position,
+                rotation,
+            }]
+        );
+
+        let missing = world.spawn().id();
+        assert!(!teleport(&mut world, missing, position, rotation));
+    }
+}
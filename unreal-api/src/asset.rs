@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use unreal_reflect::registry::UClass;
+
+use crate::{ffi, module::bindings};
+
+/// An unreal actor class that was loaded by `AssetRegistry::register_class`. Spawn it with
+/// `SpawnActor`.
+#[derive(Debug, Copy, Clone)]
+pub struct ClassHandle(UClass);
+
+impl ClassHandle {
+    pub fn class(&self) -> UClass {
+        self.0
+    }
+}
+
+/// A float curve, e.g. a damage falloff authored as a `UCurveFloat` in unreal. Loaded with
+/// `AssetRegistry::register_curve`.
+#[derive(Debug, Clone, Default)]
+pub struct Curve {
+    /// `(time, value)`, sorted by time
+    keys: Vec<(f32, f32)>,
+}
+
+impl Curve {
+    pub fn new(mut keys: Vec<(f32, f32)>) -> Self {
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keys }
+    }
+
+    pub fn keys(&self) -> &[(f32, f32)] {
+        &self.keys
+    }
+
+    /// Interpolates linearly between the keys. Before the first and after the last key the
+    /// value of that key is returned, a curve without keys is always 0.
+    pub fn evaluate(&self, time: f32) -> f32 {
+        let next = self.keys.partition_point(|&(key_time, _)| key_time <= time);
+        match (
+            next.checked_sub(1).map(|i| self.keys[i]),
+            self.keys.get(next),
+        ) {
+            (Some((t0, v0)), Some(&(t1, v1))) => v0 + (v1 - v0) * (time - t0) / (t1 - t0),
+            (Some((_, value)), None) | (None, Some(&(_, value))) => value,
+            (None, None) => 0.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AssetError {
+    /// There is no actor class at this path
+    ClassNotFound(String),
+    /// There is no float curve at this path
+    CurveNotFound(String),
+}
+
+impl std::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetError::ClassNotFound(path) => write!(f, "Unable to load the actor class {}", path),
+            AssetError::CurveNotFound(path) => write!(f, "Unable to load the float curve {}", path),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+/// Actor classes and curves loaded by path, so spawning never has to look up a path
+#[derive(Default, Debug)]
+pub struct AssetRegistry {
+    classes: HashMap<String, ClassHandle>,
+    curves: HashMap<String, Curve>,
+}
+
+impl AssetRegistry {
+    /// Loads the class at `path`, e.g. `"/Game/BP_Enemy.BP_Enemy_C"`. Registering the same path
+    /// again returns the same handle.
+    pub fn register_class(&mut self, path: &str) -> Result<ClassHandle, AssetError> {
+        if let Some(handle) = self.classes.get(path) {
+            return Ok(*handle);
+        }
+        let ptr = unsafe { (bindings().load_class)(path.into()) };
+        if ptr.is_null() {
+            return Err(AssetError::ClassNotFound(path.to_string()));
+        }
+        let handle = ClassHandle(UClass { ptr });
+        self.classes.insert(path.to_string(), handle);
+        Ok(handle)
+    }
+
+    pub fn class(&self, path: &str) -> Option<ClassHandle> {
+        self.classes.get(path).copied()
+    }
+
+    /// Downloads the keys of the `UCurveFloat` at `path` once. Later changes to the asset are not
+    /// picked up.
+    pub fn register_curve(&mut self, path: &str) -> Result<&Curve, AssetError> {
+        if !self.curves.contains_key(path) {
+            let curve =
+                load_curve(path).ok_or_else(|| AssetError::CurveNotFound(path.to_string()))?;
+            self.curves.insert(path.to_string(), curve);
+        }
+        Ok(&self.curves[path])
+    }
+
+    pub fn curve(&self, path: &str) -> Option<&Curve> {
+        self.curves.get(path)
+    }
+}
+
+fn load_curve(path: &str) -> Option<Curve> {
+    unsafe {
+        let mut len = 0;
+        if (bindings().load_curve_keys)(path.into(), std::ptr::null_mut(), &mut len) == 0 {
+            return None;
+        }
+        let mut keys = vec![ffi::CurveKey::default(); len];
+        (bindings().load_curve_keys)(path.into(), keys.as_mut_ptr(), &mut len);
+        keys.truncate(len);
+        Some(Curve::new(
+            keys.into_iter().map(|key| (key.time, key.value)).collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn class_handles_are_resolved_once() {
+        testing::install();
+        let ptr = testing::with(|unreal| {
+            let ptr = unreal.new_ptr() as *mut ffi::UClassOpague;
+            unreal
+                .classes
+                .insert("/Game/BP_Enemy.BP_Enemy_C".into(), ptr);
+            ptr
+        });
+        let mut registry = AssetRegistry::default();
+
+        let handle = registry
+            .register_class("/Game/BP_Enemy.BP_Enemy_C")
+            .unwrap();
+        assert_eq!(handle.class().ptr, ptr);
+        testing::with(|unreal| unreal.classes.clear());
+        let again = registry
+            .register_class("/Game/BP_Enemy.BP_Enemy_C")
+            .unwrap();
+        assert_eq!(again.class().ptr, ptr);
+        assert_eq!(
+            registry
+                .class("/Game/BP_Enemy.BP_Enemy_C")
+                .map(|h| h.class().ptr),
+            Some(ptr)
+        );
+    }
+
+    #[test]
+    fn unknown_class_path_is_an_error() {
+        testing::install();
+        let mut registry = AssetRegistry::default();
+
+        let err = registry
+            .register_class("/Game/Missing.Missing_C")
+            .unwrap_err();
+        assert!(
+            matches!(err, AssetError::ClassNotFound(ref path) if path == "/Game/Missing.Missing_C")
+        );
+        assert!(registry.class("/Game/Missing.Missing_C").is_none());
+    }
+
+    #[test]
+    fn curves_interpolate_between_keys() {
+        let curve = Curve::new(vec![(1.0, 10.0), (0.0, 0.0), (3.0, -10.0)]);
+        assert_eq!(curve.keys(), &[(0.0, 0.0), (1.0, 10.0), (3.0, -10.0)]);
+        assert_eq!(curve.evaluate(0.5), 5.0);
+        assert_eq!(curve.evaluate(1.0), 10.0);
+        assert_eq!(curve.evaluate(2.0), 0.0);
+        // Clamped to the first and last key
+        assert_eq!(curve.evaluate(-5.0), 0.0);
+        assert_eq!(curve.evaluate(100.0), -10.0);
+        assert_eq!(Curve::default().evaluate(1.0), 0.0);
+        assert_eq!(Curve::new(vec![(2.0, 4.0)]).evaluate(0.0), 4.0);
+    }
+
+    #[test]
+    fn curves_are_loaded_from_unreal() {
+        testing::install();
+        testing::with(|unreal| {
+            unreal.curves.insert(
+                "/Game/Falloff.Falloff".into(),
+                vec![
+                    ffi::CurveKey {
+                        time: 0.0,
+                        value: 1.0,
+                    },
+                    ffi::CurveKey {
+                        time: 2.0,
+                        value: 0.0,
+                    },
+                ],
+            )
+        });
+        let mut registry = AssetRegistry::default();
+
+        let curve = registry.register_curve("/Game/Falloff.Falloff").unwrap();
+        assert_eq!(curve.evaluate(1.5), 0.25);
+        assert!(matches!(
+            registry.register_curve("/Game/Missing.Missing"),
+            Err(AssetError::CurveNotFound(_))
+        ));
+        assert!(registry.curve("/Game/Falloff.Falloff").is_some());
+    }
+}
@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+
+use crate::{ffi_string::FfiStringBuf, module::bindings};
+
+/// Tuning values like gravity or the mouse sensitivity, read from a TOML document. It is inserted
+/// in `begin_play` before any startup system runs, from `Config/RustGame.toml` in the project
+/// directory. Without that file the config is empty, so every lookup falls back to its default.
+///
+/// ```toml
+/// gravity = -981.0
+///
+/// [movement]
+/// max_speed = 600.0
+/// ```
+#[derive(Default, Debug, Clone)]
+pub struct GameConfig {
+    values: toml::value::Table,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "Unable to read the config: {}", err),
+            ConfigError::Parse(err) => write!(f, "Unable to parse the config: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl GameConfig {
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        let values = toml::from_str(source).map_err(ConfigError::Parse)?;
+        Ok(Self { values })
+    }
+
+    /// The config that unreal provides, an empty config if there is none or it is broken
+    pub(crate) fn download() -> Self {
+        let config = FfiStringBuf::read(|data| unsafe { (bindings().get_config_blob)(data) })
+            .map_err(|err| err.to_string())
+            .and_then(|source| Self::from_toml(&source).map_err(|err| err.to_string()));
+        config.unwrap_or_else(|err| {
+            log::error!("{}", err);
+            Self::default()
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let source = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        Self::from_toml(&source)
+    }
+
+    /// Looks up `key`, where nested tables are separated by dots, e.g. `"movement.max_speed"`.
+    /// `None` if the key is missing or the value has a different type.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let mut parts = key.split('.');
+        let mut value = self.values.get(parts.next()?)?;
+        for part in parts {
+            value = value.get(part)?;
+        }
+        match value.clone().try_into() {
+            Ok(value) => Some(value),
+            Err(err) => {
+                log::warn!("Config value {} has the wrong type: {}", key, err);
+                None
+            }
+        }
+    }
+
+    pub fn get_or<T: DeserializeOwned>(&self, key: &str, default: T) -> T {
+        self.get(key).unwrap_or(default)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn nested_keys_and_defaults() {
+        let config = GameConfig::from_toml(
+            r#"
+            gravity = -500.0
+
+            [movement]
+            max_speed = 600.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.get::<f32>("gravity"), Some(-500.0));
+        assert_eq!(config.get::<f32>("movement.max_speed"), Some(600.0));
+        assert_eq!(config.get::<f32>("movement.missing"), None);
+        assert_eq!(config.get::<String>("gravity"), None);
+        assert_eq!(config.get_or("jump_height", 120.0), 120.0);
+    }
+
+    #[test]
+    fn unreal_config_overrides_defaults() {
+        testing::install();
+        testing::with(|unreal| unreal.config = "gravity = -500.0".into());
+        let config = GameConfig::download();
+        assert_eq!(config.get_or("gravity", -981.0), -500.0);
+        assert_eq!(config.get_or("jump_height", 120.0), 120.0);
+
+        testing::with(|unreal| unreal.config = "gravity = ".into());
+        assert!(GameConfig::download().is_empty());
+    }
+}
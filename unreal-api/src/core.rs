@@ -1,24 +1,66 @@
-use bevy_ecs::{prelude::*, system::Command};
+use bevy_ecs::{
+    prelude::*,
+    schedule::ShouldRun,
+    system::{Command, EntityCommands},
+};
 use ffi::{ActorComponentPtr, ActorComponentType, EventType, Quaternion};
-use std::ffi::c_void;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::c_void,
+};
 
 use crate::{
-    api::UnrealApi,
+    api::{attach_actor, detach_actor, teleport, UnrealApi},
+    asset::{AssetRegistry, ClassHandle},
+    config::GameConfig,
+    debug_draw::{flush_debug_draw, DebugDraw},
+    diagnostics::DisabledSystems,
     ffi::{self, AActorOpaque},
-    input::Input,
-    math::{Quat, Vec3},
+    ffi_string::FfiStringBuf,
+    input::{ActionMap, Input, PlayerIndex},
+    math::{Mat3, Quat, Vec2, Vec3},
     module::{bindings, Module, UserModule},
+    name::FName,
+    net::{NetId, NetIdRegistry, NetRole, NetRoleComponent},
     physics::PhysicsComponent,
     plugin::Plugin,
     register_components,
+    save::ReloadState,
+    scratch::ScratchBuffers,
+    sound::Sounds,
+    spatial::rebuild_spatial_hash,
+    task::TaskPool,
+    timer::{tick_timers, Timers},
 };
 
 pub struct UnrealCore {
     module: Module,
+    /// Restored at the end of the first frame after a hot reload
+    reload_state: Option<ReloadState>,
+    /// Holds the result of `prepare_reload` between the two calls from unreal
+    reload_bytes: Vec<u8>,
 }
 
 pub struct CorePlugin;
 
+/// Opt-in check of every `TransformComponent` in `CoreStage::PostUpdate` right before the
+/// transforms are uploaded, see `TransformComponent::invalid_field` and `LastValidTransform`.
+pub struct ValidateTransformsPlugin;
+
+impl Plugin for ValidateTransformsPlugin {
+    fn build(&self, module: &mut Module) {
+        module.add_system_set_to_stage(
+            CoreStage::PostUpdate,
+            SystemSet::new().with_system(
+                validate_transforms
+                    .after(CoreLabel::ResolveTransforms)
+                    .before(CoreLabel::UploadTransform),
+            ),
+        );
+    }
+}
+
 impl Plugin for CorePlugin {
     fn build(&self, module: &mut Module) {
         register_components! {
@@ -26,74 +68,313 @@ impl Plugin for CorePlugin {
             ActorComponent,
             PlayerInputComponent,
             ParentComponent,
+            LocalTransformComponent,
+            SocketAttachComponent,
+            ActorAttachComponent,
             PhysicsComponent,
+            TransformAuthorityRust,
+            TransformAuthorityUnreal,
+            PreviousTransform,
+            LastValidTransform,
+            BoundsComponent,
+            TagsComponent,
+            VisibilityComponent,
+            MaterialParamComponent,
+            NetId,
+            NetRoleComponent,
             => module
         };
+        module.register_serializable_component::<TransformComponent>();
+        module.register_serializable_component::<PlayerInputComponent>();
+        module.register_default_component::<TransformComponent>();
+        module.register_default_component::<PlayerInputComponent>();
+        module.register_default_component::<TransformAuthorityRust>();
+        module.register_default_component::<TransformAuthorityUnreal>();
+        module.register_default_component::<BoundsComponent>();
+        module.register_default_component::<LastValidTransform>();
+        module.register_default_component::<VisibilityComponent>();
+        module.register_default_component::<MaterialParamComponent>();
 
         module
             .insert_resource(Frame::default())
             .insert_resource(Time::default())
+            .insert_resource(Viewport::default())
+            .insert_resource(GameState::default())
+            .insert_resource(FixedFrame::default())
             .insert_resource(Input::default())
+            .insert_resource(ActionMap::default())
+            .insert_resource(DebugDraw::default())
+            .insert_resource(ClassBundleRegistry::default())
+            .insert_resource(TransformPolicy::default())
+            .insert_resource(DisabledSystems::default())
+            .insert_resource(Timers::default())
+            .insert_resource(Sounds::default())
+            .insert_resource(AssetRegistry::default())
             .insert_resource(UnrealApi::default())
+            .insert_resource(PendingActorSpawns::default())
+            .insert_resource(PendingActorDespawns::default())
+            .insert_resource(NetIdRegistry::default())
+            .insert_resource(ScratchBuffers::default())
+            .insert_resource(TaskPool::default())
+            .insert_resource(GameConfig::default())
+            .insert_resource(PendingComponentChanges::default())
             .add_stage(CoreStage::RegisterEvent)
             .add_stage_after(CoreStage::RegisterEvent, CoreStage::PreUpdate)
-            .add_stage_after(CoreStage::PreUpdate, CoreStage::Update)
-            .add_stage_after(CoreStage::Update, CoreStage::PostUpdate)
+            .add_stage_after_with_run_criteria(
+                CoreStage::PreUpdate,
+                CoreStage::FixedUpdate,
+                run_fixed_update,
+            )
+            .add_stage_after(CoreStage::FixedUpdate, CoreStage::Update)
+            .add_stage_after(CoreStage::Update, CoreStage::LateUpdate)
+            .add_stage_after(CoreStage::LateUpdate, CoreStage::PostUpdate)
             // TODO: Order matters here. Needs to be defined after the stages
             .add_event::<OnActorBeginOverlapEvent>()
             .add_event::<OnActorEndOverlapEvent>()
             .add_event::<ActorHitEvent>()
             .add_event::<ActorSpawnedEvent>()
             .add_event::<ActorDestroyEvent>()
+            .add_event::<CollisionEvent>()
             .add_system_set_to_stage(
                 CoreStage::RegisterEvent,
                 SystemSet::new()
                     .with_system(process_actor_spawned)
                     .with_system(process_actor_destroyed),
             )
+            .add_system_set_to_stage(
+                CoreStage::FixedUpdate,
+                // Runs before the systems of every fixed step
+                SystemSet::new()
+                    .with_system(store_previous_transforms.exclusive_system().at_start()),
+            )
             .add_system_set_to_stage(
                 CoreStage::PreUpdate,
                 SystemSet::new()
-                    .with_system(update_input)
-                    .with_system(download_transform_from_unreal)
-                    .with_system(download_physics_from_unreal),
+                    .with_system(update_input.label(CoreLabel::UpdateInput))
+                    .with_system(
+                        update_action_map
+                            .label(CoreLabel::UpdateInput)
+                            .after(update_input),
+                    )
+                    .with_system(tick_timers.with_run_criteria(run_if_running))
+                    .with_system(update_viewport)
+                    .with_system(poll_collision_events)
+                    .with_system(download_transform_from_unreal.label(CoreLabel::DownloadTransform))
+                    .with_system(download_physics_from_unreal.label(CoreLabel::DownloadPhysics))
+                    .with_system(download_bounds_from_unreal),
             )
             .add_system_set_to_stage(
                 CoreStage::PostUpdate,
                 SystemSet::new()
-                    .with_system(upload_transform_to_unreal)
-                    .with_system(upload_physics_to_unreal),
+                    .with_system(propagate_parent_transforms.label(CoreLabel::ResolveTransforms))
+                    .with_system(
+                        attach_to_sockets
+                            .label(CoreLabel::ResolveTransforms)
+                            .after(propagate_parent_transforms),
+                    )
+                    .with_system(
+                        upload_transform_to_unreal
+                            .label(CoreLabel::UploadTransform)
+                            .after(CoreLabel::ResolveTransforms),
+                    )
+                    .with_system(rebuild_spatial_hash.after(CoreLabel::ResolveTransforms))
+                    .with_system(upload_physics_to_unreal.label(CoreLabel::UploadPhysics))
+                    .with_system(upload_visibility_to_unreal)
+                    .with_system(upload_material_params_to_unreal)
+                    .with_system(flush_debug_draw)
+                    .with_system(flush_component_changes.after(NotifyComponentChanges))
+                    .with_system(process_actor_spawn_requests.after(CoreLabel::UploadTransform))
+                    // Runs after the commands of all other systems in this stage were applied
+                    .with_system(process_actor_despawn_requests.exclusive_system().at_end()),
             );
     }
 }
 
 impl UnrealCore {
+    /// For modules that are only known at runtime, like a `CompositeModule` behind a
+    /// `Box<dyn UserModule>`. Use `new_with` if the concrete type of the module is known.
     pub fn new(user_module: &dyn UserModule) -> Self {
+        Self::new_with(user_module)
+    }
+
+    /// Same as `new`, but generic over the module like `begin_play` and `end_play`, so the hooks
+    /// of a concrete `M` are called without dynamic dispatch
+    pub fn new_with<M: UserModule + ?Sized>(user_module: &M) -> Self {
         let mut module = Module::new();
         module.add_plugin(CorePlugin);
         user_module.initialize(&mut module);
-        Self { module }
+        Self {
+            module,
+            reload_state: None,
+            reload_bytes: Vec::new(),
+        }
     }
 
-    pub fn begin_play(&mut self, user_module: &dyn UserModule) {
-        *self = Self::new(user_module);
+    /// The state of a hot reload that was finished before play started is kept and restored at
+    /// the end of the first frame.
+    pub fn begin_play<M: UserModule + ?Sized>(&mut self, user_module: &M) {
+        let reload_state = self.reload_state.take();
+        // Rebuilds the whole schedule, including the stages added by the `UserModule`
+        *self = Self::new_with(user_module);
+        self.module.world.insert_resource(GameConfig::download());
+        if let Some(state) = reload_state {
+            // The spawn events that `finish_reload` sent were dropped with the old world
+            for actor in state.actors() {
+                self.module.world.send_event(ActorSpawnedEvent { actor });
+            }
+            self.reload_state = Some(state);
+        }
 
         self.module.startup.run_once(&mut self.module.world);
+        user_module.on_begin_play(&mut self.module.world);
+    }
+
+    /// Unreal destroys all actors when play ends, so every entity is despawned together with the
+    /// bookkeeping that refers to actors.
+    pub fn end_play<M: UserModule + ?Sized>(&mut self, user_module: &M) {
+        user_module.on_end_play(&mut self.module.world);
+
+        let world = &mut self.module.world;
+        world.clear_entities();
+        world.insert_resource(UnrealApi::default());
+        world.insert_resource(NetIdRegistry::default());
+        world.insert_resource(PendingActorSpawns::default());
+        world.insert_resource(PendingActorDespawns::default());
+    }
+
+    /// We only queue the actor here, the entity is despawned in `process_actor_destroyed` so that
+    /// we never modify the world while systems are running. Actors without an entity are not an
+    /// error, their spawn might not have been processed yet, or they were never registered, so
+    /// this returns `Success` for them as well. Only a null actor is rejected.
+    pub fn unregister_actor(&mut self, actor: *mut AActorOpaque) -> ffi::ResultCode {
+        if actor.is_null() {
+            return ffi::ResultCode::InvalidArgument;
+        }
+        self.module.world.send_event(ActorDestroyEvent {
+            actor: ActorPtr(actor),
+        });
+        ffi::ResultCode::Success
+    }
+
+    /// Removes the registered component `uuid` from the entity of `actor`. Removing a component
+    /// that the entity does not have is not an error.
+    pub fn remove_component(&mut self, actor: *mut AActorOpaque, uuid: Uuid) -> ffi::ResultCode {
+        let module = &mut self.module;
+        let reflect = match module.reflection_registry.reflect.get(&uuid) {
+            Some(reflect) => reflect,
+            None => return ffi::ResultCode::NotFound,
+        };
+        match crate::api::entity_for_actor(&module.world, actor) {
+            Some(entity) => {
+                reflect.remove_component(&mut module.world, entity);
+                ffi::ResultCode::Success
+            }
+            None => ffi::ResultCode::ActorNotFound,
+        }
+    }
+
+    /// Inserts the default value of the registered component `uuid` into the entity of `actor`,
+    /// see `Module::register_default_component`. Returns `NotFound` for unregistered components
+    /// and `InvalidArgument` for components without a default.
+    pub fn add_component(&mut self, actor: *mut AActorOpaque, uuid: Uuid) -> ffi::ResultCode {
+        let module = &mut self.module;
+        if !module.reflection_registry.uuid_set.contains(&uuid) {
+            return ffi::ResultCode::NotFound;
+        }
+        let insert_default = match module.reflection_registry.insert_default.get(&uuid) {
+            Some(insert_default) => insert_default,
+            None => return ffi::ResultCode::InvalidArgument,
+        };
+        match crate::api::entity_for_actor(&module.world, actor) {
+            Some(entity) => {
+                insert_default(&mut module.world, entity);
+                ffi::ResultCode::Success
+            }
+            None => ffi::ResultCode::ActorNotFound,
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.module.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.module.world
     }
+
     pub fn tick(&mut self, dt: f32) {
         if let Some(mut frame) = self.module.world.get_resource_mut::<Frame>() {
-            frame.dt = dt;
+            let dilation = unsafe { (bindings().get_time_dilation)() };
+            frame.advance(dt, dilation);
         }
         if let Some(mut time) = self.module.world.get_resource_mut::<Time>() {
             time.time += dt as f64;
         }
+        let paused = match self.module.world.get_resource_mut::<GameState>() {
+            Some(mut game_state) => {
+                game_state.apply_next();
+                game_state.is_paused()
+            }
+            None => false,
+        };
+        // Time that passes while paused is never fed into the accumulator, otherwise it would be
+        // replayed as a burst of fixed steps after unpausing
+        let steps = if paused {
+            0
+        } else {
+            self.module.fixed_timestep.advance(dt)
+        };
+        if let Some(mut fixed_frame) = self.module.world.get_resource_mut::<FixedFrame>() {
+            fixed_frame.dt = self.module.fixed_timestep.step;
+            fixed_frame.steps = steps;
+            fixed_frame.remaining = steps;
+            fixed_frame.alpha = self.module.fixed_timestep.alpha();
+        }
         self.module.schedule.run_once(&mut self.module.world);
+        if let Some(state) = self.reload_state.take() {
+            if let Err(err) =
+                state.restore(&mut self.module.world, &self.module.reflection_registry)
+            {
+                log::error!("Unable to restore the state after a hot reload: {}", err);
+            }
+        }
         self.module.world.clear_trackers();
     }
+
+    /// Serializes the components of all actors so that they can be restored by `finish_reload`
+    /// in the newly loaded module. Empty unless the module opted in with
+    /// `Module::preserve_state_on_reload`.
+    pub fn prepare_reload(&self) -> Vec<u8> {
+        if !self.module.preserve_state_on_reload {
+            return Vec::new();
+        }
+        let state = ReloadState::capture(&self.module.world, &self.module.reflection_registry);
+        if state.is_empty() {
+            return Vec::new();
+        }
+        bincode::serialize(&state).expect("Unable to serialize the reload state")
+    }
+
+    /// Registers every actor of the previous module that still exists. The new entities are
+    /// spawned in the next frame, after which their saved components are restored. Actors that
+    /// no longer exist are dropped.
+    pub fn finish_reload(&mut self, bytes: &[u8]) -> bincode::Result<()> {
+        let mut state: ReloadState = bincode::deserialize(bytes)?;
+        let alive: HashSet<ActorPtr> = crate::iterate_actors(bindings())
+            .into_iter()
+            .map(ActorPtr)
+            .collect();
+        state.retain_actors(|actor| alive.contains(&actor));
+        for actor in state.actors() {
+            self.module.world.send_event(ActorSpawnedEvent { actor });
+        }
+        self.reload_state = Some(state);
+        Ok(())
+    }
 }
 
 pub unsafe extern "C" fn retrieve_uuids(ptr: *mut ffi::Uuid, len: *mut usize) {
-    if let Some(global) = crate::module::MODULE.as_mut() {
+    if let Some(global) = crate::module::global() {
         if ptr.is_null() {
             *len = global.core.module.reflection_registry.uuid_set.len();
         } else {
@@ -113,6 +394,92 @@ pub unsafe extern "C" fn retrieve_uuids(ptr: *mut ffi::Uuid, len: *mut usize) {
     }
 }
 
+pub unsafe extern "C" fn retrieve_component_info(
+    uuid: ffi::Uuid,
+    info: *mut ffi::ComponentInfo,
+) -> u32 {
+    fn component_info(uuid: ffi::Uuid) -> Option<(&'static str, Vec<(&'static str, ReflectType)>)> {
+        let global = unsafe { crate::module::global() }?;
+        global
+            .core
+            .module
+            .reflection_registry
+            .component_info(&from_ffi_uuid(uuid))
+    }
+    let result = std::panic::catch_unwind(|| {
+        if let Some((name, fields)) = component_info(uuid) {
+            let info = &mut *info;
+            info.name = ffi::Utf8Str::from(name);
+            if info.fields.is_null() {
+                info.len = fields.len();
+            } else {
+                let slice = std::slice::from_raw_parts_mut(info.fields, info.len);
+                for (out, (name, ty)) in slice.iter_mut().zip(fields) {
+                    *out = ffi::ComponentField {
+                        name: ffi::Utf8Str::from(name),
+                        ty: to_ffi_reflection_type(ty),
+                    };
+                }
+            }
+            1
+        } else {
+            0
+        }
+    });
+    result.unwrap_or(0)
+}
+
+/// Called by unreal right before the module is unloaded for a hot reload. Like `retrieve_uuids`,
+/// the first call with a null `data` writes the size of the state into `len`, and the second
+/// call copies the state into `data`.
+pub unsafe extern "C" fn prepare_reload(data: *mut u8, len: *mut usize) -> ffi::ResultCode {
+    if len.is_null() {
+        return ffi::ResultCode::InvalidArgument;
+    }
+    catch_panic(|| {
+        let global = match crate::module::global() {
+            Some(global) => global,
+            None => return ffi::ResultCode::NotInitialized,
+        };
+        if data.is_null() {
+            global.core.reload_bytes = global.core.prepare_reload();
+            *len = global.core.reload_bytes.len();
+        } else {
+            let bytes = std::mem::take(&mut global.core.reload_bytes);
+            let count = bytes.len().min(*len);
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), data, count);
+            *len = count;
+        }
+        ffi::ResultCode::Success
+    })
+}
+
+/// Called by unreal on the newly loaded module with the state from `prepare_reload`
+pub unsafe extern "C" fn finish_reload(data: *const u8, len: usize) -> ffi::ResultCode {
+    if data.is_null() {
+        return ffi::ResultCode::InvalidArgument;
+    }
+    catch_panic(|| {
+        let global = match crate::module::global() {
+            Some(global) => global,
+            None => return ffi::ResultCode::NotInitialized,
+        };
+        let bytes = std::slice::from_raw_parts(data, len);
+        match global.core.finish_reload(bytes) {
+            Ok(()) => ffi::ResultCode::Success,
+            Err(err) => {
+                log::error!("Unable to read the state after a hot reload: {}", err);
+                ffi::ResultCode::InvalidArgument
+            }
+        }
+    })
+}
+
+/// Maps a panic inside of `f` to `ResultCode::Panic`
+fn catch_panic(f: impl FnOnce() -> ffi::ResultCode) -> ffi::ResultCode {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_or(ffi::ResultCode::Panic)
+}
+
 pub struct ActorSpawnedEvent {
     pub actor: ActorPtr,
 }
@@ -137,8 +504,54 @@ pub struct ActorDestroyEvent {
     pub actor: ActorPtr,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CollisionPhase {
+    /// The actors started to overlap
+    Begin,
+    /// The actors stopped overlapping
+    End,
+    /// The actors blocked each other, only sent for actors that were registered for hits
+    Hit,
+}
+
+impl From<ffi::CollisionPhase> for CollisionPhase {
+    fn from(phase: ffi::CollisionPhase) -> Self {
+        match phase {
+            ffi::CollisionPhase::Begin => CollisionPhase::Begin,
+            ffi::CollisionPhase::End => CollisionPhase::End,
+            ffi::CollisionPhase::Hit => CollisionPhase::Hit,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct CollisionImpact {
+    /// Velocity of `self_actor` relative to `other_actor` when they hit
+    pub velocity: Vec3,
+    /// Where the actors touched in world space
+    pub point: Vec3,
+}
+
+/// Overlap and hit events between two registered actors, polled from unreal once per frame in
+/// `CoreStage::PreUpdate`.
+#[derive(Debug, Copy, Clone)]
+pub struct CollisionEvent {
+    pub self_actor: Entity,
+    pub other_actor: Entity,
+    pub phase: CollisionPhase,
+    /// Only `Some` for `CollisionPhase::Hit`, overlaps have no impact
+    pub impact: Option<CollisionImpact>,
+}
+
+impl CollisionEvent {
+    /// How fast the actors hit each other, e.g. to scale damage or effects. `None` for overlaps.
+    pub fn impact_speed(&self) -> Option<f32> {
+        self.impact.map(|impact| impact.velocity.length())
+    }
+}
+
 pub unsafe extern "C" fn unreal_event(ty: *const EventType, data: *const c_void) {
-    if let Some(global) = crate::module::MODULE.as_mut() {
+    if let Some(global) = crate::module::global() {
         match *ty {
             EventType::ActorSpawned => {
                 let actor_spawned_event = data as *const ffi::ActorSpawnedEvent;
@@ -181,6 +594,40 @@ pub unsafe extern "C" fn unreal_event(ty: *const EventType, data: *const c_void)
         }
     }
 }
+/// Called by unreal when an actor is about to be destroyed, see `UnrealCore::unregister_actor`
+pub unsafe extern "C" fn unregister_actor(actor: *mut AActorOpaque) -> ffi::ResultCode {
+    catch_panic(|| match crate::module::global() {
+        Some(global) => global.core.unregister_actor(actor),
+        None => ffi::ResultCode::NotInitialized,
+    })
+}
+
+pub unsafe extern "C" fn remove_component(
+    actor: *mut AActorOpaque,
+    uuid: ffi::Uuid,
+) -> ffi::ResultCode {
+    if actor.is_null() {
+        return ffi::ResultCode::InvalidArgument;
+    }
+    catch_panic(|| match crate::module::global() {
+        Some(global) => global.core.remove_component(actor, from_ffi_uuid(uuid)),
+        None => ffi::ResultCode::NotInitialized,
+    })
+}
+
+pub unsafe extern "C" fn add_component(
+    actor: *mut AActorOpaque,
+    uuid: ffi::Uuid,
+) -> ffi::ResultCode {
+    if actor.is_null() {
+        return ffi::ResultCode::InvalidArgument;
+    }
+    catch_panic(|| match crate::module::global() {
+        Some(global) => global.core.add_component(actor, from_ffi_uuid(uuid)),
+        None => ffi::ResultCode::NotInitialized,
+    })
+}
+
 extern "C" fn get_field_float_value(
     uuid: ffi::Uuid,
     entity: ffi::Entity,
@@ -258,7 +705,7 @@ extern "C" fn get_field_bool_value(
 fn get_field_value(uuid: ffi::Uuid, entity: ffi::Entity, idx: u32) -> Option<ReflectValue> {
     let uuid = from_ffi_uuid(uuid);
     unsafe {
-        let global = crate::module::MODULE.as_mut()?;
+        let global = crate::module::global()?;
         let reflect = global.core.module.reflection_registry.reflect.get(&uuid)?;
 
         let entity = Entity::from_bits(entity.id);
@@ -268,10 +715,10 @@ fn get_field_value(uuid: ffi::Uuid, entity: ffi::Entity, idx: u32) -> Option<Ref
 
 unsafe extern "C" fn number_of_fields(uuid: ffi::Uuid, out: *mut u32) -> u32 {
     fn get_number_fields(uuid: ffi::Uuid) -> Option<u32> {
-        let global = unsafe { crate::module::MODULE.as_mut() }?;
+        let global = unsafe { crate::module::global() }?;
         let uuid = from_ffi_uuid(uuid);
         let reflect = global.core.module.reflection_registry.reflect.get(&uuid)?;
-        Some(reflect.number_of_fields() as u32)
+        Some(reflect.number_of_fields())
     }
     let result = std::panic::catch_unwind(|| {
         if let Some(count) = get_number_fields(uuid) {
@@ -285,7 +732,7 @@ unsafe extern "C" fn number_of_fields(uuid: ffi::Uuid, out: *mut u32) -> u32 {
 }
 unsafe extern "C" fn get_type_name(uuid: ffi::Uuid, out: *mut ffi::Utf8Str) -> u32 {
     fn get_type_name(uuid: ffi::Uuid) -> Option<&'static str> {
-        let global = unsafe { crate::module::MODULE.as_mut() }?;
+        let global = unsafe { crate::module::global() }?;
         let uuid = from_ffi_uuid(uuid);
         let reflect = global.core.module.reflection_registry.reflect.get(&uuid)?;
         Some(reflect.name())
@@ -302,7 +749,7 @@ unsafe extern "C" fn get_type_name(uuid: ffi::Uuid, out: *mut ffi::Utf8Str) -> u
 }
 unsafe extern "C" fn has_component(entity: ffi::Entity, uuid: ffi::Uuid) -> u32 {
     fn has_component(entity: ffi::Entity, uuid: ffi::Uuid) -> Option<u32> {
-        let global = unsafe { crate::module::MODULE.as_ref() }?;
+        let global = unsafe { crate::module::global() }?;
         let uuid = from_ffi_uuid(uuid);
         let reflect = global.core.module.reflection_registry.reflect.get(&uuid)?;
         let entity = Entity::from_bits(entity.id);
@@ -314,7 +761,7 @@ unsafe extern "C" fn has_component(entity: ffi::Entity, uuid: ffi::Uuid) -> u32
 
 unsafe extern "C" fn is_editor_component(uuid: ffi::Uuid) -> u32 {
     fn is_editor_component_inner(uuid: ffi::Uuid) -> Option<u32> {
-        let global = unsafe { crate::module::MODULE.as_mut() }?;
+        let global = unsafe { crate::module::global() }?;
         let uuid = from_ffi_uuid(uuid);
         Some(
             if global
@@ -336,7 +783,7 @@ unsafe extern "C" fn is_editor_component(uuid: ffi::Uuid) -> u32 {
 
 unsafe extern "C" fn get_field_name(uuid: ffi::Uuid, idx: u32, out: *mut ffi::Utf8Str) -> u32 {
     fn get_field_name(uuid: ffi::Uuid, idx: u32) -> Option<&'static str> {
-        let global = unsafe { crate::module::MODULE.as_mut() }?;
+        let global = unsafe { crate::module::global() }?;
         let uuid = from_ffi_uuid(uuid);
         let reflect = global.core.module.reflection_registry.reflect.get(&uuid)?;
         reflect.get_field_name(idx)
@@ -357,19 +804,10 @@ unsafe extern "C" fn get_field_type(
     out: *mut ffi::ReflectionType,
 ) -> u32 {
     fn get_field_type(uuid: ffi::Uuid, idx: u32) -> Option<ffi::ReflectionType> {
-        let global = unsafe { crate::module::MODULE.as_mut() }?;
+        let global = unsafe { crate::module::global() }?;
         let uuid = from_ffi_uuid(uuid);
         let reflect = global.core.module.reflection_registry.reflect.get(&uuid)?;
-        let ty = reflect.get_field_type(idx)?;
-        Some(match ty {
-            ReflectType::Bool => ffi::ReflectionType::Bool,
-            ReflectType::Float => ffi::ReflectionType::Float,
-            ReflectType::Vector3 => ffi::ReflectionType::Vector3,
-            ReflectType::Quat => ffi::ReflectionType::Quaternion,
-            ReflectType::UClass => ffi::ReflectionType::UClass,
-            ReflectType::USound => ffi::ReflectionType::USound,
-            ReflectType::Composite => ffi::ReflectionType::Composite,
-        })
+        reflect.get_field_type(idx).map(to_ffi_reflection_type)
     }
     let result = std::panic::catch_unwind(|| {
         if let Some(ty) = get_field_type(uuid, idx) {
@@ -382,10 +820,23 @@ unsafe extern "C" fn get_field_type(
     result.unwrap_or(0)
 }
 
+fn to_ffi_reflection_type(ty: ReflectType) -> ffi::ReflectionType {
+    match ty {
+        ReflectType::Bool => ffi::ReflectionType::Bool,
+        ReflectType::Float => ffi::ReflectionType::Float,
+        ReflectType::Vector3 => ffi::ReflectionType::Vector3,
+        ReflectType::Quat => ffi::ReflectionType::Quaternion,
+        ReflectType::UClass => ffi::ReflectionType::UClass,
+        ReflectType::USound => ffi::ReflectionType::USound,
+        ReflectType::Entity => ffi::ReflectionType::Entity,
+        ReflectType::Composite => ffi::ReflectionType::Composite,
+    }
+}
+
 pub fn from_ffi_uuid(uuid: ffi::Uuid) -> Uuid {
     unsafe {
         let arr: [u32; 4] = [uuid.a, uuid.b, uuid.c, uuid.d];
-        Uuid::from_bytes(std::mem::transmute(arr))
+        Uuid::from_bytes(std::mem::transmute::<[u32; 4], [u8; 16]>(arr))
     }
 }
 pub fn to_ffi_uuid(uuid: Uuid) -> ffi::Uuid {
@@ -432,24 +883,42 @@ pub fn create_allocate_fns() -> ffi::AllocateFns {
 }
 
 pub extern "C" fn tick(dt: f32) -> crate::ffi::ResultCode {
-    let r = std::panic::catch_unwind(|| unsafe {
-        UnrealCore::tick(&mut crate::module::MODULE.as_mut().unwrap().core, dt);
-    });
-    match r {
-        Ok(_) => ffi::ResultCode::Success,
-        Err(_) => ffi::ResultCode::Panic,
+    if !dt.is_finite() || dt < 0.0 {
+        return ffi::ResultCode::InvalidArgument;
     }
+    catch_panic(|| unsafe {
+        match crate::module::global() {
+            Some(global) => {
+                UnrealCore::tick(&mut global.core, dt);
+                ffi::ResultCode::Success
+            }
+            None => ffi::ResultCode::NotInitialized,
+        }
+    })
 }
 
 pub extern "C" fn begin_play() -> ffi::ResultCode {
-    let r = std::panic::catch_unwind(|| unsafe {
-        let global = crate::module::MODULE.as_mut().unwrap();
-        UnrealCore::begin_play(&mut global.core, global.module.as_ref());
-    });
-    match r {
-        Ok(_) => ffi::ResultCode::Success,
-        Err(_) => ffi::ResultCode::Panic,
-    }
+    catch_panic(|| unsafe {
+        match crate::module::global() {
+            Some(global) => {
+                UnrealCore::begin_play(&mut global.core, global.module.as_ref());
+                ffi::ResultCode::Success
+            }
+            None => ffi::ResultCode::NotInitialized,
+        }
+    })
+}
+
+pub extern "C" fn end_play() -> ffi::ResultCode {
+    catch_panic(|| unsafe {
+        match crate::module::global() {
+            Some(global) => {
+                UnrealCore::end_play(&mut global.core, global.module.as_ref());
+                ffi::ResultCode::Success
+            }
+            None => ffi::ResultCode::NotInitialized,
+        }
+    })
 }
 
 pub fn register_core_components(registry: &mut ReflectionRegistry) {
@@ -457,28 +926,285 @@ pub fn register_core_components(registry: &mut ReflectionRegistry) {
     registry.register::<ActorComponent>();
     registry.register::<PlayerInputComponent>();
     registry.register::<ParentComponent>();
+    registry.register::<LocalTransformComponent>();
+    registry.register::<SocketAttachComponent>();
+    registry.register::<ActorAttachComponent>();
     registry.register::<PhysicsComponent>();
+    registry.register::<TransformAuthorityRust>();
+    registry.register::<TransformAuthorityUnreal>();
+    registry.register::<PreviousTransform>();
+    registry.register::<LastValidTransform>();
+    registry.register::<BoundsComponent>();
+    registry.register::<TagsComponent>();
+    registry.register::<VisibilityComponent>();
+    registry.register::<MaterialParamComponent>();
+    registry.register::<NetId>();
+    registry.register::<NetRoleComponent>();
 }
 
 use unreal_api::{module::ReflectionRegistry, Component};
 use unreal_reflect::{
     registry::{ReflectType, ReflectValue},
-    Uuid,
+    TypeUuid, Uuid,
 };
 #[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
 pub struct StartupStage;
 
+/// Labels of the core systems, so systems of other modules can be ordered against them within
+/// the same stage, e.g. `.before(CoreLabel::UploadTransform)` in `CoreStage::PostUpdate`.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub enum CoreLabel {
+    /// `Input` and `ActionMap` are updated, in `CoreStage::PreUpdate`
+    UpdateInput,
+    /// `TransformComponent`s are downloaded from unreal, in `CoreStage::PreUpdate`
+    DownloadTransform,
+    /// `PhysicsComponent`s are downloaded from unreal, in `CoreStage::PreUpdate`
+    DownloadPhysics,
+    /// Transforms of `LocalTransformComponent`s and `SocketAttachComponent`s are computed, in
+    /// `CoreStage::PostUpdate`
+    ResolveTransforms,
+    /// `TransformComponent`s are uploaded to unreal, in `CoreStage::PostUpdate`
+    UploadTransform,
+    /// `PhysicsComponent`s are uploaded to unreal, in `CoreStage::PostUpdate`
+    UploadPhysics,
+}
+
+/// The stages run in the order they are declared. `Commands` are applied at the end of every
+/// stage, so an entity that was spawned in `Update` can not be queried by another system in
+/// `Update`, but it can be in `LateUpdate`. Use `Module::add_stage_after` for more flush points.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, StageLabel)]
 pub enum CoreStage {
     Startup,
     RegisterEvent,
     PreUpdate,
+    /// Runs zero or more times per frame with a fixed delta time, see `FixedFrame`. Never runs
+    /// while the `GameState` is paused.
+    FixedUpdate,
     Update,
+    /// Sees the commands of `Update`, e.g. to configure entities that were spawned there.
+    /// Transforms are not uploaded before `PostUpdate`.
+    LateUpdate,
     PostUpdate,
 }
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone)]
 pub struct Frame {
+    /// Seconds since the last frame, scaled by `dilation`. Gameplay should use this.
+    pub dt: f32,
+    /// Seconds since the last frame, ignoring `dilation`. Meant for things that should not slow
+    /// down in slow motion, like UI animations.
+    pub real_dt: f32,
+    /// The time dilation of the unreal world, 1 is normal speed. Reset to 1 in `begin_play`.
+    pub dilation: f32,
+    /// Seconds since `begin_play`, including the current frame
+    pub elapsed: f64,
+    /// Number of ticks since `begin_play`, including the current frame
+    pub count: u64,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self {
+            dt: 0.0,
+            real_dt: 0.0,
+            dilation: 1.0,
+            elapsed: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl Frame {
+    /// `dt` is the dilated time that unreal passes to tick
+    fn advance(&mut self, dt: f32, dilation: f32) {
+        self.dilation = dilation;
+        self.dt = dt;
+        // Unreal clamps the dilation to a small positive value, but stay safe with broken inputs
+        self.real_dt = if dilation > 0.0 { dt / dilation } else { dt };
+        self.elapsed += dt as f64;
+        self.count += 1;
+    }
+}
+
+/// The size of the game viewport, updated in `CoreStage::PreUpdate`. The size is 0 while the
+/// window is minimized or before the viewport exists.
+#[derive(Debug, Copy, Clone)]
+pub struct Viewport {
+    pub width: u32,
+    pub height: u32,
+    /// Scale of the UI for high DPI displays
+    pub dpi_scale: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            dpi_scale: 1.0,
+        }
+    }
+}
+
+impl Viewport {
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    /// Width divided by height, `None` while the viewport has no area
+    pub fn aspect_ratio(&self) -> Option<f32> {
+        (self.width > 0 && self.height > 0).then(|| self.width as f32 / self.height as f32)
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+/// Available inside `CoreStage::FixedUpdate`. Systems in that stage should use this `dt` instead
+/// of `Frame::dt`.
+#[derive(Debug, Copy, Clone)]
+pub struct FixedFrame {
     pub dt: f32,
+    /// How many times `CoreStage::FixedUpdate` runs this frame
+    pub steps: u32,
+    /// How far the current frame is between the last and the next fixed step, in `[0, 1)`
+    pub alpha: f32,
+    remaining: u32,
+}
+
+impl Default for FixedFrame {
+    fn default() -> Self {
+        Self {
+            dt: FixedTimestep::DEFAULT_STEP,
+            steps: 0,
+            alpha: 0.0,
+            remaining: 0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct FixedTimestep {
+    pub step: f32,
+    /// Upper bound of steps per frame. If a frame takes too long we would need even more steps
+    /// to catch up in the next frame, which will never finish. Time beyond this limit is dropped.
+    pub max_substeps: u32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub const DEFAULT_STEP: f32 = 1.0 / 60.0;
+    pub const DEFAULT_MAX_SUBSTEPS: u32 = 5;
+
+    pub fn new(step: f32, max_substeps: u32) -> Self {
+        assert!(step > 0.0, "The fixed timestep must be positive");
+        Self {
+            step,
+            max_substeps,
+            accumulator: 0.0,
+        }
+    }
+
+    /// The time left in the accumulator as a fraction of `step`
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+
+    /// Adds `dt` to the accumulator and returns how many fixed steps should run.
+    pub fn advance(&mut self, dt: f32) -> u32 {
+        self.accumulator += dt;
+        let steps = (self.accumulator / self.step) as u32;
+        if steps > self.max_substeps {
+            self.accumulator = 0.0;
+            self.max_substeps
+        } else {
+            self.accumulator -= steps as f32 * self.step;
+            steps
+        }
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_STEP, Self::DEFAULT_MAX_SUBSTEPS)
+    }
+}
+
+fn run_fixed_update(mut fixed_frame: ResMut<FixedFrame>) -> ShouldRun {
+    if fixed_frame.remaining > 0 {
+        fixed_frame.remaining -= 1;
+        ShouldRun::YesAndCheckAgain
+    } else {
+        ShouldRun::No
+    }
+}
+
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlayState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// Whether gameplay is paused. A new state only takes effect when the next frame starts, so all
+/// systems of a frame see the same state no matter when it was changed. The core systems ignore
+/// it and keep the actors in sync with unreal.
+#[derive(Default, Debug)]
+pub struct GameState {
+    current: PlayState,
+    next: Option<PlayState>,
+}
+
+impl GameState {
+    pub fn current(&self) -> PlayState {
+        self.current
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.current == PlayState::Paused
+    }
+
+    pub fn set(&mut self, state: PlayState) {
+        self.next = Some(state);
+    }
+
+    /// Toggles the state that was last set, not the current one, so toggling twice in a frame
+    /// does nothing
+    pub fn toggle_pause(&mut self) {
+        let next = match self.next.unwrap_or(self.current) {
+            PlayState::Running => PlayState::Paused,
+            PlayState::Paused => PlayState::Running,
+        };
+        self.next = Some(next);
+    }
+
+    fn apply_next(&mut self) {
+        if let Some(next) = self.next.take() {
+            self.current = next;
+        }
+    }
+}
+
+/// Run criteria for gameplay systems that should stop while the game is paused, see `GameState`
+pub fn run_if_running(game_state: Res<GameState>) -> ShouldRun {
+    if game_state.is_paused() {
+        ShouldRun::No
+    } else {
+        ShouldRun::Yes
+    }
+}
+
+/// Run criteria for systems that should run exactly once, in the `n`th frame after `begin_play`,
+/// e.g. for setup that needs the actors of the level to be registered and settled. The first
+/// frame is frame 1, `Frame::count` is used, so paused frames are counted as well.
+pub fn run_after_frames(n: u64) -> impl FnMut(Res<Frame>) -> ShouldRun {
+    move |frame: Res<Frame>| {
+        if frame.count == n {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
+        }
+    }
 }
 
 #[derive(Default, Debug, Copy, Clone)]
@@ -510,21 +1236,11 @@ impl ActorComponent {
     }
 
     pub fn get_actor_name(&self) -> String {
-        unsafe {
-            let mut alloc = ffi::RustAlloc::empty();
-            (bindings().actor_fns.get_actor_name)(self.actor.0, &mut alloc);
-            let name = {
-                let slice = std::slice::from_raw_parts(alloc.ptr, alloc.size);
-                let name = std::str::from_utf8(slice).unwrap();
-                name.to_string()
-            };
-            alloc.free();
-            name
-        }
+        self.actor.get_actor_name()
     }
 }
 
-#[derive(Default, Debug, Component, Clone)]
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
 #[uuid = "b8738d9e-ab21-47db-8587-4019b38e35a6"]
 pub struct TransformComponent {
     pub position: Vec3,
@@ -532,6 +1248,16 @@ pub struct TransformComponent {
     pub scale: Vec3,
 }
 
+impl Default for TransformComponent {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
 impl TransformComponent {
     pub fn right(&self) -> Vec3 {
         self.rotation * Vec3::Y
@@ -545,54 +1271,395 @@ impl TransformComponent {
     pub fn is_nan(&self) -> bool {
         self.position.is_nan() || self.rotation.is_nan() || self.scale.is_nan()
     }
-}
 
-#[derive(Debug, Component)]
-#[uuid = "f1e22f5b-2bfe-4ce5-938b-7c093def708e"]
-pub struct ParentComponent {
-    #[reflect(skip)]
-    pub parent: Entity,
-}
+    /// Positions and scales beyond this are treated as broken by `invalid_field`
+    pub const MAX_MAGNITUDE: f32 = 1.0e7;
 
-impl Default for ParentComponent {
-    fn default() -> Self {
-        todo!()
+    /// The name of the first field that is NaN, infinite, denormal or larger than
+    /// `MAX_MAGNITUDE`, or of a rotation that is far from normalized. `None` if the transform is
+    /// fine.
+    pub fn invalid_field(&self) -> Option<&'static str> {
+        fn is_broken(values: &[f32]) -> bool {
+            values.iter().any(|value| {
+                !value.is_finite()
+                    || value.is_subnormal()
+                    || value.abs() > TransformComponent::MAX_MAGNITUDE
+            })
+        }
+        if is_broken(&self.position.to_array()) {
+            Some("position")
+        } else if is_broken(&self.rotation.to_array())
+            // Loose enough to allow the drift of many chained rotations
+            || (self.rotation.length_squared() - 1.0).abs() > 1.0e-2
+        {
+            Some("rotation")
+        } else if is_broken(&self.scale.to_array()) {
+            Some("scale")
+        } else {
+            None
+        }
+    }
+
+    /// Rotates the transform so that `forward` points from `position` towards `target` while
+    /// keeping `up` as close to `up` as possible. The rotation is left unchanged if `target` is
+    /// at `position` or if the direction to `target` is parallel to `up`.
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        let Some(forward) = (target - self.position).try_normalize() else {
+            return;
+        };
+        let Some(right) = up.cross(forward).try_normalize() else {
+            return;
+        };
+        let up = forward.cross(right);
+        self.rotation = Quat::from_mat3(&Mat3::from_cols(forward, right, up)).normalize();
+    }
+
+    /// Lerps position and scale and slerps the rotation
+    pub fn lerp(&self, other: &TransformComponent, t: f32) -> TransformComponent {
+        TransformComponent {
+            position: self.position.lerp(other.position, t),
+            rotation: self.rotation.slerp(other.rotation, t),
+            scale: self.scale.lerp(other.scale, t),
+        }
+    }
+
+    /// Transforms `local` from the space of `self` into world space.
+    pub fn mul_local(&self, local: &LocalTransformComponent) -> TransformComponent {
+        TransformComponent {
+            position: self.position + self.rotation * (self.scale * local.position),
+            rotation: self.rotation * local.rotation,
+            scale: self.scale * local.scale,
+        }
     }
 }
 
+/// The tags of the actor in unreal, read once when the actor is registered. Every actor that was
+/// spawned in unreal has this component, even if it has no tags.
 #[derive(Default, Debug, Component)]
-#[uuid = "35256309-43b4-4459-9884-eb6e9137faf5"]
-pub struct PlayerInputComponent {
-    pub direction: Vec3,
+#[uuid = "8c30a301-3f80-46c1-bb36-197e6e89b95b"]
+pub struct TagsComponent {
+    #[reflect(skip)]
+    pub tags: Vec<String>,
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-pub struct ActorPtr(pub *mut AActorOpaque);
-impl ActorPtr {
-    pub fn get_actor_name(&self) -> String {
-        unsafe {
-            let mut alloc = ffi::RustAlloc::empty();
-            (bindings().actor_fns.get_actor_name)(self.0, &mut alloc);
-            let name = {
-                let slice = std::slice::from_raw_parts(alloc.ptr, alloc.size);
-                let name = std::str::from_utf8(slice).unwrap();
-                name.to_string()
-            };
-            alloc.free();
-            name
-        }
+impl TagsComponent {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
     }
 }
-unsafe impl Send for ActorPtr {}
-unsafe impl Sync for ActorPtr {}
-impl Default for ActorPtr {
-    fn default() -> Self {
-        Self(std::ptr::null_mut())
-    }
+
+/// Whether the actor is hidden in game. Read from the actor when it is registered, changes are
+/// sent to unreal in `CoreStage::PostUpdate`, but only in frames in which the component changed.
+#[derive(Default, Debug, Component, Serialize, Deserialize)]
+#[uuid = "4b7e9d2a-6c3f-4e18-a05d-8f2c1b7e6d93"]
+pub struct VisibilityComponent {
+    pub hidden: bool,
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-pub struct UnrealPtr<T> {
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MaterialParam {
+    Scalar(f32),
+    /// Also used for colors, the alpha is always 1
+    Vector(Vec3),
+}
+
+/// Material parameters of all primitive components of the actor. Only parameters whose value
+/// changed since the last frame are sent to unreal in `CoreStage::PostUpdate`. Parameters are
+/// never read back, changes from blueprints are not visible here.
+#[derive(Default, Debug, Component)]
+#[uuid = "e3b6a9d1-4f27-4c85-b0e2-7d1c5a3f9b48"]
+pub struct MaterialParamComponent {
+    #[reflect(skip)]
+    values: HashMap<String, MaterialParam>,
+    #[reflect(skip)]
+    dirty: HashSet<String>,
+}
+
+impl MaterialParamComponent {
+    pub fn set_scalar(&mut self, name: &str, value: f32) {
+        self.set(name, MaterialParam::Scalar(value));
+    }
+
+    pub fn set_vector(&mut self, name: &str, value: Vec3) {
+        self.set(name, MaterialParam::Vector(value));
+    }
+
+    pub fn get(&self, name: &str) -> Option<MaterialParam> {
+        self.values.get(name).copied()
+    }
+
+    fn set(&mut self, name: &str, param: MaterialParam) {
+        if self.values.get(name) == Some(&param) {
+            return;
+        }
+        self.values.insert(name.to_string(), param);
+        self.dirty.insert(name.to_string());
+    }
+}
+
+/// The last `TransformComponent` that passed `validate_transforms`. Entities with this component
+/// are reset to it when their transform breaks, all others are only reported.
+#[derive(Default, Debug, Component)]
+#[uuid = "b2d94c1e-8f3a-4a67-9e05-3c7f1d6a8b24"]
+pub struct LastValidTransform(#[reflect(skip)] pub TransformComponent);
+
+/// The `TransformComponent` from before the last fixed step. Entities that are moved in
+/// `CoreStage::FixedUpdate` can add it to upload a transform that is interpolated between the
+/// last two fixed steps with `FixedFrame::alpha`, which hides the stutter when the frame rate
+/// does not match the fixed step. This only works together with `TransformAuthorityRust`,
+/// otherwise the interpolated transform is downloaded again in the next frame.
+#[derive(Default, Debug, Component)]
+#[uuid = "677ada4d-fcee-44dc-b7cb-7295bbcfa901"]
+pub struct PreviousTransform(#[reflect(skip)] pub TransformComponent);
+
+/// The `TransformComponent` of this entity is never overwritten by the transform of its actor.
+/// Changes to the actor that do not come from Rust, like physics or animations, are lost.
+#[derive(Default, Debug, Component)]
+#[uuid = "181ef27a-3675-40b0-b9a3-0e7c911ef8f5"]
+pub struct TransformAuthorityRust;
+
+/// The `TransformComponent` of this entity is never uploaded to its actor. Changes from Rust are
+/// overwritten by the transform of the actor in the next frame.
+#[derive(Default, Debug, Component)]
+#[uuid = "c5b28c20-397c-450b-b0f3-0a12fd4e4034"]
+pub struct TransformAuthorityUnreal;
+
+#[derive(Debug, Component)]
+#[uuid = "f1e22f5b-2bfe-4ce5-938b-7c093def708e"]
+pub struct ParentComponent {
+    pub parent: Entity,
+}
+
+/// The transform of an entity relative to its `ParentComponent`. Entities with both components
+/// get their `TransformComponent` recomputed from the parent every frame in `CoreStage::PostUpdate`.
+#[derive(Debug, Component, Clone)]
+#[uuid = "5bd0e2a4-5c1d-4c8e-a8c6-4a1f4d37b1e9"]
+pub struct LocalTransformComponent {
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for LocalTransformComponent {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+/// Pins the `TransformComponent` of this entity to a socket or bone of the actor of `parent`,
+/// e.g. to attach an effect to the hand of a character. Updated every frame in
+/// `CoreStage::PostUpdate`. If the socket does not exist the transform of the parent is used.
+#[derive(Debug, Component)]
+#[uuid = "0e2f6d0b-7c1a-4b59-8d3e-5a9c4f1b2e67"]
+pub struct SocketAttachComponent {
+    pub parent: Entity,
+    #[reflect(skip)]
+    pub socket_name: String,
+    /// Relative to the socket
+    #[reflect(skip)]
+    pub offset: LocalTransformComponent,
+    #[reflect(skip)]
+    reported_missing: bool,
+}
+
+impl SocketAttachComponent {
+    pub fn new(parent: Entity, socket_name: impl Into<String>) -> Self {
+        Self {
+            parent,
+            socket_name: socket_name.into(),
+            offset: LocalTransformComponent::default(),
+            reported_missing: false,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: LocalTransformComponent) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// The actor of this entity is attached to the actor of `parent` in unreal, see `attach_actor`.
+/// Unlike `ParentComponent` and `SocketAttachComponent` unreal moves the actor, so physics and
+/// replication follow the parent as well. Only `attach_actor` and `detach_actor` keep this in sync
+/// with unreal, inserting it directly does nothing.
+#[derive(Debug, Component)]
+#[uuid = "a7c3e1f5-2d84-4b96-9e0a-6f1d3c8b5e72"]
+pub struct ActorAttachComponent {
+    pub parent: Entity,
+    #[reflect(skip)]
+    pub socket: Option<FName>,
+}
+
+#[derive(Default, Debug, Component, Serialize, Deserialize)]
+#[uuid = "35256309-43b4-4459-9884-eb6e9137faf5"]
+pub struct PlayerInputComponent {
+    pub direction: Vec3,
+    /// The local player that controls this entity, 0 unless there is more than one local player
+    #[reflect(skip)]
+    #[serde(default)]
+    pub player_index: PlayerIndex,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[repr(transparent)]
+pub struct ActorPtr(pub *mut AActorOpaque);
+
+impl ActorPtr {
+    pub fn get_actor_name(&self) -> String {
+        FfiStringBuf::read(|data| unsafe { (bindings().actor_fns.get_actor_name)(self.0, data) })
+            .expect("Invalid actor name")
+    }
+
+    pub fn get_class_name(&self) -> String {
+        FfiStringBuf::read(|data| unsafe {
+            (bindings().actor_fns.get_actor_class_name)(self.0, data)
+        })
+        .expect("Invalid class name")
+    }
+
+    /// The world space bounding box of the colliding components as center and half size
+    pub fn get_bounds(&self) -> (Vec3, Vec3) {
+        let mut origin = ffi::Vector3::default();
+        let mut extent = ffi::Vector3::default();
+        unsafe {
+            (bindings().actor_fns.get_actor_bounds)(self.0, &mut origin, &mut extent);
+        }
+        (origin.into(), extent.into())
+    }
+
+    /// The current world transform of the actor in unreal, which can be ahead of the
+    /// `TransformComponent` of its entity until the next download
+    pub fn get_transform(&self) -> TransformComponent {
+        let mut data = ffi::SpatialData::default();
+        (bindings().actor_fns.get_spatial_data)(
+            self.0,
+            &mut data.position,
+            &mut data.rotation,
+            &mut data.scale,
+        );
+        TransformComponent {
+            position: data.position.into(),
+            rotation: data.rotation.into(),
+            scale: data.scale.into(),
+        }
+    }
+
+    /// The world transform of a socket or bone of any component of the actor, `None` if there
+    /// is no socket with that name
+    pub fn get_socket_transform(&self, name: &str) -> Option<TransformComponent> {
+        let mut data = ffi::SpatialData::default();
+        let found = unsafe {
+            (bindings().actor_fns.get_socket_transform)(
+                self.0,
+                name.as_ptr() as *const std::os::raw::c_char,
+                name.len(),
+                &mut data,
+            )
+        };
+        (found == 1).then(|| TransformComponent {
+            position: data.position.into(),
+            rotation: data.rotation.into(),
+            scale: data.scale.into(),
+        })
+    }
+
+    /// Attaches the actor to `parent` in unreal, see `attach_actor`
+    pub fn attach_to(&self, parent: ActorPtr, socket: Option<&FName>) {
+        let socket = socket.map(FName::as_str).unwrap_or_default();
+        unsafe {
+            (bindings().actor_fns.attach_to_actor)(
+                self.0,
+                parent.0,
+                socket.as_ptr() as *const std::os::raw::c_char,
+                socket.len(),
+            );
+        }
+    }
+
+    pub fn detach(&self) {
+        unsafe {
+            (bindings().actor_fns.detach_from_actor)(self.0);
+        }
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        unsafe { (bindings().actor_fns.is_actor_hidden)(self.0) == 1 }
+    }
+
+    /// See `teleport`
+    pub fn teleport(&self, position: Vec3, rotation: Quat) {
+        unsafe {
+            (bindings().actor_fns.set_actor_location_teleport)(
+                self.0,
+                position.into(),
+                rotation.into(),
+            );
+        }
+    }
+
+    /// See `MaterialParamComponent`, which only sends changed values
+    pub fn set_material_scalar(&self, name: &str, value: f32) {
+        unsafe {
+            (bindings().actor_fns.set_material_scalar)(
+                self.0,
+                name.as_ptr() as *const std::os::raw::c_char,
+                name.len(),
+                value,
+            );
+        }
+    }
+
+    pub fn set_material_vector(&self, name: &str, value: Vec3) {
+        unsafe {
+            (bindings().actor_fns.set_material_vector)(
+                self.0,
+                name.as_ptr() as *const std::os::raw::c_char,
+                name.len(),
+                value.into(),
+            );
+        }
+    }
+
+    /// Hides the actor in game, it is still simulated and collides
+    pub fn set_hidden(&self, hidden: bool) {
+        unsafe {
+            (bindings().actor_fns.set_actor_hidden)(self.0, hidden as u32);
+        }
+    }
+
+    /// `None` for actors that are not replicated
+    pub fn get_net_id(&self) -> Option<NetId> {
+        let mut guid = 0;
+        unsafe { (bindings().actor_fns.get_actor_net_guid)(self.0, &mut guid) == 1 }
+            .then_some(NetId(guid))
+    }
+
+    pub fn get_net_role(&self) -> NetRole {
+        unsafe { (bindings().actor_fns.get_actor_net_role)(self.0) }.into()
+    }
+
+    /// The `Tags` of the actor
+    pub fn get_tags(&self) -> Vec<String> {
+        FfiStringBuf::read_list(|data, len| unsafe {
+            (bindings().actor_fns.get_actor_tags)(self.0, data, len)
+        })
+        .expect("Invalid actor tag")
+    }
+}
+unsafe impl Send for ActorPtr {}
+unsafe impl Sync for ActorPtr {}
+impl Default for ActorPtr {
+    fn default() -> Self {
+        Self(std::ptr::null_mut())
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct UnrealPtr<T> {
     pub ptr: *mut c_void,
     _m: std::marker::PhantomData<T>,
 }
@@ -616,10 +1683,7 @@ impl<T> Default for UnrealPtr<T> {
 }
 impl<T> Clone for UnrealPtr<T> {
     fn clone(&self) -> Self {
-        Self {
-            ptr: self.ptr,
-            _m: self._m,
-        }
+        *self
     }
 }
 
@@ -630,161 +1694,2316 @@ pub enum Capsule {}
 #[derive(Debug)]
 pub enum Primitive {}
 
+/// The world space bounding box of the actor, `extent` is the half size. Only entities that
+/// have this component get it updated, in `CoreStage::PreUpdate` of every frame.
+#[derive(Default, Debug, Copy, Clone, Component)]
+#[uuid = "f6a359ff-191b-4990-a7a4-b45d5d35be27"]
+pub struct BoundsComponent {
+    pub origin: Vec3,
+    pub extent: Vec3,
+}
+
+fn download_bounds_from_unreal(mut query: Query<(&ActorComponent, &mut BoundsComponent)>) {
+    for (actor, mut bounds) in query.iter_mut() {
+        let (origin, extent) = actor.actor.get_bounds();
+        bounds.origin = origin;
+        bounds.extent = extent;
+    }
+}
+
+fn upload_visibility_to_unreal(
+    query: Query<(&ActorComponent, &VisibilityComponent), Changed<VisibilityComponent>>,
+) {
+    for (actor, visibility) in query.iter() {
+        actor.actor.set_hidden(visibility.hidden);
+    }
+}
+
+fn upload_material_params_to_unreal(
+    mut query: Query<(&ActorComponent, &mut MaterialParamComponent)>,
+) {
+    for (actor, mut params) in query.iter_mut() {
+        // Checked before borrowing mutably, which would flag the component as changed
+        if params.dirty.is_empty() {
+            continue;
+        }
+        let params = &mut *params;
+        for name in params.dirty.drain() {
+            match params.values.get(&name) {
+                Some(&MaterialParam::Scalar(value)) => {
+                    actor.actor.set_material_scalar(&name, value)
+                }
+                Some(&MaterialParam::Vector(value)) => {
+                    actor.actor.set_material_vector(&name, value)
+                }
+                None => {}
+            }
+        }
+    }
+}
+
 fn download_physics_from_unreal(mut query: Query<&mut PhysicsComponent>) {
     for mut physics in query.iter_mut() {
+        if !physics.is_enabled() {
+            continue;
+        }
         physics.download_state();
     }
 }
 fn upload_physics_to_unreal(mut query: Query<&mut PhysicsComponent>) {
     for mut physics in query.iter_mut() {
-        physics.download_state();
+        if physics.is_enabled() {
+            physics.upload_state();
+        }
     }
 }
-fn download_transform_from_unreal(mut query: Query<(&ActorComponent, &mut TransformComponent)>) {
-    for (actor, mut transform) in query.iter_mut() {
-        let mut position = ffi::Vector3::default();
-        let mut rotation = ffi::Quaternion::default();
-        let mut scale = ffi::Vector3::default();
-
-        (bindings().actor_fns.get_spatial_data)(
-            actor.actor.0,
-            &mut position,
-            &mut rotation,
-            &mut scale,
-        );
+/// What to do when a `TransformComponent` contains NaNs while it is exchanged with unreal
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransformPolicy {
+    Panic,
+    /// Logs a warning and keeps the last valid transform
+    Skip,
+}
 
-        transform.position = position.into();
-        transform.rotation = rotation.into();
-        transform.scale = scale.into();
-        assert!(!transform.is_nan());
+impl Default for TransformPolicy {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Panic
+        } else {
+            Self::Skip
+        }
     }
 }
 
-fn upload_transform_to_unreal(query: Query<(&ActorComponent, &TransformComponent)>) {
-    for (actor, transform) in query.iter() {
-        let is_moveable = unsafe { (bindings().actor_fns.is_moveable)(actor.actor.0) } > 0;
-        if !is_moveable {
-            continue;
+impl TransformPolicy {
+    /// Returns `true` if the transform can be used
+    fn check(self, actor: &ActorComponent, transform: &TransformComponent) -> bool {
+        if !transform.is_nan() {
+            return true;
+        }
+        match self {
+            TransformPolicy::Panic => panic!(
+                "Transform of {} is NaN: {:?}",
+                actor.get_actor_name(),
+                transform
+            ),
+            TransformPolicy::Skip => {
+                log::warn!(
+                    "Skipping NaN transform of {}: {:?}",
+                    actor.get_actor_name(),
+                    transform
+                );
+                false
+            }
         }
-        assert!(!transform.is_nan());
-        (bindings().actor_fns.set_spatial_data)(
-            actor.actor.0,
-            transform.position.into(),
-            transform.rotation.into(),
-            transform.scale.into(),
-        );
     }
 }
 
-fn update_input(mut input: ResMut<Input>) {
-    input.update();
+/// Simulated proxies always follow the replicated actor, even with `TransformAuthorityRust`
+fn downloads_transform(authority_rust: bool, role: Option<&NetRoleComponent>) -> bool {
+    !authority_rust || role.is_some_and(|role| role.role == NetRole::SimulatedProxy)
 }
-#[derive(Debug)]
-pub struct Despawn {
-    pub entity: Entity,
+
+fn download_transform_from_unreal(
+    policy: Res<TransformPolicy>,
+    mut scratch: ResMut<ScratchBuffers>,
+    mut query: Query<(
+        &ActorComponent,
+        &mut TransformComponent,
+        Option<&TransformAuthorityRust>,
+        Option<&NetRoleComponent>,
+    )>,
+) {
+    let mut actors = scratch.actors.take();
+    let mut spatial_data = scratch.spatial_data.take();
+    actors.extend(
+        query
+            .iter()
+            .filter(|(_, _, authority_rust, role)| {
+                downloads_transform(authority_rust.is_some(), *role)
+            })
+            .map(|(actor, ..)| actor.actor),
+    );
+    spatial_data.resize(actors.len(), ffi::SpatialData::default());
+
+    if let Some(get_spatial_data_bulk) = bindings().actor_fns.get_spatial_data_bulk {
+        unsafe {
+            get_spatial_data_bulk(
+                // `ActorPtr` is a transparent wrapper around the actor pointer
+                actors.as_ptr() as *const *const AActorOpaque,
+                spatial_data.as_mut_ptr(),
+                actors.len(),
+            );
+        }
+    } else {
+        for (actor, data) in actors.iter().zip(spatial_data.iter_mut()) {
+            (bindings().actor_fns.get_spatial_data)(
+                actor.0,
+                &mut data.position,
+                &mut data.rotation,
+                &mut data.scale,
+            );
+        }
+    }
+
+    // The query is iterated in the same order and with the same filter as above
+    let downloaded_entities = query.iter_mut().filter(|(_, _, authority_rust, role)| {
+        downloads_transform(authority_rust.is_some(), *role)
+    });
+    for ((actor, mut transform, ..), data) in downloaded_entities.zip(&spatial_data) {
+        let downloaded = TransformComponent {
+            position: data.position.into(),
+            rotation: data.rotation.into(),
+            scale: data.scale.into(),
+        };
+        if policy.check(actor, &downloaded) {
+            *transform = downloaded;
+        }
+    }
+    scratch.actors.give_back(actors);
+    scratch.spatial_data.give_back(spatial_data);
 }
 
-impl Command for Despawn {
-    fn write(self, world: &mut World) {
-        world.despawn(self.entity);
-        if let Some(mut api) = world.get_resource_mut::<UnrealApi>() {
-            // If this entity had an actor, we will also remove it from the map. Otherwise
-            // `actor_to_entity` will grow indefinitely
-            if let Some(actor) = api.entity_to_actor.remove(&self.entity) {
-                api.actor_to_entity.remove(&actor);
-                unsafe {
-                    (bindings().actor_fns.destroy_actor)(actor.0);
-                }
+fn propagate_parent_transforms(
+    children: Query<(Entity, &ParentComponent, &LocalTransformComponent)>,
+    mut transforms: Query<&mut TransformComponent>,
+) {
+    let locals: HashMap<Entity, (Entity, &LocalTransformComponent)> = children
+        .iter()
+        .map(|(entity, parent, local)| (entity, (parent.parent, local)))
+        .collect();
+
+    // Parents have to be resolved before their children, so we walk up the chain until we hit
+    // an entity that has no local transform or that was already resolved.
+    let mut resolved: HashMap<Entity, TransformComponent> = HashMap::new();
+    let mut chain = Vec::new();
+    for &entity in locals.keys() {
+        let mut current = entity;
+        while let Some(&(parent, _)) = locals.get(&current) {
+            if resolved.contains_key(&current) {
+                break;
             }
+            if chain.contains(&current) {
+                log::error!("Cycle in the parent hierarchy of {:?}", current);
+                chain.clear();
+                break;
+            }
+            chain.push(current);
+            current = parent;
+        }
+
+        let mut parent_transform = resolved
+            .get(&current)
+            .cloned()
+            .or_else(|| transforms.get(current).ok().cloned());
+        while let Some(child) = chain.pop() {
+            // If the root does not exist, the whole chain is skipped
+            let Some(parent) = parent_transform else {
+                chain.clear();
+                break;
+            };
+            let transform = parent.mul_local(locals[&child].1);
+            resolved.insert(child, transform.clone());
+            parent_transform = Some(transform);
+        }
+    }
+
+    for (entity, transform) in resolved {
+        if let Ok(mut current) = transforms.get_mut(entity) {
+            *current = transform;
         }
     }
 }
 
-/// It can can that actors are destroyed inside unreal for example from the kill plane. We need to
-/// make sure to unregister them, otherwise we will end up with a dangling pointer in Rust.
-/// Here we actually despawn the whole entity instead of just removing the `ActorComponent` because
-/// it would be strange to keep the rust entity part alive, if the actor has been removed.
-fn process_actor_destroyed(
-    mut api: ResMut<UnrealApi>,
-    mut reader: EventReader<ActorDestroyEvent>,
-    mut commands: Commands,
+fn attach_to_sockets(
+    mut attached: Query<(Entity, &mut SocketAttachComponent)>,
+    actors: Query<&ActorComponent>,
+    mut transforms: Query<&mut TransformComponent>,
 ) {
-    for event in reader.iter() {
-        if let Some(entity) = api.actor_to_entity.remove(&event.actor) {
-            commands.add(Despawn { entity });
+    for (entity, mut attach) in attached.iter_mut() {
+        let Ok(parent_actor) = actors.get(attach.parent) else {
+            continue;
+        };
+        let socket = parent_actor.actor.get_socket_transform(&attach.socket_name);
+        if socket.is_none() && !attach.reported_missing {
+            log::warn!(
+                "Socket {} does not exist on the parent of {:?}, using the parent transform",
+                attach.socket_name,
+                entity
+            );
+            attach.reported_missing = true;
+        }
+        let Some(socket) = socket.or_else(|| transforms.get(attach.parent).ok().cloned()) else {
+            continue;
+        };
+        if let Ok(mut transform) = transforms.get_mut(entity) {
+            *transform = socket.mul_local(&attach.offset);
         }
     }
 }
 
-fn process_actor_spawned(
-    mut api: ResMut<UnrealApi>,
-    mut reader: EventReader<ActorSpawnedEvent>,
-    mut commands: Commands,
+fn validate_transforms(
+    mut query: Query<(
+        Entity,
+        &mut TransformComponent,
+        Option<&mut LastValidTransform>,
+    )>,
 ) {
-    unsafe {
-        if let Some(global) = crate::module::MODULE.as_mut() {
-            for &ActorSpawnedEvent { actor } in reader.iter() {
-                let mut entity_cmds = commands.spawn();
-
-                let mut len = 0;
-                (bindings().editor_component_fns.get_editor_components)(
-                    actor.0,
-                    std::ptr::null_mut(),
-                    &mut len,
+    for (entity, mut transform, last_valid) in query.iter_mut() {
+        match (transform.invalid_field(), last_valid) {
+            (None, Some(mut last_valid)) => last_valid.0 = transform.clone(),
+            (None, None) => {}
+            (Some(field), Some(last_valid)) => {
+                log::error!(
+                    "The {} of {:?} is invalid, resetting it to the last valid transform: {:?}",
+                    field,
+                    entity,
+                    *transform
                 );
+                *transform = last_valid.0.clone();
+            }
+            (Some(field), None) => {
+                log::error!("The {} of {:?} is invalid: {:?}", field, entity, *transform);
+            }
+        }
+    }
+}
 
-                let mut uuids = vec![ffi::Uuid::default(); len];
-                (bindings().editor_component_fns.get_editor_components)(
-                    actor.0,
-                    uuids.as_mut_ptr(),
-                    &mut len,
-                );
-                // We might have gotten back fewer uuids, so we truncate
-                uuids.truncate(len);
+fn store_previous_transforms(mut query: Query<(&TransformComponent, &mut PreviousTransform)>) {
+    for (transform, mut previous) in query.iter_mut() {
+        previous.0 = transform.clone();
+    }
+}
 
-                // We register all the components that are on the actor in unreal and add
-                // them to the entity
+type UploadTransformItem<'a> = (
+    &'a ActorComponent,
+    &'a TransformComponent,
+    Option<&'a PreviousTransform>,
+    Option<&'a NetRoleComponent>,
+);
+
+fn upload_transform_to_unreal(
+    policy: Res<TransformPolicy>,
+    fixed_frame: Res<FixedFrame>,
+    query: Query<UploadTransformItem, Without<TransformAuthorityUnreal>>,
+) {
+    for (actor, transform, previous, role) in query.iter() {
+        // Replication moves simulated proxies, autonomous proxies predict their own movement
+        if role.is_some_and(|role| role.role == NetRole::SimulatedProxy) {
+            continue;
+        }
+        let interpolated;
+        let transform = match previous {
+            Some(previous) => {
+                interpolated = previous.0.lerp(transform, fixed_frame.alpha);
+                &interpolated
+            }
+            None => transform,
+        };
+        let is_moveable = unsafe { (bindings().actor_fns.is_moveable)(actor.actor.0) } > 0;
+        if !is_moveable || !policy.check(actor, transform) {
+            continue;
+        }
+        (bindings().actor_fns.set_spatial_data)(
+            actor.actor.0,
+            transform.position.into(),
+            transform.rotation.into(),
+            transform.scale.into(),
+        );
+    }
+}
+
+/// Runs before `flush_component_changes`
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
+pub(crate) struct NotifyComponentChanges;
+
+/// Changes that are collected by the `notify_component_changed` systems of all observed
+/// components during `CoreStage::PostUpdate`, so that they are sent to unreal in a single call
+#[derive(Default)]
+pub(crate) struct PendingComponentChanges {
+    changes: Vec<(ActorPtr, ffi::Uuid)>,
+}
+
+pub(crate) fn notify_component_changed<T: Component + TypeUuid>(
+    mut pending: ResMut<PendingComponentChanges>,
+    query: Query<&ActorComponent, Changed<T>>,
+) {
+    let uuid = to_ffi_uuid(T::TYPE_UUID);
+    pending
+        .changes
+        .extend(query.iter().map(|actor| (actor.actor, uuid)));
+}
+
+fn flush_component_changes(mut pending: ResMut<PendingComponentChanges>) {
+    if pending.changes.is_empty() {
+        return;
+    }
+    if let Some(on_components_changed_bulk) = bindings().on_components_changed_bulk {
+        let changes: Vec<ffi::ComponentChange> = pending
+            .changes
+            .iter()
+            .map(|&(actor, uuid)| ffi::ComponentChange {
+                actor: actor.0,
+                uuid,
+            })
+            .collect();
+        unsafe {
+            on_components_changed_bulk(changes.as_ptr(), changes.len());
+        }
+    } else {
+        for &(actor, uuid) in &pending.changes {
+            unsafe {
+                (bindings().on_component_changed)(actor.0, uuid);
+            }
+        }
+    }
+    pending.changes.clear();
+}
+
+fn update_input(mut input: ResMut<Input>) {
+    input.update();
+}
+
+fn update_viewport(mut viewport: ResMut<Viewport>) {
+    let mut width = 0;
+    let mut height = 0;
+    let mut dpi_scale = 1.0;
+    unsafe {
+        (bindings().get_viewport_size)(&mut width, &mut height, &mut dpi_scale);
+    }
+    *viewport = Viewport {
+        width,
+        height,
+        dpi_scale,
+    };
+}
+
+fn update_action_map(frame: Res<Frame>, mut input: ResMut<Input>, mut actions: ResMut<ActionMap>) {
+    actions.update(&input, frame.elapsed);
+    // Newly bound unreal mappings are only polled by `Input` starting with the next frame
+    actions.register_bindings(&mut input);
+}
+
+fn poll_collision_events(
+    api: Res<UnrealApi>,
+    mut scratch: ResMut<ScratchBuffers>,
+    mut writer: EventWriter<CollisionEvent>,
+) {
+    let mut len = 0;
+    unsafe {
+        (bindings().actor_fns.poll_collision_events)(std::ptr::null_mut(), &mut len);
+    }
+    if len == 0 {
+        return;
+    }
+    let mut events = scratch.collision_events.take();
+    events.resize(len, ffi::CollisionEvent::default());
+    unsafe {
+        (bindings().actor_fns.poll_collision_events)(events.as_mut_ptr(), &mut len);
+    }
+    events.truncate(len);
+
+    for event in events.drain(..) {
+        // Actors that are not known to Rust are dropped
+        if let (Some(self_actor), Some(other_actor)) =
+            (api.entity(event.self_actor), api.entity(event.other))
+        {
+            let phase = CollisionPhase::from(event.phase);
+            let impact = (phase == CollisionPhase::Hit).then(|| CollisionImpact {
+                velocity: event.impact_velocity.into(),
+                point: event.impact_point.into(),
+            });
+            writer.send(CollisionEvent {
+                self_actor,
+                other_actor,
+                phase,
+                impact,
+            });
+        }
+    }
+    scratch.collision_events.give_back(events);
+}
+/// Runs `teleport` when the command is applied
+#[derive(Debug)]
+pub struct Teleport {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub rotation: Quat,
+}
+
+impl Command for Teleport {
+    fn write(self, world: &mut World) {
+        teleport(world, self.entity, self.position, self.rotation);
+    }
+}
+
+/// Runs `attach_actor` when the command is applied
+#[derive(Debug)]
+pub struct AttachActor {
+    pub child: Entity,
+    pub parent: Entity,
+    pub socket: Option<FName>,
+}
+
+impl Command for AttachActor {
+    fn write(self, world: &mut World) {
+        attach_actor(world, self.child, self.parent, self.socket);
+    }
+}
+
+/// Runs `detach_actor` when the command is applied
+#[derive(Debug)]
+pub struct DetachActor {
+    pub child: Entity,
+}
+
+impl Command for DetachActor {
+    fn write(self, world: &mut World) {
+        detach_actor(world, self.child);
+    }
+}
+
+/// Despawns `entity` and destroys its actor. Both happen at the end of `CoreStage::PostUpdate`,
+/// after all transforms have been uploaded, so the entity is still around for the rest of the
+/// frame. Despawning the same entity twice is a no-op.
+#[derive(Debug)]
+pub struct Despawn {
+    pub entity: Entity,
+}
+
+impl Command for Despawn {
+    fn write(self, world: &mut World) {
+        if let Some(mut pending) = world.get_resource_mut::<PendingActorDespawns>() {
+            pending.entities.push(self.entity);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PendingActorDespawns {
+    entities: Vec<Entity>,
+}
+
+fn process_actor_despawn_requests(world: &mut World) {
+    let entities = std::mem::take(&mut world.resource_mut::<PendingActorDespawns>().entities);
+    for entity in entities {
+        // If this entity had an actor, we will also remove it from the map. Otherwise
+        // `actor_to_entity` will grow indefinitely
+        world
+            .resource_mut::<NetIdRegistry>()
+            .unregister_entity(&entity);
+        if let Some(actor) = world.resource_mut::<UnrealApi>().unregister_entity(&entity) {
+            unsafe {
+                (bindings().actor_fns.destroy_actor)(actor.0);
+            }
+        }
+        if world.get_entity(entity).is_some() {
+            world.despawn(entity);
+        }
+    }
+}
+
+/// Spawns a new actor in unreal and attaches it to `entity`. The actor is not created right away,
+/// it is spawned in `CoreStage::PostUpdate` after all transforms have been uploaded. Until then
+/// the entity only has a `TransformComponent`. Core can not depend on the gameplay plugins, so
+/// components like the `MovementComponent` are never added here, the caller has to insert them.
+/// They can be inserted on the entity right away.
+#[derive(Debug)]
+pub struct SpawnActor {
+    pub entity: Entity,
+    pub class: SpawnClass,
+    pub transform: TransformComponent,
+    /// Adds a `PhysicsComponent` if the root component of the spawned actor is a primitive
+    pub physics: bool,
+}
+
+impl SpawnActor {
+    pub fn new(
+        entity: Entity,
+        class: impl Into<SpawnClass>,
+        transform: TransformComponent,
+    ) -> Self {
+        Self {
+            entity,
+            class: class.into(),
+            transform,
+            physics: false,
+        }
+    }
+
+    pub fn with_physics(mut self) -> Self {
+        self.physics = true;
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum SpawnClass {
+    /// One of the classes that are built into the plugin
+    Builtin(ffi::ActorClass),
+    /// A class that was loaded with `AssetRegistry::register_class`
+    Asset(ClassHandle),
+}
+
+impl From<ffi::ActorClass> for SpawnClass {
+    fn from(class: ffi::ActorClass) -> Self {
+        SpawnClass::Builtin(class)
+    }
+}
+
+impl From<ClassHandle> for SpawnClass {
+    fn from(class: ClassHandle) -> Self {
+        SpawnClass::Asset(class)
+    }
+}
+
+impl Command for SpawnActor {
+    fn write(self, world: &mut World) {
+        if let Some(mut entity) = world.get_entity_mut(self.entity) {
+            entity.insert(self.transform.clone());
+        }
+        if let Some(mut pending) = world.get_resource_mut::<PendingActorSpawns>() {
+            pending.requests.push(self);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PendingActorSpawns {
+    requests: Vec<SpawnActor>,
+}
+
+#[derive(Debug)]
+pub enum SpawnActorError {
+    /// Unreal did not return an actor
+    NullActor,
+}
+
+impl std::fmt::Display for SpawnActorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpawnActorError::NullActor => write!(f, "Unreal returned a null actor"),
+        }
+    }
+}
+
+fn spawn_actor(request: &SpawnActor) -> Result<ActorPtr, SpawnActorError> {
+    let transform = &request.transform;
+    let actor = unsafe {
+        match request.class {
+            SpawnClass::Builtin(class) => (bindings().spawn_actor)(
+                class,
+                transform.position.into(),
+                transform.rotation.into(),
+                transform.scale.into(),
+            ),
+            SpawnClass::Asset(class) => (bindings().spawn_actor_from_class)(
+                class.class().ptr,
+                transform.position.into(),
+                transform.rotation.into(),
+                transform.scale.into(),
+            ),
+        }
+    };
+    if actor.is_null() {
+        Err(SpawnActorError::NullActor)
+    } else {
+        Ok(ActorPtr(actor))
+    }
+}
+
+fn process_actor_spawn_requests(
+    mut pending: ResMut<PendingActorSpawns>,
+    mut api: ResMut<UnrealApi>,
+    mut commands: Commands,
+) {
+    for request in pending.requests.drain(..) {
+        let actor = match spawn_actor(&request) {
+            Ok(actor) => actor,
+            Err(err) => {
+                log::error!("Unable to spawn actor for {:?}: {}", request.entity, err);
+                commands.entity(request.entity).despawn();
+                continue;
+            }
+        };
+
+        let mut entity_cmds = commands.entity(request.entity);
+        entity_cmds.insert(ActorComponent { actor });
+
+        if request.physics {
+            let mut root_component = ActorComponentPtr::default();
+            unsafe {
+                (bindings().actor_fns.get_root_component)(actor.0, &mut root_component);
+            }
+            if root_component.ty == ActorComponentType::Primitive && !root_component.ptr.is_null() {
+                entity_cmds.insert(PhysicsComponent::new(UnrealPtr::from_raw(
+                    root_component.ptr,
+                )));
+            }
+        }
+
+        api.register_actor(actor, request.entity);
+        unsafe {
+            (bindings().actor_fns.set_entity_for_actor)(
+                actor.0,
+                ffi::Entity {
+                    id: request.entity.to_bits(),
+                },
+            );
+        }
+    }
+}
+
+/// It can can that actors are destroyed inside unreal for example from the kill plane. We need to
+/// make sure to unregister them, otherwise we will end up with a dangling pointer in Rust.
+/// Here we actually despawn the whole entity instead of just removing the `ActorComponent` because
+/// it would be strange to keep the rust entity part alive, if the actor has been removed.
+fn process_actor_destroyed(
+    mut api: ResMut<UnrealApi>,
+    mut net_ids: ResMut<NetIdRegistry>,
+    mut reader: EventReader<ActorDestroyEvent>,
+    mut commands: Commands,
+) {
+    for event in reader.iter() {
+        // Actors that were never registered are simply ignored
+        if let Some(entity) = api.unregister_actor(&event.actor) {
+            net_ids.unregister_entity(&entity);
+            // We can not use `Despawn` here, the actor is already being destroyed by unreal
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+type InsertClassBundle = Box<dyn Fn(&mut EntityCommands) + Send + Sync>;
+
+/// Maps unreal class names to components that are added to every actor of that class when it
+/// is registered, on top of the components that every actor gets.
+#[derive(Default)]
+pub struct ClassBundleRegistry {
+    bundles: HashMap<String, InsertClassBundle>,
+}
+
+impl ClassBundleRegistry {
+    /// Blueprint classes can be registered with or without the `_C` suffix that unreal adds
+    pub fn register<B>(&mut self, class_name: impl Into<String>, bundle: B)
+    where
+        B: Bundle + Clone + Send + Sync,
+    {
+        self.bundles.insert(
+            class_name.into(),
+            Box::new(move |entity: &mut EntityCommands| {
+                entity.insert_bundle(bundle.clone());
+            }),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+
+    fn get(&self, class_name: &str) -> Option<&InsertClassBundle> {
+        self.bundles.get(class_name).or_else(|| {
+            class_name
+                .strip_suffix("_C")
+                .and_then(|name| self.bundles.get(name))
+        })
+    }
+}
+
+fn process_actor_spawned(
+    mut api: ResMut<UnrealApi>,
+    mut net_ids: ResMut<NetIdRegistry>,
+    mut reader: EventReader<ActorSpawnedEvent>,
+    mut commands: Commands,
+    class_bundles: Res<ClassBundleRegistry>,
+) {
+    unsafe {
+        // Editor components can only be inserted while the module is loaded
+        let registry =
+            crate::module::global().map(|global| &global.core.module.reflection_registry);
+        for &ActorSpawnedEvent { actor } in reader.iter() {
+            // Actors that were spawned from Rust are already registered
+            if api.actor_to_entity.contains_key(&actor) {
+                continue;
+            }
+            let mut entity_cmds = commands.spawn();
+
+            if let Some(registry) = registry {
+                let mut len = 0;
+                (bindings().editor_component_fns.get_editor_components)(
+                    actor.0,
+                    std::ptr::null_mut(),
+                    &mut len,
+                );
+
+                let mut uuids = vec![ffi::Uuid::default(); len];
+                (bindings().editor_component_fns.get_editor_components)(
+                    actor.0,
+                    uuids.as_mut_ptr(),
+                    &mut len,
+                );
+                // We might have gotten back fewer uuids, so we truncate
+                uuids.truncate(len);
+
+                // We register all the components that are on the actor in unreal and add
+                // them to the entity
                 for uuid in uuids {
                     let uuid = from_ffi_uuid(uuid);
-                    if let Some(insert) = global
-                        .core
-                        .module
-                        .reflection_registry
-                        .insert_editor_component
-                        .get(&uuid)
-                    {
+                    if let Some(insert) = registry.insert_editor_component.get(&uuid) {
                         insert.insert_component(actor.0, uuid, &mut entity_cmds);
                     }
                 }
+            }
 
-                let entity = entity_cmds
-                    .insert_bundle((ActorComponent { actor }, TransformComponent::default()))
-                    .id();
-
-                // Create a physics component if the root component is a primitive
-                // component
-                // TODO: We probably should get ALL the primitive components as well
-                let mut root_component = ActorComponentPtr::default();
-                (bindings().actor_fns.get_root_component)(actor.0, &mut root_component);
-                if root_component.ty == ActorComponentType::Primitive
-                    && !root_component.ptr.is_null()
-                {
-                    let physics_component =
-                        PhysicsComponent::new(UnrealPtr::from_raw(root_component.ptr));
-                    commands.entity(entity).insert(physics_component);
+            entity_cmds.insert_bundle((
+                ActorComponent { actor },
+                TransformComponent::default(),
+                TagsComponent {
+                    tags: actor.get_tags(),
+                },
+                VisibilityComponent {
+                    hidden: actor.is_hidden(),
+                },
+            ));
+            if !class_bundles.is_empty() {
+                if let Some(insert) = class_bundles.get(&actor.get_class_name()) {
+                    insert(&mut entity_cmds);
                 }
+            }
+            let entity = entity_cmds.id();
 
-                api.register_actor(actor, entity);
-
-                // Update the `EntityComponent` with the entity id so we can easily access
-                // it in blueprint etc
-                (bindings().actor_fns.set_entity_for_actor)(
-                    actor.0,
-                    ffi::Entity {
-                        id: entity.to_bits(),
+            if let Some(net_id) = actor.get_net_id() {
+                commands.entity(entity).insert_bundle((
+                    net_id,
+                    NetRoleComponent {
+                        role: actor.get_net_role(),
                     },
+                ));
+                net_ids.register(net_id, entity);
+            }
+
+            // Create a physics component if the root component is a primitive
+            // component
+            // TODO: We probably should get ALL the primitive components as well
+            let mut root_component = ActorComponentPtr::default();
+            (bindings().actor_fns.get_root_component)(actor.0, &mut root_component);
+            if root_component.ty == ActorComponentType::Primitive && !root_component.ptr.is_null() {
+                let physics_component =
+                    PhysicsComponent::new(UnrealPtr::from_raw(root_component.ptr));
+                commands.entity(entity).insert(physics_component);
+            }
+
+            api.register_actor(actor, entity);
+
+            // Update the `EntityComponent` with the entity id so we can easily access
+            // it in blueprint etc
+            (bindings().actor_fns.set_entity_for_actor)(
+                actor.0,
+                ffi::Entity {
+                    id: entity.to_bits(),
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_vec_eq;
+    use crate::testing;
+    use bevy_ecs::schedule::IntoSystemDescriptor;
+
+    struct TestModule(fn(&mut Module));
+
+    impl UserModule for TestModule {
+        fn initialize(&self, module: &mut Module) {
+            (self.0)(module)
+        }
+    }
+
+    fn start() -> UnrealCore {
+        start_with(|_| {})
+    }
+
+    fn start_with(initialize: fn(&mut Module)) -> UnrealCore {
+        testing::install();
+        let module = TestModule(initialize);
+        let mut core = UnrealCore::new(&module);
+        core.begin_play(&module);
+        core
+    }
+
+    fn spawn(core: &mut UnrealCore) -> ActorPtr {
+        let actor = ActorPtr(testing::spawn_actor());
+        core.module.world.send_event(ActorSpawnedEvent { actor });
+        actor
+    }
+
+    fn entity_of(core: &UnrealCore, actor: ActorPtr) -> Entity {
+        core.module.world.resource::<UnrealApi>().actor_to_entity[&actor]
+    }
+
+    fn read_events<E: Send + Sync + Clone + 'static>(core: &UnrealCore) -> Vec<E> {
+        let events = core.module.world.resource::<Events<E>>();
+        events.get_reader().iter(events).cloned().collect()
+    }
+
+    fn actor_count(core: &mut UnrealCore) -> usize {
+        core.module
+            .world
+            .query::<&ActorComponent>()
+            .iter(&core.module.world)
+            .count()
+    }
+
+    #[test]
+    fn destroyed_actor_is_unregistered() {
+        let mut core = start();
+        let first = spawn(&mut core);
+        let second = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        assert_eq!(actor_count(&mut core), 2);
+
+        core.module
+            .world
+            .send_event(ActorDestroyEvent { actor: first });
+        core.tick(1.0 / 60.0);
+
+        assert_eq!(actor_count(&mut core), 1);
+        let api = core.module.world.resource::<UnrealApi>();
+        assert!(!api.actor_to_entity.contains_key(&first));
+        assert!(api.actor_to_entity.contains_key(&second));
+        assert_eq!(api.entity_to_actor.len(), 1);
+    }
+
+    #[test]
+    fn collision_events_resolve_entities() {
+        let mut core = start();
+        let first = spawn(&mut core);
+        let second = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+
+        let unknown = testing::spawn_actor();
+        testing::with(|unreal| {
+            unreal.collision_events = vec![
+                ffi::CollisionEvent {
+                    self_actor: first.0,
+                    other: second.0,
+                    phase: ffi::CollisionPhase::Begin,
+                    ..Default::default()
+                },
+                ffi::CollisionEvent {
+                    self_actor: first.0,
+                    other: unknown,
+                    phase: ffi::CollisionPhase::Begin,
+                    ..Default::default()
+                },
+            ]
+        });
+        core.tick(1.0 / 60.0);
+
+        let events = read_events::<CollisionEvent>(&core);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].self_actor, entity_of(&core, first));
+        assert_eq!(events[0].other_actor, entity_of(&core, second));
+        assert_eq!(events[0].phase, CollisionPhase::Begin);
+        assert!(events[0].impact.is_none());
+    }
+
+    #[test]
+    fn hit_events_carry_the_impact() {
+        let mut core = start();
+        let first = spawn(&mut core);
+        let second = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+
+        testing::with(|unreal| {
+            unreal.collision_events = vec![ffi::CollisionEvent {
+                self_actor: first.0,
+                other: second.0,
+                phase: ffi::CollisionPhase::Hit,
+                impact_velocity: Vec3::new(0.0, 300.0, -400.0).into(),
+                impact_point: Vec3::new(10.0, 20.0, 30.0).into(),
+            }]
+        });
+        core.tick(1.0 / 60.0);
+
+        let events = read_events::<CollisionEvent>(&core);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].phase, CollisionPhase::Hit);
+        let impact = events[0].impact.expect("hits carry an impact");
+        assert_eq!(impact.velocity, Vec3::new(0.0, 300.0, -400.0));
+        assert_eq!(impact.point, Vec3::new(10.0, 20.0, 30.0));
+        assert_eq!(events[0].impact_speed(), Some(500.0));
+    }
+
+    #[test]
+    fn fixed_timestep_substeps() {
+        let mut fixed = FixedTimestep::new(0.1, 3);
+        assert_eq!(fixed.advance(0.05), 0);
+        assert_eq!(fixed.advance(0.07), 1);
+        assert!((fixed.alpha() - 0.2).abs() < 1e-4);
+        assert_eq!(fixed.advance(0.21), 2);
+        // Too far behind, the remaining time is dropped
+        assert_eq!(fixed.advance(1.0), 3);
+        assert_eq!(fixed.alpha(), 0.0);
+    }
+
+    #[derive(Default)]
+    struct Counter(u32);
+
+    fn count(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    #[test]
+    fn fixed_update_runs_substeps() {
+        let mut core = start_with(|module| {
+            module
+                .insert_resource(Counter::default())
+                .set_fixed_timestep(0.1, 5)
+                .add_system_set_to_stage(
+                    CoreStage::FixedUpdate,
+                    SystemSet::new().with_system(count),
+                );
+        });
+        for dt in [0.05, 0.07, 0.21, 0.02] {
+            core.tick(dt);
+        }
+        assert_eq!(core.module.world.resource::<Counter>().0, 3);
+        assert_eq!(core.module.world.resource::<FixedFrame>().dt, 0.1);
+    }
+
+    #[test]
+    fn run_after_frames_runs_only_in_that_frame() {
+        let mut core = start_with(|module| {
+            module
+                .insert_resource(Counter::default())
+                .add_system_set_to_stage(
+                    CoreStage::Update,
+                    SystemSet::new()
+                        .with_run_criteria(run_after_frames(3))
+                        .with_system(count),
+                );
+        });
+        let mut counts = Vec::new();
+        for _ in 0..5 {
+            core.tick(1.0 / 60.0);
+            counts.push(core.module.world.resource::<Counter>().0);
+        }
+        assert_eq!(counts, [0, 0, 1, 1, 1]);
+    }
+
+    fn run_system<Params>(world: &mut World, system: impl IntoSystemDescriptor<Params>) {
+        let mut stage = SystemStage::single_threaded();
+        stage.add_system(system);
+        stage.run(world);
+    }
+
+    fn local(position: Vec3, rotation: Quat) -> LocalTransformComponent {
+        LocalTransformComponent {
+            position,
+            rotation,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parent_chains_resolve() {
+        let mut world = World::new();
+        let root = world
+            .spawn()
+            .insert(TransformComponent {
+                position: Vec3::new(10.0, 0.0, 0.0),
+                rotation: Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+                scale: Vec3::ONE,
+            })
+            .id();
+        let child = world
+            .spawn()
+            .insert_bundle((
+                TransformComponent::default(),
+                ParentComponent { parent: root },
+                local(Vec3::X, Quat::IDENTITY),
+            ))
+            .id();
+        let grandchild = world
+            .spawn()
+            .insert_bundle((
+                TransformComponent::default(),
+                ParentComponent { parent: child },
+                local(Vec3::new(0.0, 2.0, 0.0), Quat::IDENTITY),
+            ))
+            .id();
+
+        run_system(&mut world, propagate_parent_transforms);
+
+        // Two levels, the rotation of the root turns +X into +Y
+        let child = world.get::<TransformComponent>(child).unwrap();
+        assert_vec_eq!(child.position, Vec3::new(10.0, 1.0, 0.0));
+        // Three levels, +Y turns into -X
+        let grandchild = world.get::<TransformComponent>(grandchild).unwrap();
+        assert_vec_eq!(grandchild.position, Vec3::new(8.0, 1.0, 0.0));
+    }
+
+    #[derive(Default)]
+    struct Order(Vec<&'static str>);
+
+    fn record(name: &'static str) -> impl FnMut(ResMut<Order>) {
+        move |mut order: ResMut<Order>| order.0.push(name)
+    }
+
+    #[test]
+    fn custom_stages_run_in_order() {
+        let mut core = start_with(|module| {
+            module
+                .insert_resource(Order::default())
+                .add_stage_after(CoreStage::Update, "AfterUpdate")
+                .add_stage_before(CoreStage::Update, "BeforeUpdate")
+                // Stages can be positioned relative to custom stages as well
+                .add_stage_after("AfterUpdate", "Last")
+                .add_system_set_to_stage("Last", SystemSet::new().with_system(record("last")))
+                .add_system_set_to_stage(
+                    "AfterUpdate",
+                    SystemSet::new().with_system(record("after")),
+                )
+                .add_system_set_to_stage(
+                    CoreStage::Update,
+                    SystemSet::new().with_system(record("update")),
+                )
+                .add_system_set_to_stage(
+                    "BeforeUpdate",
+                    SystemSet::new().with_system(record("before")),
+                );
+        });
+        core.tick(1.0 / 60.0);
+        assert_eq!(
+            core.module.world.resource::<Order>().0,
+            ["before", "update", "after", "last"]
+        );
+    }
+
+    #[derive(Default)]
+    struct OtherCounter(u32);
+
+    fn count_other(mut counter: ResMut<OtherCounter>) {
+        counter.0 += 1;
+    }
+
+    #[test]
+    fn parallel_stage_runs_its_systems() {
+        let mut core = start_with(|module| {
+            module
+                .insert_resource(Counter::default())
+                .insert_resource(OtherCounter::default())
+                .add_parallel_stage_after(CoreStage::Update, "Parallel")
+                .add_system_set_to_stage(
+                    "Parallel",
+                    SystemSet::new().with_system(count).with_system(count_other),
+                );
+        });
+        core.tick(1.0 / 60.0);
+        core.tick(1.0 / 60.0);
+        assert_eq!(core.module.world.resource::<Counter>().0, 2);
+        assert_eq!(core.module.world.resource::<OtherCounter>().0, 2);
+
+        let stage = core
+            .module
+            .schedule
+            .get_stage::<SystemStage>(&"Parallel")
+            .unwrap();
+        assert_eq!(stage.parallel_systems().len(), 2);
+    }
+
+    fn system_names(schedule: &Schedule) -> Vec<String> {
+        use bevy_ecs::schedule::GraphNode;
+        let mut names = Vec::new();
+        for (label, stage) in schedule.iter_stages() {
+            let stage = stage.downcast_ref::<SystemStage>().unwrap();
+            let systems = stage
+                .parallel_systems()
+                .iter()
+                .map(|system| system.name())
+                .chain(stage.exclusive_at_start_systems().iter().map(|s| s.name()))
+                .chain(
+                    stage
+                        .exclusive_before_commands_systems()
+                        .iter()
+                        .map(|s| s.name()),
+                )
+                .chain(stage.exclusive_at_end_systems().iter().map(|s| s.name()));
+            names.extend(systems.map(|name| format!("{:?}: {}", label, name)));
+        }
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn new_and_begin_play_build_the_same_schedule() {
+        testing::install();
+        let module = TestModule(|_| {});
+        let created = UnrealCore::new(&module);
+        let mut started = UnrealCore::new(&module);
+        started.begin_play(&module);
+
+        let names = system_names(&created.module.schedule);
+        assert_eq!(names, system_names(&started.module.schedule));
+        for system in [
+            "update_input",
+            "download_transform_from_unreal",
+            "upload_transform_to_unreal",
+        ] {
+            assert!(
+                names.iter().any(|name| name.ends_with(system)),
+                "{} is missing",
+                system
+            );
+        }
+    }
+
+    fn spawn_physics(core: &mut UnrealCore) -> (Entity, *mut c_void) {
+        let (actor, primitive) = testing::spawn_physics_actor();
+        core.module.world.send_event(ActorSpawnedEvent {
+            actor: ActorPtr(actor),
+        });
+        core.tick(1.0 / 60.0);
+        (entity_of(core, ActorPtr(actor)), primitive)
+    }
+
+    fn physics(core: &mut UnrealCore, entity: Entity) -> Mut<'_, PhysicsComponent> {
+        core.module
+            .world
+            .get_mut::<PhysicsComponent>(entity)
+            .unwrap()
+    }
+
+    #[test]
+    fn physics_velocity_syncs_with_unreal() {
+        let mut core = start();
+        let (entity, primitive) = spawn_physics(&mut core);
+        testing::with(|unreal| {
+            let primitive = unreal.primitive_mut(primitive);
+            primitive.velocity = Vec3::new(10.0, 0.0, 0.0);
+            primitive.angular_velocity = Vec3::Z;
+        });
+        core.tick(1.0 / 60.0);
+        assert_eq!(
+            physics(&mut core, entity).velocity(),
+            Vec3::new(10.0, 0.0, 0.0)
+        );
+        assert_eq!(physics(&mut core, entity).angular_velocity(), Vec3::Z);
+
+        // Only changes made through the setters are uploaded
+        testing::take_calls();
+        core.tick(1.0 / 60.0);
+        assert!(!testing::calls()
+            .iter()
+            .any(|call| matches!(call, testing::Call::SetVelocity { .. })));
+
+        physics(&mut core, entity).set_velocity(Vec3::Y);
+        core.tick(1.0 / 60.0);
+        assert!(testing::calls().contains(&testing::Call::SetVelocity {
+            primitive,
+            velocity: Vec3::Y,
+        }));
+        core.tick(1.0 / 60.0);
+        assert_eq!(physics(&mut core, entity).velocity(), Vec3::Y);
+    }
+
+    #[test]
+    fn look_at_aligns_forward() {
+        let mut transform = TransformComponent {
+            position: Vec3::new(1.0, 2.0, 3.0),
+            ..Default::default()
+        };
+        let target = Vec3::new(4.0, -2.0, 8.0);
+        transform.look_at(target, Vec3::Z);
+
+        assert_vec_eq!(
+            transform.forward(),
+            (target - transform.position).normalize(),
+        );
+        assert!(transform.rotation.is_normalized());
+        // The right vector stays horizontal
+        assert!(transform.right().z.abs() < 1e-5);
+    }
+
+    #[test]
+    fn look_at_keeps_rotation_when_degenerate() {
+        let rotation = Quat::from_rotation_z(0.5);
+        let mut transform = TransformComponent {
+            rotation,
+            ..Default::default()
+        };
+        transform.look_at(transform.position, Vec3::Z);
+        assert_eq!(transform.rotation, rotation);
+        transform.look_at(Vec3::Z * 10.0, Vec3::Z);
+        assert_eq!(transform.rotation, rotation);
+    }
+
+    #[test]
+    fn frame_counts_ticks() {
+        let mut core = start();
+        assert_eq!(core.module.world.resource::<Frame>().count, 0);
+        for dt in [0.5, 0.25, 0.125] {
+            core.tick(dt);
+        }
+        let frame = core.module.world.resource::<Frame>();
+        assert_eq!(frame.count, 3);
+        assert_eq!(frame.elapsed, 0.875);
+        assert_eq!(frame.dt, 0.125);
+
+        // Play starts over with a new frame
+        core.begin_play(&TestModule(|_| {}));
+        assert_eq!(core.module.world.resource::<Frame>().count, 0);
+    }
+
+    fn spawn_request(core: &mut UnrealCore, position: Vec3) -> Entity {
+        let entity = core.module.world.spawn().id();
+        let transform = TransformComponent {
+            position,
+            ..Default::default()
+        };
+        SpawnActor::new(entity, ffi::ActorClass::RustActor, transform)
+            .write(&mut core.module.world);
+        entity
+    }
+
+    #[test]
+    fn spawned_actor_is_registered() {
+        let mut core = start();
+        let entity = spawn_request(&mut core, Vec3::new(1.0, 2.0, 3.0));
+        core.tick(1.0 / 60.0);
+
+        let actor = core
+            .module
+            .world
+            .get::<ActorComponent>(entity)
+            .unwrap()
+            .actor;
+        let api = core.module.world.resource::<UnrealApi>();
+        assert_eq!(api.actor_to_entity.get(&actor), Some(&entity));
+        assert!(testing::calls().contains(&testing::Call::SpawnActor {
+            actor: actor.0,
+            position: Vec3::new(1.0, 2.0, 3.0),
+        }));
+    }
+
+    #[test]
+    fn failed_spawn_despawns_entity() {
+        let mut core = start();
+        testing::with(|unreal| unreal.fail_spawns = true);
+        let entity = spawn_request(&mut core, Vec3::ZERO);
+        core.tick(1.0 / 60.0);
+
+        assert!(core.module.world.get_entity(entity).is_none());
+        assert!(core
+            .module
+            .world
+            .resource::<UnrealApi>()
+            .actor_to_entity
+            .is_empty());
+    }
+
+    #[derive(Debug, Component, Clone)]
+    #[uuid = "3d4b66d3-a90f-44cc-a766-3f838fcbb903"]
+    struct Door;
+
+    #[derive(Debug, Component, Clone)]
+    #[uuid = "a4df8dcd-bde9-440d-a17f-57d5aee70ad5"]
+    struct Pickup;
+
+    fn spawn_class(core: &mut UnrealCore, class_name: &str) -> Entity {
+        let actor = spawn(core);
+        testing::with(|unreal| unreal.actor_mut(actor.0).class_name = class_name.into());
+        core.tick(1.0 / 60.0);
+        entity_of(core, actor)
+    }
+
+    #[test]
+    fn class_bundles_are_inserted_per_class() {
+        let mut core = start_with(|module| {
+            module
+                .register_class_bundle("BP_Door", (Door,))
+                .register_class_bundle("BP_Pickup", (Pickup,));
+        });
+        let door = spawn_class(&mut core, "BP_Door_C");
+        let pickup = spawn_class(&mut core, "BP_Pickup");
+        let other = spawn_class(&mut core, "BP_Other_C");
+
+        let world = &core.module.world;
+        assert!(world.get::<Door>(door).is_some());
+        assert!(world.get::<Pickup>(door).is_none());
+        assert!(world.get::<Pickup>(pickup).is_some());
+        assert!(world.get::<Door>(pickup).is_none());
+        assert!(world.get::<Door>(other).is_none());
+        assert!(world.get::<Pickup>(other).is_none());
+    }
+
+    fn uploaded_positions(calls: &[testing::Call], actor: ActorPtr) -> Vec<Vec3> {
+        calls
+            .iter()
+            .filter_map(|call| match call {
+                testing::Call::SetSpatialData {
+                    actor: uploaded,
+                    position,
+                    ..
+                } if *uploaded == actor.0 => Some(*position),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nan_transforms_are_skipped() {
+        let mut core = start_with(|module| {
+            module.insert_resource(TransformPolicy::Skip);
+        });
+        let downloaded = spawn(&mut core);
+        let uploaded = spawn(&mut core);
+        let position = Vec3::new(1.0, 2.0, 3.0);
+        testing::with(|unreal| unreal.actor_mut(downloaded.0).position = position);
+        core.tick(1.0 / 60.0);
+        let downloaded_entity = entity_of(&core, downloaded);
+        let uploaded_entity = entity_of(&core, uploaded);
+        core.module
+            .world
+            .entity_mut(uploaded_entity)
+            .insert(TransformAuthorityRust)
+            .get_mut::<TransformComponent>()
+            .unwrap()
+            .position = Vec3::NAN;
+
+        testing::with(|unreal| unreal.actor_mut(downloaded.0).position = Vec3::NAN);
+        testing::take_calls();
+        core.tick(1.0 / 60.0);
+        let calls = testing::take_calls();
+
+        let world = &core.module.world;
+        let transform = world.get::<TransformComponent>(downloaded_entity).unwrap();
+        assert_eq!(transform.position, position);
+        assert_eq!(uploaded_positions(&calls, downloaded), vec![position]);
+        assert!(world
+            .get::<TransformComponent>(uploaded_entity)
+            .unwrap()
+            .is_nan());
+        assert!(uploaded_positions(&calls, uploaded).is_empty());
+    }
+
+    #[test]
+    fn reload_state_survives_begin_play() {
+        fn initialize(module: &mut Module) {
+            module.preserve_state_on_reload();
+        }
+        let mut core = start_with(initialize);
+        let actor = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        let input = PlayerInputComponent {
+            direction: Vec3::X,
+            ..Default::default()
+        };
+        let entity = entity_of(&core, actor);
+        core.module.world.entity_mut(entity).insert(input);
+        let bytes = core.prepare_reload();
+
+        let module = TestModule(initialize);
+        let mut reloaded = UnrealCore::new(&module);
+        reloaded.finish_reload(&bytes).unwrap();
+        reloaded.begin_play(&module);
+        reloaded.tick(1.0 / 60.0);
+
+        let entity = entity_of(&reloaded, actor);
+        let input = reloaded.module.world.get::<PlayerInputComponent>(entity);
+        assert_eq!(input.unwrap().direction, Vec3::X);
+        assert_eq!(actor_count(&mut reloaded), 1);
+        assert!(reloaded.reload_state.is_none());
+    }
+
+    #[test]
+    fn transform_authority_modes() {
+        let mut core = start();
+        let both = spawn(&mut core);
+        let rust = spawn(&mut core);
+        let unreal = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        let in_unreal = Vec3::new(1.0, 0.0, 0.0);
+        let in_rust = Vec3::new(0.0, 2.0, 0.0);
+        for actor in [both, rust, unreal] {
+            testing::with(|mock| mock.actor_mut(actor.0).position = in_unreal);
+            let entity = entity_of(&core, actor);
+            let mut entity = core.module.world.entity_mut(entity);
+            entity.get_mut::<TransformComponent>().unwrap().position = in_rust;
+            if actor == rust {
+                entity.insert(TransformAuthorityRust);
+            } else if actor == unreal {
+                entity.insert(TransformAuthorityUnreal);
+            }
+        }
+        testing::take_calls();
+        core.tick(1.0 / 60.0);
+        let calls = testing::take_calls();
+
+        let position = |actor| {
+            let entity = entity_of(&core, actor);
+            core.module
+                .world
+                .get::<TransformComponent>(entity)
+                .unwrap()
+                .position
+        };
+        let uploads = |actor| uploaded_positions(&calls, actor);
+        assert_eq!(position(both), in_unreal);
+        assert_eq!(uploads(both), vec![in_unreal]);
+        assert_eq!(position(rust), in_rust);
+        assert_eq!(uploads(rust), vec![in_rust]);
+        assert_eq!(position(unreal), in_unreal);
+        assert!(uploads(unreal).is_empty());
+    }
+
+    #[test]
+    fn transforms_are_downloaded_in_bulk() {
+        let mut core = start();
+        let actors: Vec<_> = (0..5).map(|_| spawn(&mut core)).collect();
+        core.tick(1.0 / 60.0);
+        for (i, actor) in actors.iter().enumerate() {
+            testing::with(|unreal| unreal.actor_mut(actor.0).position = Vec3::X * i as f32);
+        }
+        testing::take_calls();
+        core.tick(1.0 / 60.0);
+
+        let downloads: Vec<_> = testing::take_calls()
+            .into_iter()
+            .filter(|call| {
+                matches!(
+                    call,
+                    testing::Call::GetSpatialData { .. } | testing::Call::GetSpatialDataBulk { .. }
+                )
+            })
+            .collect();
+        assert_eq!(
+            downloads,
+            vec![testing::Call::GetSpatialDataBulk { len: 5 }]
+        );
+        for (i, &actor) in actors.iter().enumerate() {
+            let entity = entity_of(&core, actor);
+            let transform = core.module.world.get::<TransformComponent>(entity).unwrap();
+            assert_eq!(transform.position, Vec3::X * i as f32);
+        }
+    }
+
+    #[test]
+    fn actor_tags_are_registered() {
+        let mut core = start();
+        let enemy = spawn(&mut core);
+        let untagged = spawn(&mut core);
+        testing::with(|unreal| {
+            unreal.actor_mut(enemy.0).tags = vec!["Enemy".into(), "Boss".into()];
+        });
+        core.tick(1.0 / 60.0);
+
+        let world = &core.module.world;
+        let tags = world.get::<TagsComponent>(entity_of(&core, enemy)).unwrap();
+        assert_eq!(tags.tags, vec!["Enemy", "Boss"]);
+        assert!(tags.has_tag("Boss"));
+        assert!(!tags.has_tag("Pickup"));
+        let tags = world
+            .get::<TagsComponent>(entity_of(&core, untagged))
+            .unwrap();
+        assert!(tags.tags.is_empty());
+    }
+
+    #[test]
+    fn despawn_destroys_the_actor_once() {
+        let mut core = start();
+        let actor = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        let entity = entity_of(&core, actor);
+
+        testing::take_calls();
+        Despawn { entity }.write(&mut core.module.world);
+        Despawn { entity }.write(&mut core.module.world);
+        core.tick(1.0 / 60.0);
+        Despawn { entity }.write(&mut core.module.world);
+        core.tick(1.0 / 60.0);
+
+        let destroyed = testing::take_calls()
+            .into_iter()
+            .filter(|call| matches!(call, testing::Call::DestroyActor { .. }))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            destroyed,
+            vec![testing::Call::DestroyActor { actor: actor.0 }]
+        );
+        assert!(core.module.world.get_entity(entity).is_none());
+        let api = core.module.world.resource::<UnrealApi>();
+        assert!(api.actor_to_entity.is_empty());
+        assert!(api.entity_to_actor.is_empty());
+    }
+
+    #[test]
+    fn net_ids_map_both_ways() {
+        let mut core = start();
+        let first = spawn(&mut core);
+        let second = spawn(&mut core);
+        let local = spawn(&mut core);
+        testing::with(|unreal| {
+            unreal.actor_mut(first.0).net_guid = Some(11);
+            unreal.actor_mut(second.0).net_guid = Some(42);
+        });
+        core.tick(1.0 / 60.0);
+
+        let world = &core.module.world;
+        let registry = world.resource::<NetIdRegistry>();
+        for (actor, guid) in [(first, 11), (second, 42)] {
+            let entity = entity_of(&core, actor);
+            assert_eq!(registry.entity(NetId(guid)), Some(entity));
+            assert_eq!(registry.net_id(entity), Some(NetId(guid)));
+            assert_eq!(world.get::<NetId>(entity), Some(&NetId(guid)));
+        }
+        let local = entity_of(&core, local);
+        assert_eq!(registry.net_id(local), None);
+        assert!(world.get::<NetId>(local).is_none());
+        assert_eq!(registry.entity(NetId(7)), None);
+    }
+
+    #[test]
+    fn pause_stops_gated_systems_and_timers() {
+        let mut core = start_with(|module| {
+            module
+                .insert_resource(Counter::default())
+                .add_system_set_to_stage(
+                    CoreStage::Update,
+                    SystemSet::new()
+                        .with_run_criteria(run_if_running)
+                        .with_system(count),
+                );
+        });
+        core.module
+            .world
+            .resource_mut::<Timers>()
+            .insert("respawn", crate::timer::Timer::from_seconds(1.0, false));
+        core.tick(0.25);
+
+        core.module
+            .world
+            .resource_mut::<GameState>()
+            .set(PlayState::Paused);
+        core.tick(0.25);
+        core.tick(0.25);
+        let world = &core.module.world;
+        assert_eq!(world.resource::<Counter>().0, 1);
+        assert_eq!(
+            world.resource::<Timers>().get("respawn").unwrap().elapsed(),
+            0.25
+        );
+
+        core.module
+            .world
+            .resource_mut::<GameState>()
+            .set(PlayState::Running);
+        core.tick(0.25);
+        let world = &core.module.world;
+        assert_eq!(world.resource::<Counter>().0, 2);
+        assert_eq!(
+            world.resource::<Timers>().get("respawn").unwrap().elapsed(),
+            0.5
+        );
+    }
+
+    #[derive(Default)]
+    struct Hooks {
+        began: bool,
+        actors_at_end: Option<usize>,
+    }
+
+    struct LifecycleModule;
+
+    impl UserModule for LifecycleModule {
+        fn initialize(&self, module: &mut Module) {
+            module.insert_resource(Hooks::default());
+        }
+
+        fn on_begin_play(&self, world: &mut World) {
+            world.resource_mut::<Hooks>().began = true;
+        }
+
+        fn on_end_play(&self, world: &mut World) {
+            let actors = world.query::<&ActorComponent>().iter(world).count();
+            world.resource_mut::<Hooks>().actors_at_end = Some(actors);
+        }
+    }
+
+    #[test]
+    fn lifecycle_hooks_fire() {
+        testing::install();
+        let mut core = UnrealCore::new(&LifecycleModule);
+        core.begin_play(&LifecycleModule);
+        let hooks = core.module.world.resource::<Hooks>();
+        assert!(hooks.began);
+        assert_eq!(hooks.actors_at_end, None);
+
+        spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        core.end_play(&LifecycleModule);
+
+        // The hook still sees the entities, which are despawned right after
+        let hooks = core.module.world.resource::<Hooks>();
+        assert_eq!(hooks.actors_at_end, Some(1));
+        assert_eq!(actor_count(&mut core), 0);
+        assert!(core
+            .module
+            .world
+            .resource::<UnrealApi>()
+            .actor_to_entity
+            .is_empty());
+    }
+
+    #[test]
+    fn unregister_actor_result_codes() {
+        let mut core = start();
+        let actor = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+
+        assert_eq!(
+            core.unregister_actor(std::ptr::null_mut()),
+            ffi::ResultCode::InvalidArgument
+        );
+        let unknown = testing::spawn_actor();
+        assert_eq!(core.unregister_actor(unknown), ffi::ResultCode::Success);
+        assert_eq!(core.unregister_actor(actor.0), ffi::ResultCode::Success);
+        core.tick(1.0 / 60.0);
+
+        assert_eq!(actor_count(&mut core), 0);
+        assert!(core
+            .module
+            .world
+            .resource::<UnrealApi>()
+            .actor_to_entity
+            .is_empty());
+    }
+
+    #[test]
+    fn remove_component_by_uuid() {
+        let mut core = start();
+        let (entity, _) = spawn_physics(&mut core);
+        let actor = core
+            .module
+            .world
+            .get::<ActorComponent>(entity)
+            .unwrap()
+            .actor;
+
+        let uuid = PhysicsComponent::TYPE_UUID;
+        assert_eq!(
+            core.remove_component(actor.0, uuid),
+            ffi::ResultCode::Success
+        );
+        assert!(core.module.world.get::<PhysicsComponent>(entity).is_none());
+        // Removing it again is a no-op
+        assert_eq!(
+            core.remove_component(actor.0, uuid),
+            ffi::ResultCode::Success
+        );
+
+        let unknown = testing::spawn_actor();
+        assert_eq!(
+            core.remove_component(unknown, uuid),
+            ffi::ResultCode::ActorNotFound
+        );
+        assert_eq!(
+            core.remove_component(actor.0, Uuid::nil()),
+            ffi::ResultCode::NotFound
+        );
+    }
+
+    fn step_forward(mut query: Query<&mut TransformComponent, With<PreviousTransform>>) {
+        for mut transform in query.iter_mut() {
+            transform.position += Vec3::X;
+            transform.rotation *= Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+        }
+    }
+
+    #[test]
+    fn uploaded_transform_is_interpolated() {
+        let mut core = start_with(|module| {
+            module.set_fixed_timestep(0.1, 5).add_system_set_to_stage(
+                CoreStage::FixedUpdate,
+                SystemSet::new().with_system(step_forward),
+            );
+        });
+        let actor = spawn(&mut core);
+        core.tick(0.01);
+        let entity = entity_of(&core, actor);
+        core.module
+            .world
+            .entity_mut(entity)
+            .insert_bundle((TransformAuthorityRust, PreviousTransform::default()));
+
+        testing::take_calls();
+        // One fixed step and half of the next one
+        core.tick(0.14);
+
+        assert!((core.module.world.resource::<FixedFrame>().alpha - 0.5).abs() < 1e-4);
+        let transform = core.module.world.get::<TransformComponent>(entity).unwrap();
+        assert_vec_eq!(transform.position, Vec3::X, 1e-6);
+        let uploaded = testing::take_calls()
+            .into_iter()
+            .find_map(|call| match call {
+                testing::Call::SetSpatialData {
+                    actor: uploaded,
+                    position,
+                    rotation,
+                    ..
+                } if uploaded == actor.0 => Some((position, rotation)),
+                _ => None,
+            });
+        let (position, rotation) = uploaded.unwrap();
+        assert_vec_eq!(position, Vec3::X * 0.5, 1e-4);
+        let half = Quat::from_rotation_z(std::f32::consts::FRAC_PI_4);
+        assert_vec_eq!(rotation, half, 1e-3);
+    }
+
+    #[test]
+    fn bounds_are_downloaded_for_opted_in_entities() {
+        let mut core = start();
+        let with_bounds = spawn(&mut core);
+        let without_bounds = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        let entity = entity_of(&core, with_bounds);
+        core.module
+            .world
+            .entity_mut(entity)
+            .insert(BoundsComponent::default());
+        let bounds = (Vec3::new(1.0, 2.0, 3.0), Vec3::new(50.0, 50.0, 100.0));
+        testing::with(|unreal| {
+            unreal.actor_mut(with_bounds.0).bounds = bounds;
+            unreal.actor_mut(without_bounds.0).bounds = bounds;
+        });
+        core.tick(1.0 / 60.0);
+
+        let component = core.module.world.get::<BoundsComponent>(entity).unwrap();
+        assert_eq!((component.origin, component.extent), bounds);
+        let other = entity_of(&core, without_bounds);
+        assert!(core.module.world.get::<BoundsComponent>(other).is_none());
+    }
+
+    /// `(actor, uuid)` of every change notification, whether it was sent one by one or in bulk
+    fn component_changes(calls: &[testing::Call]) -> Vec<(*mut c_void, Uuid)> {
+        calls
+            .iter()
+            .flat_map(|call| match call {
+                testing::Call::OnComponentChanged { actor, uuid } => vec![(*actor, *uuid)],
+                testing::Call::OnComponentsChangedBulk { changes } => changes.clone(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn observed_component_changes_notify_the_actor_once() {
+        let mut core = start_with(|module| {
+            module.observe_component::<Door>();
+        });
+        let door = spawn(&mut core);
+        let other = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        let entity = entity_of(&core, door);
+        core.module.world.entity_mut(entity).insert(Door);
+        core.module
+            .world
+            .entity_mut(entity_of(&core, other))
+            .insert(Pickup);
+        testing::take_calls();
+
+        core.tick(1.0 / 60.0);
+        assert_eq!(
+            component_changes(&testing::take_calls()),
+            vec![(door.0, Door::TYPE_UUID)]
+        );
+
+        core.tick(1.0 / 60.0);
+        assert!(component_changes(&testing::take_calls()).is_empty());
+
+        core.module
+            .world
+            .get_mut::<Door>(entity)
+            .unwrap()
+            .set_changed();
+        core.tick(1.0 / 60.0);
+        assert_eq!(component_changes(&testing::take_calls()).len(), 1);
+    }
+
+    #[test]
+    fn socket_attachments_follow_the_socket() {
+        let mut core = start();
+        let character = spawn(&mut core);
+        let hand = (
+            Vec3::new(10.0, 0.0, 100.0),
+            Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+        );
+        testing::with(|unreal| {
+            let actor = unreal.actor_mut(character.0);
+            actor.position = Vec3::new(10.0, 0.0, 0.0);
+            actor.sockets.insert("hand".into(), hand);
+        });
+        core.tick(1.0 / 60.0);
+        let parent = entity_of(&core, character);
+        let mut attach = SocketAttachComponent::new(parent, "hand");
+        attach.offset = local(Vec3::X * 5.0, Quat::IDENTITY);
+        let effect = core
+            .module
+            .world
+            .spawn()
+            .insert_bundle((TransformComponent::default(), attach))
+            .id();
+
+        core.tick(1.0 / 60.0);
+        let transform = core.module.world.get::<TransformComponent>(effect).unwrap();
+        assert_vec_eq!(transform.position, Vec3::new(10.0, 5.0, 100.0));
+        assert_vec_eq!(transform.rotation, hand.1, 1e-6);
+
+        testing::with(|unreal| {
+            let actor = unreal.actor_mut(character.0);
+            actor
+                .sockets
+                .insert("hand".into(), (Vec3::Z * 50.0, Quat::IDENTITY));
+        });
+        core.tick(1.0 / 60.0);
+        let transform = core.module.world.get::<TransformComponent>(effect).unwrap();
+        assert_vec_eq!(transform.position, Vec3::new(5.0, 0.0, 50.0));
+
+        // Without the socket the transform of the parent is used
+        testing::with(|unreal| unreal.actor_mut(character.0).sockets.clear());
+        core.tick(1.0 / 60.0);
+        let transform = core.module.world.get::<TransformComponent>(effect).unwrap();
+        assert_vec_eq!(transform.position, Vec3::new(15.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn visibility_is_uploaded_only_on_change() {
+        let mut core = start();
+        let actor = spawn(&mut core);
+        testing::with(|unreal| unreal.actor_mut(actor.0).hidden = true);
+        core.tick(1.0 / 60.0);
+        let entity = entity_of(&core, actor);
+        assert!(
+            core.module
+                .world
+                .get::<VisibilityComponent>(entity)
+                .unwrap()
+                .hidden
+        );
+        let hidden_calls = || {
+            testing::take_calls()
+                .into_iter()
+                .filter(|call| matches!(call, testing::Call::SetActorHidden { .. }))
+                .collect::<Vec<_>>()
+        };
+        hidden_calls();
+
+        core.tick(1.0 / 60.0);
+        assert!(hidden_calls().is_empty());
+
+        core.module
+            .world
+            .get_mut::<VisibilityComponent>(entity)
+            .unwrap()
+            .hidden = false;
+        core.tick(1.0 / 60.0);
+        assert_eq!(
+            hidden_calls(),
+            vec![testing::Call::SetActorHidden {
+                actor: actor.0,
+                hidden: false,
+            }]
+        );
+        assert!(!testing::with(|unreal| unreal.actor_mut(actor.0).hidden));
+
+        core.tick(1.0 / 60.0);
+        assert!(hidden_calls().is_empty());
+    }
+
+    #[test]
+    fn concrete_and_boxed_modules_start_the_same() {
+        testing::install();
+        let mut concrete = UnrealCore::new_with(&LifecycleModule);
+        concrete.begin_play(&LifecycleModule);
+
+        let boxed: Box<dyn UserModule> = Box::new(LifecycleModule);
+        let mut dynamic = UnrealCore::new(boxed.as_ref());
+        dynamic.begin_play(boxed.as_ref());
+
+        for core in [&mut concrete, &mut dynamic] {
+            assert!(core.module.world.resource::<Hooks>().began);
+            core.end_play(&LifecycleModule);
+            assert_eq!(core.module.world.resource::<Hooks>().actors_at_end, Some(0));
+        }
+        assert_eq!(
+            system_names(&concrete.module.schedule),
+            system_names(&dynamic.module.schedule)
+        );
+    }
+
+    #[derive(Default)]
+    struct SeenPickups {
+        update: Vec<usize>,
+        late_update: Vec<usize>,
+    }
+
+    #[test]
+    fn late_update_sees_entities_spawned_in_update() {
+        fn spawn_pickup(mut commands: Commands) {
+            commands.spawn().insert(Pickup);
+        }
+        fn seen_in_update(mut seen: ResMut<SeenPickups>, query: Query<&Pickup>) {
+            seen.update.push(query.iter().count());
+        }
+        fn seen_in_late_update(mut seen: ResMut<SeenPickups>, query: Query<&Pickup>) {
+            seen.late_update.push(query.iter().count());
+        }
+        let mut core = start_with(|module| {
+            module
+                .insert_resource(SeenPickups::default())
+                .add_system_set_to_stage(
+                    CoreStage::Update,
+                    SystemSet::new()
+                        .with_system(spawn_pickup)
+                        .with_system(seen_in_update.after(spawn_pickup)),
+                )
+                .add_system_set_to_stage(
+                    CoreStage::LateUpdate,
+                    SystemSet::new().with_system(seen_in_late_update),
+                );
+        });
+        core.tick(1.0 / 60.0);
+        core.tick(1.0 / 60.0);
+
+        let seen = core.module.world.resource::<SeenPickups>();
+        assert_eq!(seen.update, vec![0, 1]);
+        assert_eq!(seen.late_update, vec![1, 2]);
+    }
+
+    #[test]
+    fn transform_download_reuses_scratch_buffers() {
+        let mut core = start();
+        for _ in 0..5 {
+            spawn(&mut core);
+        }
+        core.tick(1.0 / 60.0);
+        core.tick(1.0 / 60.0);
+
+        let mut scratch = core.module.world.resource_mut::<ScratchBuffers>();
+        let actors = scratch.actors.take();
+        assert!(actors.is_empty());
+        assert!(actors.capacity() >= 5);
+        assert!(scratch.spatial_data.take().capacity() >= 5);
+    }
+
+    #[test]
+    fn invalid_transforms_are_reported_and_reset() {
+        let mut core = start_with(|module| {
+            module
+                .add_plugin(ValidateTransformsPlugin)
+                .insert_resource(TransformPolicy::Skip);
+        });
+        let guarded_actor = spawn(&mut core);
+        let reported_actor = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        let guarded = entity_of(&core, guarded_actor);
+        let reported = entity_of(&core, reported_actor);
+        let valid = TransformComponent {
+            position: Vec3::new(1.0, 2.0, 3.0),
+            ..Default::default()
+        };
+        for entity in [guarded, reported] {
+            core.module
+                .world
+                .entity_mut(entity)
+                .insert_bundle((TransformAuthorityRust, valid.clone()));
+        }
+        core.module
+            .world
+            .entity_mut(guarded)
+            .insert(LastValidTransform::default());
+        core.tick(1.0 / 60.0);
+
+        for entity in [guarded, reported] {
+            core.module
+                .world
+                .get_mut::<TransformComponent>(entity)
+                .unwrap()
+                .position
+                .x = f32::NAN;
+        }
+        testing::take_calls();
+        core.tick(1.0 / 60.0);
+
+        let transform = core
+            .module
+            .world
+            .get::<TransformComponent>(guarded)
+            .unwrap();
+        assert_eq!(transform.position, valid.position);
+        let transform = core
+            .module
+            .world
+            .get::<TransformComponent>(reported)
+            .unwrap();
+        // Only reported, and then skipped by the upload
+        assert_eq!(transform.invalid_field(), Some("position"));
+        let uploads = testing::take_calls();
+        assert_eq!(
+            uploaded_positions(&uploads, guarded_actor),
+            vec![valid.position]
+        );
+        assert!(uploaded_positions(&uploads, reported_actor).is_empty());
+    }
+
+    #[test]
+    fn unpausing_does_not_replay_fixed_steps() {
+        let mut core = start_with(|module| {
+            module
+                .insert_resource(Counter::default())
+                .set_fixed_timestep(0.1, 5)
+                .add_system_set_to_stage(
+                    CoreStage::FixedUpdate,
+                    SystemSet::new().with_system(count),
                 );
+        });
+        core.tick(0.15);
+        assert_eq!(core.module.world.resource::<Counter>().0, 1);
+
+        core.module
+            .world
+            .resource_mut::<GameState>()
+            .set(PlayState::Paused);
+        for _ in 0..20 {
+            core.tick(0.1);
+        }
+        assert_eq!(core.module.world.resource::<Counter>().0, 1);
+        assert_eq!(core.module.world.resource::<FixedFrame>().steps, 0);
+
+        core.module
+            .world
+            .resource_mut::<GameState>()
+            .set(PlayState::Running);
+        // Only the 0.05 left over from before the pause is added
+        core.tick(0.04);
+        assert_eq!(core.module.world.resource::<FixedFrame>().steps, 0);
+        core.tick(0.02);
+        assert_eq!(core.module.world.resource::<FixedFrame>().steps, 1);
+        assert_eq!(core.module.world.resource::<Counter>().0, 2);
+    }
+
+    #[test]
+    fn viewport_follows_unreal() {
+        let mut core = start();
+        testing::with(|unreal| unreal.viewport = (1920, 1080, 1.5));
+        core.tick(1.0 / 60.0);
+        let viewport = core.module.world.resource::<Viewport>();
+        assert_eq!((viewport.width, viewport.height), (1920, 1080));
+        assert_eq!(viewport.dpi_scale, 1.5);
+        assert_eq!(viewport.size(), Vec2::new(1920.0, 1080.0));
+        assert_eq!(viewport.aspect_ratio(), Some(1920.0 / 1080.0));
+        assert!(!viewport.is_minimized());
+
+        testing::with(|unreal| unreal.viewport = (0, 0, 1.0));
+        core.tick(1.0 / 60.0);
+        let viewport = core.module.world.resource::<Viewport>();
+        assert!(viewport.is_minimized());
+        assert_eq!(viewport.aspect_ratio(), None);
+    }
+
+    #[test]
+    fn disabled_physics_is_not_synced() {
+        let mut core = start();
+        let (entity, primitive) = spawn_physics(&mut core);
+        physics(&mut core, entity).set_enabled(false);
+        physics(&mut core, entity).set_velocity(Vec3::Y);
+        testing::with(|unreal| unreal.primitive_mut(primitive).velocity = Vec3::X);
+        testing::take_calls();
+        core.tick(1.0 / 60.0);
+
+        assert!(!testing::take_calls()
+            .iter()
+            .any(|call| matches!(call, testing::Call::SetVelocity { .. })));
+        // Neither downloaded
+        assert_eq!(physics(&mut core, entity).velocity(), Vec3::Y);
+        assert!(!physics(&mut core, entity).is_active());
+
+        // Enabling drops the change and picks up the state of unreal
+        physics(&mut core, entity).set_enabled(true);
+        core.tick(1.0 / 60.0);
+        assert!(!testing::take_calls()
+            .iter()
+            .any(|call| matches!(call, testing::Call::SetVelocity { .. })));
+        assert_eq!(physics(&mut core, entity).velocity(), Vec3::X);
+    }
+
+    #[test]
+    fn systems_can_run_before_the_upload() {
+        fn lift(mut query: Query<&mut TransformComponent, With<TransformAuthorityRust>>) {
+            for mut transform in query.iter_mut() {
+                transform.position.z += 100.0;
             }
         }
+        let mut core = start_with(|module| {
+            module.add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                SystemSet::new().with_system(lift.before(CoreLabel::UploadTransform)),
+            );
+        });
+        let actor = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        let entity = entity_of(&core, actor);
+        core.module
+            .world
+            .entity_mut(entity)
+            .insert(TransformAuthorityRust);
+        testing::take_calls();
+
+        core.tick(1.0 / 60.0);
+        // The change of this frame is already part of this frame's upload
+        assert_eq!(
+            uploaded_positions(&testing::take_calls(), actor),
+            vec![Vec3::Z * 100.0]
+        );
+    }
+
+    #[test]
+    fn component_changes_are_sent_in_one_bulk_call() {
+        let mut core = start_with(|module| {
+            module
+                .observe_component::<Door>()
+                .observe_component::<Pickup>();
+        });
+        let actors: Vec<_> = (0..3).map(|_| spawn(&mut core)).collect();
+        core.tick(1.0 / 60.0);
+        for &actor in &actors {
+            let entity = entity_of(&core, actor);
+            core.module
+                .world
+                .entity_mut(entity)
+                .insert_bundle((Door, Pickup));
+        }
+        testing::take_calls();
+        core.tick(1.0 / 60.0);
+
+        let calls: Vec<_> = testing::take_calls()
+            .into_iter()
+            .filter(|call| {
+                matches!(
+                    call,
+                    testing::Call::OnComponentChanged { .. }
+                        | testing::Call::OnComponentsChangedBulk { .. }
+                )
+            })
+            .collect();
+        assert_eq!(calls.len(), 1);
+        assert!(matches!(
+            calls[0],
+            testing::Call::OnComponentsChangedBulk { .. }
+        ));
+        let mut changes = component_changes(&calls);
+        changes.sort();
+        let mut expected: Vec<_> = actors
+            .iter()
+            .flat_map(|actor| {
+                let actor = actor.0;
+                [(actor, Door::TYPE_UUID), (actor, Pickup::TYPE_UUID)]
+            })
+            .collect();
+        expected.sort();
+        assert_eq!(changes, expected);
+
+        // Nothing changed, nothing is sent
+        core.tick(1.0 / 60.0);
+        assert!(component_changes(&testing::take_calls()).is_empty());
+    }
+
+    #[test]
+    fn frame_carries_the_time_dilation() {
+        let mut core = start();
+        testing::with(|unreal| unreal.time_dilation = 0.5);
+        // Unreal passes the dilated delta time
+        core.tick(0.01);
+
+        let frame = core.module.world.resource::<Frame>();
+        assert_eq!(frame.dilation, 0.5);
+        assert_eq!(frame.dt, 0.01);
+        assert_eq!(frame.dt, frame.real_dt * 0.5);
+
+        // A broken dilation falls back to the dilated time
+        testing::with(|unreal| unreal.time_dilation = 0.0);
+        core.tick(0.01);
+        let frame = core.module.world.resource::<Frame>();
+        assert_eq!(frame.real_dt, 0.01);
+    }
+
+    #[test]
+    fn changed_material_scalars_are_sent_once() {
+        let mut core = start();
+        let actor = spawn(&mut core);
+        core.tick(1.0 / 60.0);
+        let entity = entity_of(&core, actor);
+        core.module
+            .world
+            .entity_mut(entity)
+            .insert(MaterialParamComponent::default());
+        let material_calls = || {
+            testing::take_calls()
+                .into_iter()
+                .filter(|call| matches!(call, testing::Call::SetMaterialScalar { .. }))
+                .collect::<Vec<_>>()
+        };
+        material_calls();
+
+        let set_glow = |core: &mut UnrealCore, value| {
+            core.module
+                .world
+                .get_mut::<MaterialParamComponent>(entity)
+                .unwrap()
+                .set_scalar("Glow", value);
+        };
+        set_glow(&mut core, 0.75);
+        core.tick(1.0 / 60.0);
+        assert_eq!(
+            material_calls(),
+            vec![testing::Call::SetMaterialScalar {
+                actor: actor.0,
+                name: "Glow".into(),
+                value: 0.75,
+            }]
+        );
+
+        set_glow(&mut core, 0.75);
+        core.tick(1.0 / 60.0);
+        assert!(material_calls().is_empty());
+    }
+
+    #[derive(Default)]
+    struct Gravity(f32);
+
+    fn read_gravity(config: Res<GameConfig>, mut gravity: ResMut<Gravity>) {
+        gravity.0 = config.get_or("gravity", -981.0);
+    }
+
+    #[test]
+    fn systems_read_the_config_of_unreal() {
+        testing::install();
+        testing::with(|unreal| unreal.config = "gravity = -500.0".into());
+        let module = TestModule(|module| {
+            module
+                .insert_resource(Gravity::default())
+                .add_system_set_to_stage(
+                    CoreStage::Update,
+                    SystemSet::new().with_system(read_gravity),
+                );
+        });
+        let mut core = UnrealCore::new(&module);
+        core.begin_play(&module);
+        core.tick(1.0 / 60.0);
+        assert_eq!(core.module.world.resource::<Gravity>().0, -500.0);
     }
 }
@@ -0,0 +1,160 @@
+use bevy_ecs::system::ResMut;
+use glam::{Quat, Vec3};
+use unreal_ffi::Color;
+
+use crate::module::bindings;
+
+#[derive(Debug, Copy, Clone)]
+pub enum DebugDrawCommand {
+    Line {
+        start: Vec3,
+        end: Vec3,
+        color: Color,
+        thickness: f32,
+        duration: f32,
+    },
+    Sphere {
+        center: Vec3,
+        radius: f32,
+        color: Color,
+    },
+    Box {
+        center: Vec3,
+        extent: Vec3,
+        rotation: Quat,
+        color: Color,
+    },
+}
+
+/// Buffers debug shapes that are drawn by unreal at the end of the frame. A `duration` <= 0
+/// draws the shape for a single frame.
+#[derive(Default)]
+pub struct DebugDraw {
+    commands: Vec<DebugDrawCommand>,
+}
+
+impl DebugDraw {
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Color, thickness: f32, duration: f32) {
+        self.commands.push(DebugDrawCommand::Line {
+            start,
+            end,
+            color,
+            thickness,
+            duration,
+        });
+    }
+
+    pub fn sphere(&mut self, center: Vec3, radius: f32, color: Color) {
+        self.commands.push(DebugDrawCommand::Sphere {
+            center,
+            radius,
+            color,
+        });
+    }
+
+    /// `extent` is the half size of the box
+    pub fn cuboid(&mut self, center: Vec3, extent: Vec3, rotation: Quat, color: Color) {
+        self.commands.push(DebugDrawCommand::Box {
+            center,
+            extent,
+            rotation,
+            color,
+        });
+    }
+
+    /// The commands that are going to be sent to unreal this frame, in the order they were added
+    pub fn commands(&self) -> &[DebugDrawCommand] {
+        &self.commands
+    }
+}
+
+pub(crate) fn flush_debug_draw(mut debug_draw: ResMut<DebugDraw>) {
+    for command in debug_draw.commands.drain(..) {
+        unsafe {
+            match command {
+                DebugDrawCommand::Line {
+                    start,
+                    end,
+                    color,
+                    thickness,
+                    duration,
+                } => (bindings().debug_draw_line)(
+                    start.into(),
+                    end.into(),
+                    color,
+                    thickness,
+                    duration,
+                ),
+                DebugDrawCommand::Sphere {
+                    center,
+                    radius,
+                    color,
+                } => (bindings().debug_draw_sphere)(center.into(), radius, color, 0.0),
+                DebugDrawCommand::Box {
+                    center,
+                    extent,
+                    rotation,
+                    color,
+                } => (bindings().debug_draw_box)(
+                    center.into(),
+                    extent.into(),
+                    rotation.into(),
+                    color,
+                    0.0,
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Call};
+    use bevy_ecs::{prelude::*, schedule::SystemStage};
+
+    #[test]
+    fn commands_are_forwarded_in_order() {
+        testing::install();
+        let mut world = World::new();
+        let mut debug_draw = DebugDraw::default();
+        debug_draw.sphere(Vec3::X, 2.0, Color::RED);
+        debug_draw.line(Vec3::ZERO, Vec3::Z, Color::GREEN, 1.5, 3.0);
+        debug_draw.cuboid(Vec3::Y, Vec3::ONE, Quat::IDENTITY, Color::BLUE);
+        world.insert_resource(debug_draw);
+
+        let mut stage = SystemStage::single_threaded().with_system(flush_debug_draw);
+        stage.run(&mut world);
+
+        assert_eq!(
+            testing::take_calls(),
+            vec![
+                Call::DebugDrawSphere {
+                    center: Vec3::X,
+                    radius: 2.0,
+                    color: Color::RED,
+                    duration: 0.0,
+                },
+                Call::DebugDrawLine {
+                    start: Vec3::ZERO,
+                    end: Vec3::Z,
+                    color: Color::GREEN,
+                    thickness: 1.5,
+                    duration: 3.0,
+                },
+                Call::DebugDrawBox {
+                    center: Vec3::Y,
+                    extent: Vec3::ONE,
+                    rotation: Quat::IDENTITY,
+                    color: Color::BLUE,
+                    duration: 0.0,
+                },
+            ]
+        );
+        assert!(world.resource::<DebugDraw>().commands().is_empty());
+
+        // Nothing is drawn twice
+        stage.run(&mut world);
+        assert!(testing::calls().is_empty());
+    }
+}
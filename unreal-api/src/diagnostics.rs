@@ -0,0 +1,139 @@
+use std::{
+    borrow::Cow,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use bevy_ecs::{
+    archetype::ArchetypeComponentId,
+    component::ComponentId,
+    query::Access,
+    schedule::SystemLabelId,
+    system::{IntoSystem, System},
+    world::World,
+};
+
+/// Systems that were disabled by `catch_panics` after they panicked
+#[derive(Default, Debug)]
+pub struct DisabledSystems {
+    pub systems: Vec<Cow<'static, str>>,
+}
+
+/// Wraps `system` so that a panic inside of it only disables this system instead of aborting
+/// the whole frame. The system is never run again and is listed in `DisabledSystems`.
+pub fn catch_panics<S, Params>(system: S) -> CatchPanics<S::System>
+where
+    S: IntoSystem<(), (), Params>,
+{
+    CatchPanics {
+        system: IntoSystem::into_system(system),
+        panicked: false,
+        reported: false,
+    }
+}
+
+pub struct CatchPanics<S> {
+    system: S,
+    panicked: bool,
+    reported: bool,
+}
+
+impl<S> System for CatchPanics<S>
+where
+    S: System<In = (), Out = ()>,
+{
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        self.system.name()
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        self.system.component_access()
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        self.system.archetype_component_access()
+    }
+
+    fn is_send(&self) -> bool {
+        self.system.is_send()
+    }
+
+    unsafe fn run_unsafe(&mut self, input: Self::In, world: &World) -> Self::Out {
+        if self.panicked {
+            return;
+        }
+        let system = &mut self.system;
+        if catch_unwind(AssertUnwindSafe(|| system.run_unsafe(input, world))).is_err() {
+            log::error!("System {} panicked and is now disabled", self.system.name());
+            self.panicked = true;
+        }
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        if !self.panicked {
+            self.system.apply_buffers(world);
+            return;
+        }
+        // Commands of a system that panicked halfway through are never applied
+        if !self.reported {
+            self.reported = true;
+            world
+                .get_resource_or_insert_with(DisabledSystems::default)
+                .systems
+                .push(self.system.name());
+        }
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.system.initialize(world);
+    }
+
+    fn update_archetype_component_access(&mut self, world: &World) {
+        self.system.update_archetype_component_access(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        self.system.check_change_tick(change_tick);
+    }
+
+    fn default_labels(&self) -> Vec<SystemLabelId> {
+        self.system.default_labels()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::prelude::*;
+
+    #[derive(Default)]
+    struct Counter(u32);
+
+    fn healthy(mut counter: ResMut<Counter>) {
+        counter.0 += 1;
+    }
+
+    fn panicking(mut counter: ResMut<Counter>) {
+        counter.0 += 100;
+        panic!("bug in a gameplay system");
+    }
+
+    #[test]
+    fn panicking_system_is_disabled() {
+        let mut world = World::new();
+        world.insert_resource(Counter::default());
+        let mut stage = SystemStage::single_threaded()
+            .with_system(catch_panics(panicking))
+            .with_system(catch_panics(healthy).after(panicking));
+
+        stage.run(&mut world);
+        stage.run(&mut world);
+
+        assert_eq!(world.resource::<Counter>().0, 102);
+        let disabled = &world.resource::<DisabledSystems>().systems;
+        assert_eq!(disabled.len(), 1);
+        assert!(disabled[0].ends_with("panicking"));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::ffi::RustAlloc;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FfiStringError {
+    /// Unreal returned bytes that are not valid UTF-8
+    InvalidUtf8(std::str::Utf8Error),
+}
+
+impl std::fmt::Display for FfiStringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FfiStringError::InvalidUtf8(err) => {
+                write!(f, "Unreal returned a string that is not UTF-8: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FfiStringError {}
+
+/// Receives a string that unreal allocates through `AllocateFns`. The allocation is freed when the
+/// buffer is dropped, also if the string turned out to be invalid.
+pub struct FfiStringBuf {
+    alloc: RustAlloc,
+}
+
+impl Default for FfiStringBuf {
+    fn default() -> Self {
+        Self {
+            alloc: RustAlloc::empty(),
+        }
+    }
+}
+
+impl Drop for FfiStringBuf {
+    fn drop(&mut self) {
+        let alloc = std::mem::replace(&mut self.alloc, RustAlloc::empty());
+        // Only ever filled by unreal through our own allocator
+        unsafe { alloc.free() };
+    }
+}
+
+impl FfiStringBuf {
+    /// For bindings that write one string, like `GetActorNameFn`. Nothing written counts as an
+    /// empty string.
+    pub fn read(fill: impl FnOnce(*mut RustAlloc)) -> Result<String, FfiStringError> {
+        let mut buf = Self::default();
+        fill(&mut buf.alloc);
+        buf.decode()
+    }
+
+    /// For bindings with the two call protocol of `RetrieveUuids`, like `GetActorTagsFn`. `fill`
+    /// is called with a null pointer to query the number of strings, and then again with a buffer
+    /// for that many strings. Unreal can write fewer strings in the second call.
+    pub fn read_list(
+        mut fill: impl FnMut(*mut RustAlloc, *mut usize),
+    ) -> Result<Vec<String>, FfiStringError> {
+        let mut len = 0;
+        fill(std::ptr::null_mut(), &mut len);
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        let mut allocs: Vec<RustAlloc> = (0..len).map(|_| RustAlloc::empty()).collect();
+        fill(allocs.as_mut_ptr(), &mut len);
+        // Wrapped right away, so every allocation is freed even if one of the strings is invalid
+        let bufs: Vec<FfiStringBuf> = allocs
+            .into_iter()
+            .map(|alloc| FfiStringBuf { alloc })
+            .collect();
+        bufs.iter().take(len).map(FfiStringBuf::decode).collect()
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        if self.alloc.ptr.is_null() || self.alloc.size == 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(self.alloc.ptr, self.alloc.size) }
+    }
+
+    fn decode(&self) -> Result<String, FfiStringError> {
+        std::str::from_utf8(self.as_bytes())
+            .map(str::to_string)
+            .map_err(FfiStringError::InvalidUtf8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::{alloc, Layout};
+
+    /// Allocates like unreal does through `AllocateFns`
+    unsafe fn write_bytes(bytes: &[u8], out: *mut RustAlloc) {
+        let ptr = alloc(Layout::from_size_align(bytes.len(), 1).unwrap());
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+        *out = RustAlloc {
+            ptr,
+            size: bytes.len(),
+            align: 1,
+        };
+    }
+
+    #[test]
+    fn nothing_written_is_empty() {
+        assert_eq!(FfiStringBuf::read(|_| {}).unwrap(), "");
+    }
+
+    #[test]
+    fn list_uses_the_two_call_protocol() {
+        let strings = ["Enemy", "Flying", "Boss"];
+        let mut calls = Vec::new();
+        let result = FfiStringBuf::read_list(|data, len| unsafe {
+            calls.push(data.is_null());
+            if data.is_null() {
+                *len = strings.len();
+                return;
+            }
+            // Fewer strings than announced, e.g. a tag was removed in between
+            for (idx, s) in strings.iter().take(2).enumerate() {
+                write_bytes(s.as_bytes(), data.add(idx));
+            }
+            *len = 2;
+        });
+        assert_eq!(result.unwrap(), ["Enemy", "Flying"]);
+        assert_eq!(calls, [true, false]);
+
+        let mut calls = 0;
+        let result = FfiStringBuf::read_list(|_, len| {
+            calls += 1;
+            unsafe { *len = 0 };
+        });
+        assert!(result.unwrap().is_empty());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn invalid_utf8_is_rejected() {
+        let result = FfiStringBuf::read(|data| unsafe { write_bytes(&[b'a', 0xff, 0xfe], data) });
+        assert!(matches!(result, Err(FfiStringError::InvalidUtf8(_))));
+
+        let result = FfiStringBuf::read_list(|data, len| unsafe {
+            if data.is_null() {
+                *len = 2;
+                return;
+            }
+            write_bytes(b"valid", data);
+            write_bytes(&[0xc3], data.add(1));
+        });
+        assert!(matches!(result, Err(FfiStringError::InvalidUtf8(_))));
+    }
+}
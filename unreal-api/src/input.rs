@@ -1,59 +1,116 @@
-use unreal_ffi::ActionState;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use unreal_ffi::{ActionState, GamepadState, MouseState};
+pub use unreal_ffi::{GamepadAxis, GamepadButton};
 
 use crate::module::bindings;
-use std::{collections::HashMap, os::raw::c_char};
+use std::{
+    collections::{HashMap, HashSet},
+    os::raw::c_char,
+};
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Action {
     Pressed,
     Released,
 }
 
 pub type Binding = &'static str;
-#[derive(Default)]
-pub struct Input {
-    axis: HashMap<Binding, f32>,
-    action: HashMap<Binding, Action>,
 
-    action_bindings: Vec<Binding>,
-    axis_bindings: Vec<Binding>,
+/// Index of a local player's gamepad. Only the first `MAX_GAMEPADS` are tracked.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct GamepadId(pub u32);
+
+pub const MAX_GAMEPADS: usize = 2;
+
+struct Gamepads {
+    states: [GamepadState; MAX_GAMEPADS],
+    deadzone: f32,
 }
-impl Input {
-    pub fn register_action_binding(&mut self, binding: Binding) {
-        self.action_bindings.push(binding);
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self {
+            states: Default::default(),
+            deadzone: 0.15,
+        }
     }
-    pub fn register_axis_binding(&mut self, binding: Binding) {
-        self.axis_bindings.push(binding);
+}
+
+/// Maps `value` into `[-1, 1]`. Everything inside the deadzone becomes 0 and the remaining range
+/// is rescaled so that the output still starts at 0 right outside the deadzone.
+pub fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    let value = value.clamp(-1.0, 1.0);
+    if value.abs() <= deadzone {
+        return 0.0;
     }
+    value.signum() * (value.abs() - deadzone) / (1.0 - deadzone)
+}
 
-    pub fn update(&mut self) {
+#[derive(Default)]
+struct Mouse {
+    delta: Vec2,
+    scroll: f32,
+}
+
+impl Mouse {
+    fn update(&mut self, delta: Vec2, state: MouseState) {
+        self.delta = delta;
+        self.scroll = state.scroll;
+    }
+}
+
+/// Which part of the game receives input, the `*_in` queries only see it in the active context
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum InputContext {
+    #[default]
+    Gameplay,
+    Ui,
+}
+
+/// Index of a local player, e.g. in split screen. Player 0 always exists.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PlayerIndex(pub u32);
+
+#[derive(Default)]
+struct PlayerActions {
+    axis: HashMap<Binding, f32>,
+    /// Actions that are held down in this frame
+    held: HashSet<Binding>,
+    /// `held` of the previous frame, to detect presses and releases
+    previous_held: HashSet<Binding>,
+}
+
+impl PlayerActions {
+    fn update(
+        &mut self,
+        player: PlayerIndex,
+        action_bindings: &[Binding],
+        axis_bindings: &[Binding],
+    ) {
         self.axis.clear();
-        self.action.clear();
-
-        for binding in &self.action_bindings {
-            let check_state = |state: ActionState| -> bool {
-                let mut out = 0;
-                unsafe {
-                    (bindings().get_action_state)(
-                        binding.as_ptr() as *const c_char,
-                        binding.len(),
-                        state,
-                        &mut out,
-                    );
-                }
-                out == 1
-            };
+        self.previous_held = std::mem::take(&mut self.held);
 
-            if check_state(ActionState::Pressed) {
-                self.action.insert(binding, Action::Pressed);
+        for binding in action_bindings {
+            let mut out = 0;
+            unsafe {
+                (bindings().get_action_state)(
+                    player.0,
+                    binding.as_ptr() as *const c_char,
+                    binding.len(),
+                    ActionState::Held,
+                    &mut out,
+                );
             }
-            if check_state(ActionState::Released) {
-                self.action.insert(binding, Action::Released);
+            if out == 1 {
+                self.held.insert(binding);
             }
         }
-        for binding in &self.axis_bindings {
+        for binding in axis_bindings {
             let mut value: f32 = 0.0;
             unsafe {
                 (bindings().get_axis_value)(
+                    player.0,
                     binding.as_ptr() as *const c_char,
                     binding.len(),
                     &mut value,
@@ -63,13 +120,593 @@ impl Input {
         }
     }
 
+    fn just_pressed(&self, binding: Binding) -> bool {
+        self.held.contains(binding) && !self.previous_held.contains(binding)
+    }
+}
+
+/// The action and axis bindings are read for every local player, see
+/// `Input::set_local_player_count`. All methods without a `PlayerIndex` read player 0.
+pub struct Input {
+    /// Never empty, the first entry is player 0
+    players: Vec<PlayerActions>,
+
+    action_bindings: Vec<Binding>,
+    axis_bindings: Vec<Binding>,
+
+    gamepads: Gamepads,
+    mouse: Mouse,
+    contexts: Vec<InputContext>,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self {
+            players: vec![PlayerActions::default()],
+            action_bindings: Vec::new(),
+            axis_bindings: Vec::new(),
+            gamepads: Gamepads::default(),
+            mouse: Mouse::default(),
+            contexts: Vec::new(),
+        }
+    }
+}
+
+impl Input {
+    pub fn register_action_binding(&mut self, binding: Binding) {
+        if !self.action_bindings.contains(&binding) {
+            self.action_bindings.push(binding);
+        }
+    }
+    pub fn register_axis_binding(&mut self, binding: Binding) {
+        if !self.axis_bindings.contains(&binding) {
+            self.axis_bindings.push(binding);
+        }
+    }
+
+    /// How many local players have their bindings read every frame. At least 1, defaults to 1.
+    pub fn set_local_player_count(&mut self, count: usize) {
+        self.players
+            .resize_with(count.max(1), PlayerActions::default);
+    }
+
+    pub fn local_player_count(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn update(&mut self) {
+        for (index, player) in self.players.iter_mut().enumerate() {
+            player.update(
+                PlayerIndex(index as u32),
+                &self.action_bindings,
+                &self.axis_bindings,
+            );
+        }
+        for (index, state) in self.gamepads.states.iter_mut().enumerate() {
+            *state = GamepadState::default();
+            unsafe {
+                (bindings().get_gamepad_state)(index as u32, state);
+            }
+        }
+
+        // The cursor does not move while it is captured, so the delta can not be derived from
+        // the cursor position
+        let mut delta = Vec2::ZERO;
+        let mut mouse = MouseState::default();
+        unsafe {
+            (bindings().get_mouse_delta)(&mut delta.x, &mut delta.y);
+            (bindings().get_mouse_state)(&mut mouse);
+        }
+        self.mouse.update(delta, mouse);
+    }
+
     pub fn get_axis_value(&self, binding: Binding) -> Option<f32> {
-        self.axis.get(&binding).copied()
+        self.player_axis_value(PlayerIndex::default(), binding)
+    }
+
+    /// `None` if the binding is not registered or the player is not tracked
+    pub fn player_axis_value(&self, player: PlayerIndex, binding: Binding) -> Option<f32> {
+        self.player(player)?.axis.get(&binding).copied()
     }
 
+    /// `is_action_held` for the local player `player`
+    pub fn player_held(&self, player: PlayerIndex, binding: Binding) -> bool {
+        self.player(player)
+            .is_some_and(|actions| actions.held.contains(binding))
+    }
+
+    /// `just_pressed` for the local player `player`
+    pub fn player_just_pressed(&self, player: PlayerIndex, binding: Binding) -> bool {
+        self.player(player)
+            .is_some_and(|actions| actions.just_pressed(binding))
+    }
+
+    fn player(&self, player: PlayerIndex) -> Option<&PlayerActions> {
+        self.players.get(player.0 as usize)
+    }
+
+    /// Same as `just_pressed`
     pub fn is_action_pressed(&self, binding: Binding) -> bool {
-        self.action
-            .get(binding)
-            .map_or(false, |state| matches!(state, Action::Pressed))
+        self.just_pressed(binding)
+    }
+
+    /// True for every frame in which the action is held down
+    pub fn is_action_held(&self, binding: Binding) -> bool {
+        self.players[0].held.contains(binding)
+    }
+
+    /// True only in the frame in which the action went down
+    pub fn just_pressed(&self, binding: Binding) -> bool {
+        self.players[0].just_pressed(binding)
+    }
+
+    /// True only in the frame in which the action was let go
+    pub fn just_released(&self, binding: Binding) -> bool {
+        let player = &self.players[0];
+        !player.held.contains(binding) && player.previous_held.contains(binding)
+    }
+
+    /// `None` if the action did not change in this frame
+    pub fn action_state(&self, binding: Binding) -> Option<Action> {
+        if self.just_pressed(binding) {
+            Some(Action::Pressed)
+        } else if self.just_released(binding) {
+            Some(Action::Released)
+        } else {
+            None
+        }
+    }
+
+    /// `InputContext::Gameplay` while nothing was pushed
+    pub fn active_context(&self) -> InputContext {
+        self.contexts.last().copied().unwrap_or_default()
+    }
+
+    /// Makes `context` the active context, e.g. when a menu is opened
+    pub fn push_context(&mut self, context: InputContext) {
+        self.contexts.push(context);
+    }
+
+    /// Restores the context that was active before the last `push_context`
+    pub fn pop_context(&mut self) -> Option<InputContext> {
+        self.contexts.pop()
+    }
+
+    /// `just_pressed`, but always false if `context` is not active
+    pub fn pressed_in(&self, context: InputContext, binding: Binding) -> bool {
+        self.active_context() == context && self.just_pressed(binding)
+    }
+
+    /// `is_action_held`, but always false if `context` is not active
+    pub fn held_in(&self, context: InputContext, binding: Binding) -> bool {
+        self.active_context() == context && self.is_action_held(binding)
+    }
+
+    /// `get_axis_value`, but always 0 if `context` is not active
+    pub fn axis_in(&self, context: InputContext, binding: Binding) -> f32 {
+        if self.active_context() == context {
+            self.get_axis_value(binding).unwrap_or(0.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Analog stick and trigger values are in `[-1, 1]` with the deadzone already applied.
+    /// Returns 0 if the gamepad is not connected.
+    pub fn gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepad(id)
+            .map(|state| apply_deadzone(state.axis(axis), self.gamepads.deadzone))
+            .unwrap_or(0.0)
+    }
+
+    pub fn gamepad_button(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad(id)
+            .is_some_and(|state| state.is_pressed(button))
+    }
+
+    pub fn is_gamepad_connected(&self, id: GamepadId) -> bool {
+        self.gamepad(id).is_some()
+    }
+
+    /// Values below `deadzone` are reported as 0. Defaults to 0.15.
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f32) {
+        self.gamepads.deadzone = deadzone.clamp(0.0, 0.99);
+    }
+
+    /// Mouse movement since the last frame, as reported by the mouse axes of unreal. Unlike the
+    /// cursor position this keeps changing while the mouse is captured.
+    pub fn mouse_delta(&self) -> Vec2 {
+        self.mouse.delta
+    }
+
+    /// Mouse wheel movement of this frame
+    pub fn scroll_delta(&self) -> f32 {
+        self.mouse.scroll
+    }
+
+    /// Hides the cursor and locks it to the viewport, e.g. for first person controls
+    pub fn set_mouse_captured(&mut self, captured: bool) {
+        unsafe {
+            (bindings().set_mouse_captured)(captured as u32);
+        }
+    }
+
+    fn gamepad(&self, id: GamepadId) -> Option<&GamepadState> {
+        self.gamepads
+            .states
+            .get(id.0 as usize)
+            .filter(|state| state.connected != 0)
+    }
+}
+
+/// Something that can drive a named action in the `ActionMap`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum InputSource {
+    /// An action mapping from the unreal project settings
+    Action(Binding),
+    /// An axis mapping from the unreal project settings
+    Axis(Binding),
+    GamepadButton(GamepadId, GamepadButton),
+    GamepadAxis(GamepadId, GamepadAxis),
+}
+
+impl InputSource {
+    fn value(&self, input: &Input) -> f32 {
+        match *self {
+            InputSource::Action(binding) => input.is_action_held(binding) as u32 as f32,
+            InputSource::Axis(binding) => input.get_axis_value(binding).unwrap_or(0.0),
+            InputSource::GamepadButton(id, button) => {
+                input.gamepad_button(id, button) as u32 as f32
+            }
+            InputSource::GamepadAxis(id, axis) => input.gamepad_axis(id, axis),
+        }
+    }
+
+    fn register(&self, input: &mut Input) {
+        match *self {
+            InputSource::Action(binding) => input.register_action_binding(binding),
+            InputSource::Axis(binding) => input.register_axis_binding(binding),
+            InputSource::GamepadButton(..) | InputSource::GamepadAxis(..) => {}
+        }
+    }
+}
+
+/// Named actions on top of `Input`. Every action is bound to one or more `InputSource`s, each
+/// with a scale, which are summed up every frame. This allows composite axes, e.g. binding
+/// "MoveForward" to a "Forward" action with a scale of 1 and a "Backward" action with a scale
+/// of -1.
+///
+/// Changes to the bindings take effect in the next frame.
+#[derive(Default)]
+pub struct ActionMap {
+    bindings: HashMap<&'static str, Vec<(InputSource, f32)>>,
+    values: HashMap<&'static str, f32>,
+    /// `Frame::elapsed` of the last frame in which each action was pressed, until it is consumed
+    /// by `buffered`
+    pressed_at: HashMap<&'static str, f64>,
+    elapsed: f64,
+}
+
+impl ActionMap {
+    pub fn bind_action(&mut self, name: &'static str, source: InputSource) -> &mut Self {
+        self.bind_axis(name, source, 1.0)
+    }
+
+    pub fn bind_axis(&mut self, name: &'static str, source: InputSource, scale: f32) -> &mut Self {
+        self.bindings.entry(name).or_default().push((source, scale));
+        self
+    }
+
+    /// Replaces all bindings of `name` with `source`
+    pub fn rebind(&mut self, name: &'static str, source: InputSource) {
+        self.bindings.insert(name, vec![(source, 1.0)]);
+    }
+
+    pub fn unbind(&mut self, name: &'static str) {
+        self.bindings.remove(name);
+        self.pressed_at.remove(name);
+    }
+
+    pub fn pressed(&self, name: &str) -> bool {
+        self.axis(name) != 0.0
+    }
+
+    /// The sum of all bound sources clamped to `[-1, 1]`
+    pub fn axis(&self, name: &str) -> f32 {
+        self.values.get(name).copied().unwrap_or(0.0)
+    }
+
+    /// True if `name` was pressed within the last `window` seconds, including this frame. A press
+    /// is only reported once, so a jump that was pressed slightly before landing still triggers,
+    /// but only a single time.
+    pub fn buffered(&mut self, name: &str, window: f32) -> bool {
+        match self.pressed_at.get(name) {
+            Some(&pressed_at) if self.elapsed - pressed_at <= window as f64 => {
+                self.pressed_at.remove(name);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Makes sure that `Input` polls every unreal binding that is used by an action
+    pub fn register_bindings(&self, input: &mut Input) {
+        for (source, _) in self.bindings.values().flatten() {
+            source.register(input);
+        }
+    }
+
+    /// `elapsed` is `Frame::elapsed`, which timestamps the presses for `buffered`
+    pub fn update(&mut self, input: &Input, elapsed: f64) {
+        let previous = std::mem::take(&mut self.values);
+        self.values = self
+            .bindings
+            .iter()
+            .map(|(&name, sources)| {
+                let value: f32 = sources
+                    .iter()
+                    .map(|(source, scale)| source.value(input) * scale)
+                    .sum();
+                (name, value.clamp(-1.0, 1.0))
+            })
+            .collect();
+
+        self.elapsed = elapsed;
+        for (&name, &value) in &self.values {
+            let was_pressed = previous.get(name).is_some_and(|&value| value != 0.0);
+            if value != 0.0 && !was_pressed {
+                self.pressed_at.insert(name, elapsed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn hold(player: u32, action: &str, held: bool) {
+        testing::with(|unreal| unreal.actions.insert((player, action.to_string()), held));
+    }
+
+    fn set_axis(player: u32, axis: &str, value: f32) {
+        testing::with(|unreal| unreal.axes.insert((player, axis.to_string()), value));
+    }
+
+    fn action_map(input: &mut Input) -> ActionMap {
+        let mut actions = ActionMap::default();
+        actions
+            .bind_axis("MoveForward", InputSource::Action("Forward"), 1.0)
+            .bind_axis("MoveForward", InputSource::Action("Backward"), -1.0)
+            .bind_axis("MoveForward", InputSource::Axis("Stick"), 1.0)
+            .bind_action("Jump", InputSource::Action("Jump"));
+        actions.register_bindings(input);
+        actions
+    }
+
+    #[test]
+    fn action_map_resolves_sources() {
+        testing::install();
+        let mut input = Input::default();
+        let mut actions = action_map(&mut input);
+
+        hold(0, "Backward", true);
+        set_axis(0, "Stick", 0.25);
+        input.update();
+        actions.update(&input, 0.0);
+        assert_eq!(actions.axis("MoveForward"), -0.75);
+        assert!(actions.pressed("MoveForward"));
+        assert!(!actions.pressed("Jump"));
+
+        // The sum is clamped
+        hold(0, "Backward", false);
+        hold(0, "Forward", true);
+        set_axis(0, "Stick", 1.0);
+        hold(0, "Jump", true);
+        input.update();
+        actions.update(&input, 0.1);
+        assert_eq!(actions.axis("MoveForward"), 1.0);
+        assert!(actions.pressed("Jump"));
+        assert_eq!(actions.axis("Unbound"), 0.0);
+    }
+
+    #[test]
+    fn gamepad_axes_apply_deadzone() {
+        testing::install();
+        testing::with(|unreal| {
+            unreal.gamepads.insert(
+                0,
+                GamepadState {
+                    connected: 1,
+                    left_x: 0.1,
+                    left_y: -0.575,
+                    right_x: 1.5,
+                    buttons: 1 << GamepadButton::FaceBottom as u32,
+                    ..Default::default()
+                },
+            );
+            // Values without `connected` are ignored
+            unreal.gamepads.insert(
+                1,
+                GamepadState {
+                    left_x: 1.0,
+                    ..Default::default()
+                },
+            );
+        });
+        let mut input = Input::default();
+        input.update();
+
+        let pad = GamepadId(0);
+        assert_eq!(input.gamepad_axis(pad, GamepadAxis::LeftX), 0.0);
+        assert!((input.gamepad_axis(pad, GamepadAxis::LeftY) + 0.5).abs() < 1e-5);
+        assert_eq!(input.gamepad_axis(pad, GamepadAxis::RightX), 1.0);
+        assert!(input.gamepad_button(pad, GamepadButton::FaceBottom));
+        assert!(!input.gamepad_button(pad, GamepadButton::FaceTop));
+
+        assert!(!input.is_gamepad_connected(GamepadId(1)));
+        assert_eq!(input.gamepad_axis(GamepadId(1), GamepadAxis::LeftX), 0.0);
+    }
+
+    #[test]
+    fn deadzone_is_configurable() {
+        testing::install();
+        testing::with(|unreal| {
+            unreal.gamepads.insert(
+                0,
+                GamepadState {
+                    connected: 1,
+                    left_x: 0.3,
+                    ..Default::default()
+                },
+            )
+        });
+        let mut input = Input::default();
+        input.set_gamepad_deadzone(0.5);
+        input.update();
+        assert_eq!(input.gamepad_axis(GamepadId(0), GamepadAxis::LeftX), 0.0);
+    }
+
+    #[test]
+    fn mouse_delta_ignores_the_cursor() {
+        testing::install();
+        let mut input = Input::default();
+
+        // A captured cursor stays in place while the mouse moves
+        testing::with(|unreal| {
+            unreal.mouse = MouseState {
+                has_position: 1,
+                position: Vec2::new(100.0, 50.0).into(),
+                scroll: 1.0,
+            };
+            unreal.mouse_delta = Vec2::new(3.0, -2.0);
+        });
+        input.update();
+        assert_eq!(input.mouse_delta(), Vec2::new(3.0, -2.0));
+        assert_eq!(input.scroll_delta(), 1.0);
+
+        testing::with(|unreal| unreal.mouse_delta = Vec2::ZERO);
+        input.update();
+        assert_eq!(input.mouse_delta(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn action_edges() {
+        testing::install();
+        let mut input = Input::default();
+        input.register_action_binding("Jump");
+
+        hold(0, "Jump", true);
+        input.update();
+        assert!(input.just_pressed("Jump"));
+        assert!(input.is_action_held("Jump"));
+        assert!(!input.just_released("Jump"));
+        assert_eq!(input.action_state("Jump"), Some(Action::Pressed));
+
+        input.update();
+        assert!(!input.just_pressed("Jump"));
+        assert!(input.is_action_held("Jump"));
+        assert!(!input.just_released("Jump"));
+        assert_eq!(input.action_state("Jump"), None);
+
+        hold(0, "Jump", false);
+        input.update();
+        assert!(!input.just_pressed("Jump"));
+        assert!(!input.is_action_held("Jump"));
+        assert!(input.just_released("Jump"));
+        assert_eq!(input.action_state("Jump"), Some(Action::Released));
+
+        input.update();
+        assert!(!input.just_released("Jump"));
+    }
+
+    #[test]
+    fn only_the_active_context_sees_input() {
+        testing::install();
+        let mut input = Input::default();
+        input.register_action_binding("Jump");
+        input.register_axis_binding("MoveForward");
+        hold(0, "Jump", true);
+        set_axis(0, "MoveForward", 1.0);
+        input.update();
+
+        assert_eq!(input.active_context(), InputContext::Gameplay);
+        assert!(input.pressed_in(InputContext::Gameplay, "Jump"));
+        assert!(!input.pressed_in(InputContext::Ui, "Jump"));
+
+        input.push_context(InputContext::Ui);
+        assert!(!input.pressed_in(InputContext::Gameplay, "Jump"));
+        assert!(!input.held_in(InputContext::Gameplay, "Jump"));
+        assert_eq!(input.axis_in(InputContext::Gameplay, "MoveForward"), 0.0);
+        assert!(input.held_in(InputContext::Ui, "Jump"));
+        assert_eq!(input.axis_in(InputContext::Ui, "MoveForward"), 1.0);
+
+        assert_eq!(input.pop_context(), Some(InputContext::Ui));
+        assert!(input.held_in(InputContext::Gameplay, "Jump"));
+        assert_eq!(input.pop_context(), None);
+    }
+
+    #[test]
+    fn local_players_have_independent_input() {
+        testing::install();
+        let mut input = Input::default();
+        input.set_local_player_count(2);
+        input.register_axis_binding("MoveForward");
+        input.register_axis_binding("MoveRight");
+        input.register_action_binding("Jump");
+        let (first, second) = (PlayerIndex(0), PlayerIndex(1));
+
+        set_axis(0, "MoveForward", 1.0);
+        set_axis(1, "MoveRight", -0.5);
+        hold(1, "Jump", true);
+        input.update();
+
+        let direction = |player| {
+            Vec2::new(
+                input.player_axis_value(player, "MoveForward").unwrap(),
+                input.player_axis_value(player, "MoveRight").unwrap(),
+            )
+        };
+        assert_eq!(direction(first), Vec2::new(1.0, 0.0));
+        assert_eq!(direction(second), Vec2::new(0.0, -0.5));
+        assert!(!input.player_just_pressed(first, "Jump"));
+        assert!(input.player_just_pressed(second, "Jump"));
+        assert!(input.player_held(second, "Jump"));
+        // The methods without a player read player 0
+        assert_eq!(input.get_axis_value("MoveForward"), Some(1.0));
+        assert!(!input.just_pressed("Jump"));
+        // Players that do not exist have no input
+        assert_eq!(input.player_axis_value(PlayerIndex(2), "MoveForward"), None);
+    }
+
+    #[test]
+    fn buffered_presses_expire_and_are_consumed() {
+        testing::install();
+        let mut input = Input::default();
+        let mut actions = action_map(&mut input);
+
+        hold(0, "Jump", true);
+        input.update();
+        actions.update(&input, 1.0);
+        hold(0, "Jump", false);
+        input.update();
+        actions.update(&input, 1.1);
+        // Pressed 0.1s ago
+        assert!(!actions.buffered("Jump", 0.05));
+        assert!(actions.buffered("Jump", 0.2));
+        assert!(!actions.buffered("Jump", 0.2));
+
+        // Holding the button is a single press
+        hold(0, "Jump", true);
+        input.update();
+        actions.update(&input, 2.0);
+        input.update();
+        actions.update(&input, 2.1);
+        assert!(actions.buffered("Jump", 0.5));
+        input.update();
+        actions.update(&input, 2.2);
+        assert!(!actions.buffered("Jump", 0.5));
+        assert!(!actions.buffered("Unbound", 0.5));
     }
 }
@@ -2,20 +2,36 @@
 extern crate self as unreal_api;
 
 pub mod api;
+pub mod asset;
+pub mod config;
 pub use unreal_ffi as ffi;
 pub mod core;
+pub mod debug_draw;
+pub mod diagnostics;
 pub mod editor_component;
+pub mod ffi_string;
 pub mod input;
 pub mod log;
+pub mod math;
 pub mod module;
+pub mod name;
+pub mod net;
 pub mod physics;
 pub mod plugin;
+pub mod save;
+pub mod scratch;
+pub mod snapshot;
 pub mod sound;
+pub mod spatial;
+pub mod task;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod timer;
+pub mod tween;
 pub use unreal_api_derive::Component;
 
 // TODO: Here for the unreal_api_derive macro. Lets restructure this
 pub use bevy_ecs as ecs;
-pub use glam as math;
 pub use unreal_reflect::*;
 
 pub use uuid;
@@ -1,5 +1,5 @@
 use glam::{Quat, Vec3};
-use log::{set_boxed_logger, set_max_level, LevelFilter, Metadata, Record, SetLoggerError};
+use log::{set_boxed_logger, set_max_level, Level, LevelFilter, Metadata, Record, SetLoggerError};
 use unreal_ffi as ffi;
 use unreal_ffi::Color;
 
@@ -7,21 +7,48 @@ use crate::{core::ActorPtr, module::bindings, physics::CollisionShape};
 struct UnrealLogger;
 
 impl log::Log for UnrealLogger {
-    fn enabled(&self, _metadata: &Metadata) -> bool {
-        // TODO
-        true
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
     }
 
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
             let text = record.args().to_string();
-            (crate::module::bindings().log)(text.as_ptr() as *const _, text.len() as i32);
+            let text = truncate(&text, i32::MAX as usize);
+            (crate::module::bindings().log)(
+                unreal_level(record.level()),
+                text.as_ptr() as *const _,
+                text.len() as i32,
+            );
         }
     }
 
     fn flush(&self) {}
 }
 
+fn unreal_level(level: Level) -> ffi::LogLevel {
+    match level {
+        Level::Error => ffi::LogLevel::Error,
+        Level::Warn => ffi::LogLevel::Warning,
+        Level::Info | Level::Debug | Level::Trace => ffi::LogLevel::Display,
+    }
+}
+
+/// Unreal takes the length as an `i32`, so longer messages are cut off at the last char boundary
+/// before `max_len`
+fn truncate(text: &str, max_len: usize) -> &str {
+    if text.len() <= max_len {
+        return text;
+    }
+    let mut len = max_len;
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    &text[..len]
+}
+
+/// Every loaded module has its own copy of the `log` crate, so this has to be called again after a
+/// hot reload. Fails if this module already installed a logger.
 pub fn init() -> Result<(), SetLoggerError> {
     set_boxed_logger(Box::new(UnrealLogger)).map(|()| set_max_level(LevelFilter::Info))
 }
@@ -114,3 +141,47 @@ impl LogCategory {
         Self { name }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Call};
+    use log::Log;
+
+    #[test]
+    fn levels_map_to_unreal() {
+        assert_eq!(unreal_level(Level::Error), ffi::LogLevel::Error);
+        assert_eq!(unreal_level(Level::Warn), ffi::LogLevel::Warning);
+        for level in [Level::Info, Level::Debug, Level::Trace] {
+            assert_eq!(unreal_level(level), ffi::LogLevel::Display);
+        }
+    }
+
+    #[test]
+    fn messages_are_forwarded() {
+        testing::install();
+        set_max_level(LevelFilter::Info);
+        UnrealLogger.log(
+            &Record::builder()
+                .level(Level::Warn)
+                .args(format_args!("null \0 inside"))
+                .build(),
+        );
+        assert_eq!(
+            testing::take_calls(),
+            vec![Call::Log {
+                level: ffi::LogLevel::Warning,
+                message: "null \0 inside".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn long_messages_are_cut_at_char_boundaries() {
+        assert_eq!(truncate("short", 10), "short");
+        // 'ä' takes two bytes
+        assert_eq!(truncate("aää", 4), "aä");
+        assert_eq!(truncate("aää", 3), "aä");
+        assert_eq!(truncate("aää", 2), "a");
+    }
+}
@@ -0,0 +1,254 @@
+pub use glam::*;
+
+/// Additions to `Vec3`. `distance`, `project_onto` and `clamp_length_max` are provided by glam
+/// directly and `xy` by `Vec3Swizzles`.
+pub trait Vec3Ext {
+    fn with_x(self, x: f32) -> Self;
+    fn with_y(self, y: f32) -> Self;
+    fn with_z(self, z: f32) -> Self;
+    /// Mirrors the vector at the plane with the normal `normal`, like a ball bouncing off a wall.
+    /// `normal` must be normalized.
+    fn reflect(self, normal: Vec3) -> Self;
+    /// True if no component differs by more than `epsilon`
+    fn approx_eq(self, other: Vec3, epsilon: f32) -> bool;
+}
+
+impl Vec3Ext for Vec3 {
+    fn with_x(mut self, x: f32) -> Self {
+        self.x = x;
+        self
+    }
+
+    fn with_y(mut self, y: f32) -> Self {
+        self.y = y;
+        self
+    }
+
+    fn with_z(mut self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+
+    fn reflect(self, normal: Vec3) -> Self {
+        self - 2.0 * self.dot(normal) * normal
+    }
+
+    fn approx_eq(self, other: Vec3, epsilon: f32) -> bool {
+        self.abs_diff_eq(other, epsilon)
+    }
+}
+
+/// Euler angles in the convention of unreal's `FRotator`, in radians. Positive `pitch` looks up,
+/// positive `yaw` turns from X towards Y and positive `roll` tilts the right side down. The
+/// rotation is applied in the order roll, pitch, yaw, i.e. around the local X, then Y, then Z
+/// axis.
+pub trait QuatExt {
+    fn from_pitch_yaw_roll(pitch: f32, yaw: f32, roll: f32) -> Self;
+    /// Returns `(pitch, yaw, roll)`. Near a pitch of ±90° yaw and roll can not be separated and
+    /// the result only describes the same rotation.
+    fn to_pitch_yaw_roll(self) -> (f32, f32, f32);
+    /// True if both quaternions describe the same rotation, up to `epsilon` per component. `q`
+    /// and `-q` are the same rotation, unlike with `Quat::abs_diff_eq`.
+    fn approx_eq(self, other: Quat, epsilon: f32) -> bool;
+}
+
+impl QuatExt for Quat {
+    fn from_pitch_yaw_roll(pitch: f32, yaw: f32, roll: f32) -> Self {
+        // Unreal rotates around Y and X in the opposite direction of the right hand rule
+        Quat::from_euler(EulerRot::ZYX, yaw, -pitch, -roll)
+    }
+
+    fn to_pitch_yaw_roll(self) -> (f32, f32, f32) {
+        let (yaw, pitch, roll) = self.to_euler(EulerRot::ZYX);
+        (-pitch, yaw, -roll)
+    }
+
+    fn approx_eq(self, other: Quat, epsilon: f32) -> bool {
+        self.abs_diff_eq(other, epsilon) || self.abs_diff_eq(-other, epsilon)
+    }
+}
+
+/// Asserts that two `Vec3`s or two `Quat`s are equal up to `epsilon` per component, with
+/// `approx_eq`, so `q` and `-q` count as the same rotation. `epsilon` defaults to `1e-4`. Only
+/// meant for tests, of this crate and of crates that use the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+#[macro_export]
+macro_rules! assert_vec_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_vec_eq!($left, $right, 1e-4)
+    };
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {{
+        #[allow(unused_imports)]
+        use $crate::math::{QuatExt as _, Vec3Ext as _};
+        let (left, right, epsilon) = ($left, $right, $epsilon);
+        assert!(
+            left.approx_eq(right, epsilon),
+            "{:?} != {:?} (epsilon {})",
+            left,
+            right,
+            epsilon
+        );
+    }};
+}
+
+/// Values that `damp` can smooth
+pub trait Damp: Sized {
+    /// `t` is in `[0, 1]`
+    fn interpolate(self, target: Self, t: f32) -> Self;
+}
+
+impl Damp for f32 {
+    fn interpolate(self, target: Self, t: f32) -> Self {
+        self + (target - self) * t
+    }
+}
+
+impl Damp for Vec3 {
+    fn interpolate(self, target: Self, t: f32) -> Self {
+        self.lerp(target, t)
+    }
+}
+
+impl Damp for Quat {
+    fn interpolate(self, target: Self, t: f32) -> Self {
+        self.slerp(target, t)
+    }
+}
+
+/// Moves `current` towards `target` so that the remaining difference shrinks by a factor of
+/// `exp(-rate * dt)`. Unlike `lerp(current, target, rate * dt)` the result does not depend on
+/// the frame rate: one step with `dt` ends up at the same value as two steps with `dt / 2`.
+pub fn damp<T: Damp>(current: T, target: T, rate: f32, dt: f32) -> T {
+    current.interpolate(target, 1.0 - f32::exp(-rate * dt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn slerp_midpoint() {
+        let a = Quat::IDENTITY;
+        let b = Quat::from_rotation_z(FRAC_PI_2);
+        let mid = a.slerp(b, 0.5);
+        assert_vec_eq!(mid, Quat::from_rotation_z(FRAC_PI_2 / 2.0), 1e-6);
+        assert!(mid.is_normalized());
+        assert_vec_eq!(a.slerp(b, 0.0), a, 1e-6);
+        assert_vec_eq!(a.slerp(b, 1.0), b, 1e-6);
+
+        // `-b` is the same rotation, the shortest path is taken either way
+        assert_vec_eq!(a.slerp(-b, 0.5), mid, 1e-6);
+    }
+
+    #[test]
+    fn slerp_near_parallel() {
+        let a = Quat::from_rotation_x(0.3);
+        let b = a * Quat::from_rotation_y(1e-5);
+        let mid = a.slerp(b, 0.5);
+        assert!(mid.is_finite());
+        assert!(mid.is_normalized());
+        assert_vec_eq!(mid, a * Quat::from_rotation_y(0.5e-5), 1e-6);
+    }
+
+    #[test]
+    fn reflect_keeps_the_angle() {
+        let incoming = Vec3::new(1.0, 0.0, -1.0);
+        let normal = Vec3::Z;
+        let reflected = incoming.reflect(normal);
+        assert_eq!(reflected, Vec3::new(1.0, 0.0, 1.0));
+        // The angle of incidence equals the angle of reflection
+        let incidence = (-incoming).angle_between(normal);
+        assert!((reflected.angle_between(normal) - incidence).abs() < 1e-6);
+        assert!((reflected.length() - incoming.length()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn clamp_length_and_helpers() {
+        let v = Vec3::new(3.0, 4.0, 0.0);
+        assert_vec_eq!(v.clamp_length_max(2.5), Vec3::new(1.5, 2.0, 0.0), 1e-6);
+        assert_eq!(v.clamp_length_max(10.0), v);
+        assert_eq!(v.clamp_length(6.0, 10.0).length(), 6.0);
+        assert_eq!(v.with_z(1.0), Vec3::new(3.0, 4.0, 1.0));
+        assert_eq!(v.xy(), Vec2::new(3.0, 4.0));
+        assert_eq!(v.project_onto(Vec3::X), Vec3::X * 3.0);
+        assert_eq!(v.distance(Vec3::ZERO), 5.0);
+    }
+
+    #[test]
+    fn pitch_yaw_roll_round_trip() {
+        for &(pitch, yaw, roll) in &[
+            (0.0, 0.0, 0.0),
+            (0.3, -1.2, 0.7),
+            (-1.1, 2.9, -2.5),
+            (1.5, 0.4, 0.1),
+        ] {
+            let q = Quat::from_pitch_yaw_roll(pitch, yaw, roll);
+            let (p, y, r) = q.to_pitch_yaw_roll();
+            assert!((p - pitch).abs() < 1e-4, "pitch {} != {}", p, pitch);
+            assert!((y - yaw).abs() < 1e-4, "yaw {} != {}", y, yaw);
+            assert!((r - roll).abs() < 1e-4, "roll {} != {}", r, roll);
+            assert_vec_eq!(Quat::from_pitch_yaw_roll(p, y, r), q, 1e-5);
+        }
+    }
+
+    #[test]
+    fn yaw_and_pitch_turn_forward_like_unreal() {
+        let forward = |pitch, yaw| Quat::from_pitch_yaw_roll(pitch, yaw, 0.0) * Vec3::X;
+        assert_vec_eq!(forward(0.0, 0.0), Vec3::X, 1e-6);
+        // Positive yaw turns right, towards +Y
+        assert_vec_eq!(forward(0.0, FRAC_PI_2), Vec3::Y, 1e-6);
+        assert_vec_eq!(forward(0.0, -FRAC_PI_2), -Vec3::Y, 1e-6);
+        // Positive pitch looks up
+        assert!(forward(FRAC_PI_2 / 2.0, 0.0).z > 0.7);
+        // Roll does not change the forward direction
+        let rolled = Quat::from_pitch_yaw_roll(0.0, FRAC_PI_2, 1.0) * Vec3::X;
+        assert_vec_eq!(rolled, Vec3::Y, 1e-6);
+    }
+
+    #[test]
+    fn damp_does_not_depend_on_the_step_size() {
+        let one_step = damp(0.0, 10.0, 4.0, 0.1);
+        let mut many_steps = 0.0;
+        for _ in 0..10 {
+            many_steps = damp(many_steps, 10.0, 4.0, 0.01);
+        }
+        assert!((one_step - many_steps).abs() < 1e-4);
+        assert!((one_step - 10.0 * (1.0 - f32::exp(-0.4))).abs() < 1e-5);
+
+        let target = Vec3::new(4.0, -2.0, 8.0);
+        let half = damp(damp(Vec3::ZERO, target, 2.0, 0.25), target, 2.0, 0.25);
+        assert_vec_eq!(damp(Vec3::ZERO, target, 2.0, 0.5), half, 1e-5);
+
+        // No movement without time, no overshoot with a lot of it
+        assert_eq!(damp(3.0, 10.0, 4.0, 0.0), 3.0);
+        assert!((damp(3.0, 10.0, 4.0, 100.0) - 10.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn negated_quaternions_are_the_same_rotation() {
+        let q = Quat::from_pitch_yaw_roll(0.3, -1.2, 0.7);
+        assert!(q.approx_eq(-q, 1e-6));
+        assert!(!q.abs_diff_eq(-q, 1e-6));
+        assert_vec_eq!(q, -q);
+        // Both still rotate vectors the same way
+        assert_vec_eq!(q * Vec3::X, -q * Vec3::X);
+
+        assert!(!q.approx_eq(Quat::from_pitch_yaw_roll(0.3, -1.2, 0.8), 1e-4));
+        assert!(!q.approx_eq(-Quat::IDENTITY, 1e-4));
+    }
+
+    #[test]
+    fn vectors_are_equal_within_epsilon() {
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        assert!(v.approx_eq(v + Vec3::splat(1e-5), 1e-4));
+        assert!(!v.approx_eq(v + Vec3::Z * 1e-3, 1e-4));
+        assert_vec_eq!(v, v + Vec3::X * 5e-5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn assert_vec_eq_fails_outside_epsilon() {
+        assert_vec_eq!(Vec3::X, Vec3::Y);
+    }
+}
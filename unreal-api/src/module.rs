@@ -1,18 +1,31 @@
 use std::collections::{HashMap, HashSet};
 
 use bevy_ecs::{
+    bundle::Bundle,
+    component::Component,
     event::Event,
-    prelude::{Events, System},
-    schedule::{Schedule, StageLabel, SystemSet, SystemStage},
+    prelude::{Events, IntoSystem, System},
+    schedule::{
+        ParallelSystemDescriptorCoercion, Schedule, ShouldRun, StageLabel, SystemSet, SystemStage,
+    },
     system::Resource,
 };
-use unreal_reflect::{registry::ReflectDyn, uuid, TypeUuid, World};
+use serde::{de::DeserializeOwned, Serialize};
+use unreal_reflect::{
+    registry::{ReflectDyn, ReflectType, ReflectValue},
+    uuid, Entity, TypeUuid, World,
+};
 
 use crate::{
-    core::{CoreStage, StartupStage, UnrealCore},
+    asset::{AssetError, AssetRegistry, ClassHandle},
+    core::{
+        notify_component_changed, ClassBundleRegistry, CoreStage, FixedTimestep,
+        NotifyComponentChanges, StartupStage, UnrealCore,
+    },
     editor_component::InsertEditorComponent,
     ffi::UnrealBindings,
     plugin::Plugin,
+    save::{Serializable, SerializableComponent},
 };
 
 pub static mut MODULE: Option<Global> = None;
@@ -21,6 +34,15 @@ pub struct Global {
     pub module: Box<dyn UserModule>,
 }
 
+/// The loaded module, `None` until `implement_unreal_module!` initialized it
+///
+/// # Safety
+/// `MODULE` is a process wide global, only call this from the game thread and don't hold on to
+/// the reference across calls from unreal.
+pub unsafe fn global() -> Option<&'static mut Global> {
+    (*std::ptr::addr_of_mut!(MODULE)).as_mut()
+}
+
 pub trait InitUserModule {
     fn initialize() -> Self;
 }
@@ -41,8 +63,13 @@ pub trait InsertReflectionStruct {
 #[derive(Default)]
 pub struct ReflectionRegistry {
     pub uuid_set: HashSet<uuid::Uuid>,
+    /// Used to report both types when a UUID is registered twice
+    pub type_names: HashMap<uuid::Uuid, &'static str>,
     pub reflect: HashMap<uuid::Uuid, Box<dyn ReflectDyn>>,
     pub insert_editor_component: HashMap<uuid::Uuid, Box<dyn InsertEditorComponent>>,
+    pub serializable: HashMap<uuid::Uuid, Box<dyn Serializable>>,
+    /// Inserts the default value of the component, see `Module::register_default_component`
+    pub insert_default: HashMap<uuid::Uuid, fn(&mut World, Entity)>,
 }
 
 impl ReflectionRegistry {
@@ -50,16 +77,127 @@ impl ReflectionRegistry {
     where
         T: InsertReflectionStruct + TypeUuid + 'static,
     {
-        if self.uuid_set.contains(&T::TYPE_UUID) {
+        let type_name = std::any::type_name::<T>();
+        if let Some(existing) = self.type_names.get(&T::TYPE_UUID) {
             panic!(
-                "Duplicated UUID {} for {}",
+                "Duplicated UUID {}: {} uses the same UUID as {}",
                 T::TYPE_UUID,
-                std::any::type_name::<T>()
+                type_name,
+                existing
             );
         }
         T::insert(self);
         self.uuid_set.insert(T::TYPE_UUID);
+        self.type_names.insert(T::TYPE_UUID, type_name);
+    }
+
+    /// The name and the reflected fields of a registered component, in field index order
+    pub fn component_info(
+        &self,
+        uuid: &uuid::Uuid,
+    ) -> Option<(&'static str, Vec<(&'static str, ReflectType)>)> {
+        let reflect = self.reflect.get(uuid)?;
+        let fields = (0..reflect.number_of_fields())
+            .filter_map(|idx| Some((reflect.get_field_name(idx)?, reflect.get_field_type(idx)?)))
+            .collect();
+        Some((reflect.name(), fields))
     }
+
+    /// Reads the field `field_name` of the component `uuid` on `entity`
+    pub fn get_field(
+        &self,
+        world: &World,
+        entity: Entity,
+        uuid: &uuid::Uuid,
+        field_name: &str,
+    ) -> Option<ReflectValue> {
+        let reflect = self.reflect.get(uuid)?;
+        let idx = field_index(reflect.as_ref(), field_name)?;
+        reflect.get_field_value(world, entity, idx)
+    }
+
+    /// Writes `value` into the field `field_name` of the component `uuid` on `entity`. Returns
+    /// false if the entity has no such component or field, or if `value` has the wrong type.
+    pub fn set_field(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        uuid: &uuid::Uuid,
+        field_name: &str,
+        value: ReflectValue,
+    ) -> bool {
+        match self.reflect.get(uuid) {
+            Some(reflect) => field_index(reflect.as_ref(), field_name)
+                .is_some_and(|idx| reflect.set_field_value(world, entity, idx, value)),
+            None => false,
+        }
+    }
+
+    /// The component has to be registered with `register` as well
+    pub fn register_serializable<T>(&mut self)
+    where
+        T: Component + TypeUuid + Serialize + DeserializeOwned,
+    {
+        self.serializable
+            .insert(T::TYPE_UUID, Box::new(SerializableComponent::<T>::new()));
+    }
+
+    /// The component has to be registered with `register` as well
+    pub fn register_default<T>(&mut self)
+    where
+        T: Component + TypeUuid + Default,
+    {
+        self.insert_default.insert(T::TYPE_UUID, |world, entity| {
+            world.entity_mut(entity).insert(T::default());
+        });
+    }
+
+    /// The name and reflected field values of every registered component on `entity`, e.g. for
+    /// an inspector. Components that are not registered are skipped.
+    pub fn components_on(
+        &self,
+        world: &World,
+        entity: Entity,
+    ) -> Vec<(String, HashMap<String, ReflectValue>)> {
+        self.reflect
+            .values()
+            .filter(|reflect| reflect.has_component(world, entity))
+            .map(|reflect| {
+                let fields = field_values(reflect.as_ref(), world, entity);
+                (reflect.name().to_string(), fields)
+            })
+            .collect()
+    }
+
+    /// The reflected fields of the default value of the component `uuid`, keyed by field name.
+    /// `None` if the component was not registered with both `register` and `register_default`.
+    pub fn default_fields(&self, uuid: &uuid::Uuid) -> Option<HashMap<String, ReflectValue>> {
+        let reflect = self.reflect.get(uuid)?;
+        let insert_default = self.insert_default.get(uuid)?;
+        // The default value is only reachable through a world, so it is built in a scratch world
+        let mut world = World::new();
+        let entity = world.spawn().id();
+        insert_default(&mut world, entity);
+        Some(field_values(reflect.as_ref(), &world, entity))
+    }
+}
+
+fn field_values(
+    reflect: &dyn ReflectDyn,
+    world: &World,
+    entity: Entity,
+) -> HashMap<String, ReflectValue> {
+    (0..reflect.number_of_fields())
+        .filter_map(|idx| {
+            let name = reflect.get_field_name(idx)?;
+            let value = reflect.get_field_value(world, entity, idx)?;
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+fn field_index(reflect: &dyn ReflectDyn, field_name: &str) -> Option<u32> {
+    (0..reflect.number_of_fields()).find(|&idx| reflect.get_field_name(idx) == Some(field_name))
 }
 
 pub struct Module {
@@ -67,6 +205,8 @@ pub struct Module {
     pub(crate) startup: Schedule,
     pub(crate) reflection_registry: ReflectionRegistry,
     pub(crate) world: World,
+    pub(crate) fixed_timestep: FixedTimestep,
+    pub(crate) preserve_state_on_reload: bool,
 }
 
 impl Module {
@@ -79,6 +219,8 @@ impl Module {
             startup,
             reflection_registry: ReflectionRegistry::default(),
             world: World::new(),
+            fixed_timestep: FixedTimestep::default(),
+            preserve_state_on_reload: false,
         }
     }
     pub fn insert_resource(&mut self, resource: impl Resource) -> &mut Self {
@@ -92,6 +234,9 @@ impl Module {
         self
     }
 
+    /// Inserts a new stage right after `label`, e.g. to run AI after `CoreStage::Update` but
+    /// before the transforms are uploaded in `CoreStage::PostUpdate`. `label` can be a
+    /// `CoreStage` or any stage that was added before.
     pub fn add_stage_after(
         &mut self,
         label: impl StageLabel,
@@ -102,6 +247,34 @@ impl Module {
         self
     }
 
+    pub fn add_stage_after_with_run_criteria<Param>(
+        &mut self,
+        label: impl StageLabel,
+        insert: impl StageLabel,
+        run_criteria: impl IntoSystem<(), ShouldRun, Param>,
+    ) -> &mut Self {
+        self.schedule.add_stage_after(
+            label,
+            insert,
+            SystemStage::single_threaded().with_run_criteria(run_criteria),
+        );
+        self
+    }
+
+    /// Configures how often `CoreStage::FixedUpdate` runs. Defaults to 60 times per second.
+    pub fn set_fixed_timestep(&mut self, step: f32, max_substeps: u32) -> &mut Self {
+        self.fixed_timestep = FixedTimestep::new(step, max_substeps);
+        self
+    }
+
+    /// Keeps the `Serializable` components of all actors when the module is hot reloaded during
+    /// play. Without this, every entity starts from scratch after a reload.
+    pub fn preserve_state_on_reload(&mut self) -> &mut Self {
+        self.preserve_state_on_reload = true;
+        self
+    }
+
+    /// Inserts a new stage right before `label`. See `Module::add_stage_after`.
     pub fn add_stage_before(
         &mut self,
         label: impl StageLabel,
@@ -112,6 +285,32 @@ impl Module {
         self
     }
 
+    /// Like `add_stage_after`, but systems of the new stage whose data access does not conflict
+    /// run in parallel on worker threads. Meant for CPU heavy gameplay like AI that only works
+    /// with components and resources. Systems in it must not call into unreal, which is only safe
+    /// from the game thread. All other stages, including the `CoreStage`s that sync with unreal,
+    /// stay single threaded.
+    pub fn add_parallel_stage_after(
+        &mut self,
+        label: impl StageLabel,
+        insert: impl StageLabel,
+    ) -> &mut Self {
+        self.schedule
+            .add_stage_after(label, insert, SystemStage::parallel());
+        self
+    }
+
+    /// Inserts a new parallel stage right before `label`. See `Module::add_parallel_stage_after`.
+    pub fn add_parallel_stage_before(
+        &mut self,
+        label: impl StageLabel,
+        insert: impl StageLabel,
+    ) -> &mut Self {
+        self.schedule
+            .add_stage_before(label, insert, SystemStage::parallel());
+        self
+    }
+
     pub fn add_system_set_to_stage(&mut self, label: impl StageLabel, set: SystemSet) -> &mut Self {
         self.schedule.add_system_set_to_stage(label, set);
         self
@@ -124,6 +323,58 @@ impl Module {
         self.reflection_registry.register::<T>();
     }
 
+    /// Allows the component to be saved with `save::serialize_world`
+    pub fn register_serializable_component<T>(&mut self)
+    where
+        T: InsertReflectionStruct + TypeUuid + Component + Serialize + DeserializeOwned,
+    {
+        self.reflection_registry.register_serializable::<T>();
+    }
+
+    /// Allows unreal to add the component at runtime with its default value
+    pub fn register_default_component<T>(&mut self)
+    where
+        T: InsertReflectionStruct + TypeUuid + Component + Default,
+    {
+        self.reflection_registry.register_default::<T>();
+    }
+
+    /// Loads an actor class once while the module is initialized, see `AssetRegistry`
+    pub fn register_class(&mut self, path: &str) -> Result<ClassHandle, AssetError> {
+        self.world
+            .get_resource_or_insert_with(AssetRegistry::default)
+            .register_class(path)
+    }
+
+    /// Notifies unreal at the end of every frame in which `T` was added to or changed on an
+    /// entity with an actor, see `UEntityComponent::OnComponentChanged`
+    pub fn observe_component<T>(&mut self) -> &mut Self
+    where
+        T: Component + TypeUuid,
+    {
+        self.add_system_set_to_stage(
+            CoreStage::PostUpdate,
+            SystemSet::new()
+                .with_system(notify_component_changed::<T>.label(NotifyComponentChanges)),
+        )
+    }
+
+    /// Every actor of the unreal class `class_name` gets `bundle` when it is registered. See
+    /// `ClassBundleRegistry`.
+    pub fn register_class_bundle<B>(
+        &mut self,
+        class_name: impl Into<String>,
+        bundle: B,
+    ) -> &mut Self
+    where
+        B: Bundle + Clone + Send + Sync,
+    {
+        self.world
+            .get_resource_or_insert_with(ClassBundleRegistry::default)
+            .register(class_name, bundle);
+        self
+    }
+
     pub fn add_plugin<P: Plugin>(&mut self, plugin: P) -> &mut Self {
         plugin.build(self);
         self
@@ -153,6 +404,49 @@ impl Default for Module {
 
 pub trait UserModule {
     fn initialize(&self, module: &mut Module);
+    /// Called once play has started, after the startup systems ran
+    fn on_begin_play(&self, _world: &mut World) {}
+    /// Called when play ends, before all entities are despawned
+    fn on_end_play(&self, _world: &mut World) {}
+}
+
+/// Combines several `UserModule`s into one. The modules are initialized in the order they were
+/// added. Registering the same component UUID from two modules panics, see
+/// `ReflectionRegistry::register`.
+#[derive(Default)]
+pub struct CompositeModule {
+    modules: Vec<Box<dyn UserModule>>,
+}
+
+impl CompositeModule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_module(mut self, module: impl UserModule + 'static) -> Self {
+        self.modules.push(Box::new(module));
+        self
+    }
+}
+
+impl UserModule for CompositeModule {
+    fn initialize(&self, module: &mut Module) {
+        for user_module in &self.modules {
+            user_module.initialize(module);
+        }
+    }
+
+    fn on_begin_play(&self, world: &mut World) {
+        for user_module in &self.modules {
+            user_module.on_begin_play(world);
+        }
+    }
+
+    fn on_end_play(&self, world: &mut World) {
+        for user_module in &self.modules {
+            user_module.on_end_play(world);
+        }
+    }
 }
 pub static mut BINDINGS: Option<UnrealBindings> = None;
 
@@ -188,14 +482,21 @@ macro_rules! implement_unreal_module {
 
             let r = std::panic::catch_unwind(|| unsafe {
                 let module = Box::new(<$module as $crate::module::InitUserModule>::initialize());
-                let core = $crate::core::UnrealCore::new(module.as_ref());
+                let core = $crate::core::UnrealCore::new_with::<$module>(&module);
 
                 $crate::module::MODULE = Some($crate::module::Global { core, module });
                 $crate::ffi::RustBindings {
                     retrieve_uuids: $crate::core::retrieve_uuids,
+                    retrieve_component_info: $crate::core::retrieve_component_info,
+                    prepare_reload: $crate::core::prepare_reload,
+                    finish_reload: $crate::core::finish_reload,
                     tick: $crate::core::tick,
                     begin_play: $crate::core::begin_play,
+                    end_play: $crate::core::end_play,
                     unreal_event: $crate::core::unreal_event,
+                    unregister_actor: $crate::core::unregister_actor,
+                    remove_component: $crate::core::remove_component,
+                    add_component: $crate::core::add_component,
                     reflection_fns: $crate::core::create_reflection_fns(),
                     allocate_fns: $crate::core::create_allocate_fns(),
                 }
@@ -212,5 +513,209 @@ macro_rules! implement_unreal_module {
 }
 
 pub fn bindings() -> &'static UnrealBindings {
-    unsafe { BINDINGS.as_ref().unwrap() }
+    unsafe { (*std::ptr::addr_of!(BINDINGS)).as_ref().unwrap() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ActorComponent, PlayerInputComponent, TransformComponent};
+    use crate::math::{Quat, Vec3};
+
+    #[derive(Default, Debug, crate::Component)]
+    #[uuid = "b8738d9e-ab21-47db-8587-4019b38e35a6"]
+    pub struct SameUuidComponent {
+        pub value: f32,
+    }
+
+    struct Register(fn(&mut Module));
+
+    impl UserModule for Register {
+        fn initialize(&self, module: &mut Module) {
+            (self.0)(module)
+        }
+    }
+
+    fn initialize(user_module: &dyn UserModule) -> Module {
+        let mut module = Module::new();
+        user_module.initialize(&mut module);
+        module
+    }
+
+    #[test]
+    fn composite_registers_all_modules() {
+        let composite = CompositeModule::new()
+            .with_module(Register(|module| {
+                module.register_component::<TransformComponent>()
+            }))
+            .with_module(Register(|module| {
+                module.register_component::<ActorComponent>()
+            }));
+        let registry = initialize(&composite).reflection_registry;
+
+        assert!(registry.uuid_set.contains(&TransformComponent::TYPE_UUID));
+        assert!(registry.uuid_set.contains(&ActorComponent::TYPE_UUID));
+    }
+
+    #[test]
+    fn duplicate_uuid_names_both_types() {
+        let mut registry = ReflectionRegistry::default();
+        registry.register::<TransformComponent>();
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            registry.register::<SameUuidComponent>()
+        }))
+        .unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+
+        assert!(message.contains(std::any::type_name::<TransformComponent>()));
+        assert!(message.contains(std::any::type_name::<SameUuidComponent>()));
+    }
+
+    #[test]
+    fn component_info_lists_fields() {
+        let mut registry = ReflectionRegistry::default();
+        registry.register::<TransformComponent>();
+
+        let (name, fields) = registry
+            .component_info(&TransformComponent::TYPE_UUID)
+            .unwrap();
+        assert_eq!(name, "TransformComponent");
+        assert_eq!(
+            fields,
+            vec![
+                ("position", ReflectType::Vector3),
+                ("rotation", ReflectType::Quat),
+                ("scale", ReflectType::Vector3),
+            ]
+        );
+        assert!(registry.component_info(&uuid::Uuid::nil()).is_none());
+    }
+
+    #[test]
+    fn fields_are_accessed_by_name() {
+        let mut registry = ReflectionRegistry::default();
+        registry.register::<TransformComponent>();
+        let mut custom_registry = ReflectionRegistry::default();
+        custom_registry.register::<SameUuidComponent>();
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                TransformComponent::default(),
+                SameUuidComponent { value: 2.0 },
+            ))
+            .id();
+        let transform = TransformComponent::TYPE_UUID;
+
+        let scale = crate::math::Vec3::new(1.0, 2.0, 3.0);
+        assert!(registry.set_field(
+            &mut world,
+            entity,
+            &transform,
+            "scale",
+            ReflectValue::Vector3(scale)
+        ));
+        assert_eq!(
+            world.get::<TransformComponent>(entity).unwrap().scale,
+            scale
+        );
+        assert!(matches!(
+            registry.get_field(&world, entity, &transform, "scale"),
+            Some(ReflectValue::Vector3(value)) if value == scale
+        ));
+
+        // Wrong types and unknown fields are rejected
+        assert!(!registry.set_field(
+            &mut world,
+            entity,
+            &transform,
+            "scale",
+            ReflectValue::Float(1.0)
+        ));
+        assert!(!registry.set_field(
+            &mut world,
+            entity,
+            &transform,
+            "size",
+            ReflectValue::Vector3(scale)
+        ));
+        assert!(registry
+            .get_field(&world, entity, &transform, "size")
+            .is_none());
+        assert_eq!(
+            world.get::<TransformComponent>(entity).unwrap().scale,
+            scale
+        );
+
+        let custom = SameUuidComponent::TYPE_UUID;
+        assert!(matches!(
+            custom_registry.get_field(&world, entity, &custom, "value"),
+            Some(ReflectValue::Float(value)) if value == 2.0
+        ));
+        assert!(custom_registry.set_field(
+            &mut world,
+            entity,
+            &custom,
+            "value",
+            ReflectValue::Float(5.0)
+        ));
+        assert_eq!(world.get::<SameUuidComponent>(entity).unwrap().value, 5.0);
+    }
+
+    #[test]
+    fn default_fields_of_the_transform() {
+        let mut registry = ReflectionRegistry::default();
+        registry.register::<TransformComponent>();
+        assert!(registry
+            .default_fields(&TransformComponent::TYPE_UUID)
+            .is_none());
+        registry.register_default::<TransformComponent>();
+
+        let fields = registry
+            .default_fields(&TransformComponent::TYPE_UUID)
+            .unwrap();
+        assert!(matches!(fields["position"], ReflectValue::Vector3(v) if v == Vec3::ZERO));
+        assert!(matches!(fields["rotation"], ReflectValue::Quat(q) if q == Quat::IDENTITY));
+        assert!(matches!(fields["scale"], ReflectValue::Vector3(v) if v == Vec3::ONE));
+    }
+
+    #[test]
+    fn components_on_lists_registered_components() {
+        let mut registry = ReflectionRegistry::default();
+        registry.register::<TransformComponent>();
+        registry.register::<PlayerInputComponent>();
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .insert_bundle((
+                TransformComponent {
+                    position: Vec3::X,
+                    ..Default::default()
+                },
+                PlayerInputComponent {
+                    direction: Vec3::Y,
+                    ..Default::default()
+                },
+                // Not registered
+                SameUuidComponent { value: 1.0 },
+            ))
+            .id();
+        let bare = world.spawn().insert(TransformComponent::default()).id();
+
+        let mut components = registry.components_on(&world, entity);
+        components.sort_by(|a, b| a.0.cmp(&b.0));
+        let names: Vec<_> = components.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["PlayerInputComponent", "TransformComponent"]);
+        assert!(matches!(
+            components[0].1["direction"],
+            ReflectValue::Vector3(v) if v == Vec3::Y
+        ));
+        let transform = &components[1].1;
+        assert_eq!(transform.len(), 3);
+        assert!(matches!(transform["position"], ReflectValue::Vector3(v) if v == Vec3::X));
+
+        let components = registry.components_on(&world, bare);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].0, "TransformComponent");
+    }
 }
@@ -0,0 +1,105 @@
+use std::{cell::RefCell, collections::HashMap, os::raw::c_char, sync::Arc};
+
+use crate::module::bindings;
+
+thread_local! {
+    // Names are only ever created on the game thread
+    static NAME_IDS: RefCell<HashMap<Arc<str>, u32>> = RefCell::new(HashMap::new());
+}
+
+/// A string that was registered in the name table of unreal, like the names of sockets, bones or
+/// tags. Each distinct string is only sent to unreal once, later conversions of the same string
+/// reuse the cached id. Two `FName`s are equal if their ids are equal, so like in unreal names
+/// that only differ in case are equal.
+#[derive(Debug, Clone)]
+pub struct FName {
+    id: u32,
+    name: Arc<str>,
+}
+
+impl FName {
+    /// The id of the name in unreal
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+}
+
+impl From<&str> for FName {
+    fn from(name: &str) -> Self {
+        NAME_IDS.with(|ids| {
+            let mut ids = ids.borrow_mut();
+            if let Some((name, &id)) = ids.get_key_value(name) {
+                return FName {
+                    id,
+                    name: name.clone(),
+                };
+            }
+            let id =
+                unsafe { (bindings().register_name)(name.as_ptr() as *const c_char, name.len()) };
+            let name: Arc<str> = Arc::from(name);
+            ids.insert(name.clone(), id);
+            FName { id, name }
+        })
+    }
+}
+
+impl PartialEq for FName {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for FName {}
+
+impl std::hash::Hash for FName {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl std::fmt::Display for FName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn registered_names() -> usize {
+        testing::with(|unreal| unreal.names.len())
+    }
+
+    #[test]
+    fn same_string_reuses_the_cached_id() {
+        testing::install();
+        let first = FName::from("name_test_hand_r");
+        assert_eq!(registered_names(), 1);
+
+        let second = FName::from("name_test_hand_r");
+        assert_eq!(first.id(), second.id());
+        assert_eq!(first, second);
+        // Served from the cache, unreal only saw the string once
+        assert_eq!(registered_names(), 1);
+
+        let other = FName::from("name_test_hand_l");
+        assert_ne!(other, first);
+        assert_eq!(registered_names(), 2);
+    }
+
+    #[test]
+    fn names_are_case_insensitive() {
+        testing::install();
+        let lower = FName::from("name_test_muzzle");
+        let upper = FName::from("Name_Test_Muzzle");
+        assert_eq!(lower, upper);
+        assert_eq!(upper.as_str(), "Name_Test_Muzzle");
+        assert_eq!(upper.to_string(), "Name_Test_Muzzle");
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use bevy_ecs::entity::Entity;
+
+use crate::{ffi, Component};
+
+/// The network GUID that unreal assigned to the actor of this entity. Unlike the actor pointer it
+/// is the same on every machine. Purely local actors have no `NetId`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Component)]
+#[uuid = "0c7d1c8e-5b0a-4f7e-a3d2-6f1b9e4a2c57"]
+pub struct NetId(#[reflect(skip)] pub u64);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NetRole {
+    /// Simulated here, e.g. everything on the server or in single player
+    Authority,
+    /// Follows the state that the server replicates
+    SimulatedProxy,
+    /// Like `SimulatedProxy`, but controlled by a local player
+    AutonomousProxy,
+}
+
+impl From<ffi::NetRole> for NetRole {
+    fn from(role: ffi::NetRole) -> Self {
+        match role {
+            ffi::NetRole::Authority => NetRole::Authority,
+            ffi::NetRole::SimulatedProxy => NetRole::SimulatedProxy,
+            ffi::NetRole::AutonomousProxy => NetRole::AutonomousProxy,
+        }
+    }
+}
+
+/// The local role of a replicated actor, inserted together with its `NetId`. Gameplay systems
+/// that simulate entities should skip entities without authority, entities without this
+/// component always have authority. The transforms of simulated proxies are only downloaded from
+/// unreal and never uploaded.
+#[derive(Debug, Copy, Clone, Component)]
+#[uuid = "6d2f8b1a-3e9c-4a57-b4d0-1c7e5f9a2b63"]
+pub struct NetRoleComponent {
+    #[reflect(skip)]
+    pub role: NetRole,
+}
+
+impl Default for NetRoleComponent {
+    fn default() -> Self {
+        Self {
+            role: NetRole::Authority,
+        }
+    }
+}
+
+impl NetRoleComponent {
+    pub fn has_authority(&self) -> bool {
+        self.role == NetRole::Authority
+    }
+
+    pub fn is_proxy(&self) -> bool {
+        !self.has_authority()
+    }
+}
+
+/// True for entities without a `NetRoleComponent`
+pub fn has_authority(role: Option<&NetRoleComponent>) -> bool {
+    role.is_none_or(NetRoleComponent::has_authority)
+}
+
+/// Maps every `NetId` to its entity and back
+#[derive(Default, Debug)]
+pub struct NetIdRegistry {
+    net_id_to_entity: HashMap<NetId, Entity>,
+    entity_to_net_id: HashMap<Entity, NetId>,
+}
+
+impl NetIdRegistry {
+    pub fn register(&mut self, net_id: NetId, entity: Entity) {
+        self.net_id_to_entity.insert(net_id, entity);
+        self.entity_to_net_id.insert(entity, net_id);
+    }
+
+    pub fn entity(&self, net_id: NetId) -> Option<Entity> {
+        self.net_id_to_entity.get(&net_id).copied()
+    }
+
+    pub fn net_id(&self, entity: Entity) -> Option<NetId> {
+        self.entity_to_net_id.get(&entity).copied()
+    }
+
+    /// Returns `None` if the entity had no `NetId`
+    pub fn unregister_entity(&mut self, entity: &Entity) -> Option<NetId> {
+        let net_id = self.entity_to_net_id.remove(entity)?;
+        self.net_id_to_entity.remove(&net_id);
+        Some(net_id)
+    }
+}
@@ -8,6 +8,8 @@ use crate::{
     module::bindings,
 };
 
+pub use crate::ffi::CollisionChannel;
+
 #[derive(Debug)]
 pub struct SweepResult {
     pub actor: Option<ActorPtr>,
@@ -16,7 +18,7 @@ pub struct SweepResult {
     pub penetration_depth: f32,
     pub normal: Vec3,
     pub impact_normal: Vec3,
-    pub start_in_penentration: bool,
+    pub started_penetrating: bool,
 }
 
 #[derive(Clone, Default)]
@@ -70,7 +72,7 @@ pub fn sweep_multi(
                             location: hit.location.into(),
                             normal: hit.normal.into(),
                             penetration_depth: hit.pentration_depth,
-                            start_in_penentration: hit.start_penetrating == 1,
+                            started_penetrating: hit.start_penetrating == 1,
                             impact_normal: hit.impact_normal.into(),
                         }
                     })
@@ -178,7 +180,22 @@ pub struct PhysicsComponent {
     #[reflect(skip)]
     pub ptr: UnrealPtr<Primitive>,
     pub is_simulating: bool,
-    pub velocity: Vec3,
+    /// The velocity that was downloaded from unreal at the start of the frame. Private so that
+    /// every change goes through `PhysicsComponent::set_velocity`, which marks it for upload.
+    velocity: Vec3,
+    /// In radians per second. Same as `velocity`, see `PhysicsComponent::set_angular_velocity`.
+    angular_velocity: Vec3,
+    #[reflect(skip)]
+    velocity_changed: bool,
+    #[reflect(skip)]
+    angular_velocity_changed: bool,
+    #[reflect(skip)]
+    collision: ffi::CollisionResponse,
+    #[reflect(skip)]
+    collision_changed: bool,
+    /// Disabled components are neither downloaded nor uploaded, see `set_enabled`
+    #[reflect(skip)]
+    disabled: bool,
 }
 
 impl PhysicsComponent {
@@ -216,14 +233,164 @@ impl PhysicsComponent {
     pub fn download_state(&mut self) {
         unsafe {
             self.is_simulating = (bindings().physics_fns.is_simulating)(self.ptr.ptr) == 1;
-            self.velocity = (bindings().physics_fns.get_velocity)(self.ptr.ptr).into();
+            // Changes that were not uploaded yet win over the state in unreal
+            if !self.velocity_changed {
+                self.velocity = (bindings().physics_fns.get_velocity)(self.ptr.ptr).into();
+            }
+            if !self.angular_velocity_changed {
+                self.angular_velocity =
+                    (bindings().physics_fns.get_angular_velocity)(self.ptr.ptr).into();
+            }
+            if !self.collision_changed {
+                (bindings().physics_fns.get_collision_response)(self.ptr.ptr, &mut self.collision);
+            }
         }
     }
 
-    pub fn upload_state(&mut self) {
+    /// A disabled component keeps its last state and is skipped by the core sync systems and the
+    /// gameplay plugins, without having to remove it. Enabling it again downloads the current
+    /// state from unreal and drops all changes that were made while it was disabled.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled != self.disabled {
+            return;
+        }
+        self.disabled = !enabled;
+        if enabled {
+            self.velocity_changed = false;
+            self.angular_velocity_changed = false;
+            self.collision_changed = false;
+            self.download_state();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.disabled
+    }
+
+    /// True if the primitive is simulating and the component is enabled, i.e. the velocity is
+    /// driven by physics
+    pub fn is_active(&self) -> bool {
+        self.is_simulating && !self.disabled
+    }
+
+    /// Turns the physics simulation of the primitive on or off right away. Turning it off drops
+    /// the velocity, turning it on downloads the current state from unreal.
+    pub fn set_simulating(&mut self, simulating: bool) {
+        if simulating == self.is_simulating {
+            return;
+        }
         unsafe {
-            (bindings().physics_fns.set_velocity)(self.ptr.ptr, self.velocity.into());
+            (bindings().physics_fns.set_simulate_physics)(self.ptr.ptr, simulating as u32);
+        }
+        self.is_simulating = simulating;
+        if simulating {
+            self.download_state();
+        } else {
+            self.velocity = Vec3::ZERO;
+            self.angular_velocity = Vec3::ZERO;
+            self.velocity_changed = false;
+            self.angular_velocity_changed = false;
+        }
+    }
+
+    /// Uploads the velocity if it was changed with `PhysicsComponent::set_velocity`. Uploading it
+    /// unconditionally would throw away every impulse or force that was added this frame.
+    /// Velocities are only uploaded while the primitive is simulating.
+    pub fn upload_state(&mut self) {
+        if !self.is_simulating {
+            self.velocity_changed = false;
+            self.angular_velocity_changed = false;
         }
+        if self.velocity_changed {
+            self.velocity_changed = false;
+            unsafe {
+                (bindings().physics_fns.set_velocity)(self.ptr.ptr, self.velocity.into());
+            }
+        }
+        if self.angular_velocity_changed {
+            self.angular_velocity_changed = false;
+            unsafe {
+                (bindings().physics_fns.set_angular_velocity)(
+                    self.ptr.ptr,
+                    self.angular_velocity.into(),
+                );
+            }
+        }
+        if self.collision_changed {
+            self.collision_changed = false;
+            unsafe {
+                (bindings().physics_fns.set_collision_response)(self.ptr.ptr, self.collision);
+            }
+        }
+    }
+
+    /// A bit for every `CollisionChannel` that this primitive blocks, see `CollisionChannel::mask`
+    pub fn collision_channels(&self) -> u32 {
+        self.collision.channels
+    }
+
+    /// Channels that are neither in `channels` nor in the overlap channels are ignored. The new
+    /// channels are sent to unreal in `CoreStage::PostUpdate`, e.g. `set_collision_channels(0)`
+    /// turns off all blocking collisions.
+    pub fn set_collision_channels(&mut self, channels: u32) {
+        self.collision.channels = channels;
+        self.collision_changed = true;
+    }
+
+    pub fn set_collision_response(&mut self, channel: CollisionChannel, blocks: bool) {
+        if blocks {
+            self.set_collision_channels(self.collision.channels | channel.mask());
+        } else {
+            self.set_collision_channels(self.collision.channels & !channel.mask());
+        }
+    }
+
+    pub fn blocks(&self, channel: CollisionChannel) -> bool {
+        self.collision.channels & channel.mask() != 0
+    }
+
+    /// A bit for every `CollisionChannel` that this primitive overlaps. Channels that are also
+    /// blocked count as blocked.
+    pub fn overlap_channels(&self) -> u32 {
+        self.collision.overlap_channels
+    }
+
+    /// Like `set_collision_channels`, but for the channels that generate overlaps
+    pub fn set_overlap_channels(&mut self, channels: u32) {
+        self.collision.overlap_channels = channels;
+        self.collision_changed = true;
+    }
+
+    pub fn set_overlap_response(&mut self, channel: CollisionChannel, overlaps: bool) {
+        if overlaps {
+            self.set_overlap_channels(self.collision.overlap_channels | channel.mask());
+        } else {
+            self.set_overlap_channels(self.collision.overlap_channels & !channel.mask());
+        }
+    }
+
+    /// False for channels that are blocked
+    pub fn overlaps(&self, channel: CollisionChannel) -> bool {
+        !self.blocks(channel) && self.collision.overlap_channels & channel.mask() != 0
+    }
+
+    pub fn object_type(&self) -> CollisionChannel {
+        self.collision.object_type
+    }
+
+    pub fn set_object_type(&mut self, object_type: CollisionChannel) {
+        self.collision.object_type = object_type;
+        self.collision_changed = true;
+    }
+
+    pub fn velocity(&self) -> Vec3 {
+        self.velocity
+    }
+
+    /// The new velocity is sent to unreal in `CoreStage::PostUpdate`
+    pub fn set_velocity(&mut self, velocity: Vec3) {
+        self.velocity = velocity;
+        self.velocity_changed = true;
     }
 
     pub fn add_impulse(&mut self, impulse: Vec3) {
@@ -237,4 +404,184 @@ impl PhysicsComponent {
             (bindings().physics_fns.add_force)(self.ptr.ptr, force.into());
         }
     }
+
+    /// In radians per second
+    pub fn angular_velocity(&self) -> Vec3 {
+        self.angular_velocity
+    }
+
+    pub fn set_angular_velocity(&mut self, angular_velocity: Vec3) {
+        self.angular_velocity = angular_velocity;
+        self.angular_velocity_changed = true;
+    }
+
+    pub fn add_torque(&mut self, torque: Vec3) {
+        unsafe {
+            (bindings().physics_fns.add_torque)(self.ptr.ptr, torque.into());
+        }
+    }
+
+    pub fn add_angular_impulse(&mut self, impulse: Vec3) {
+        unsafe {
+            (bindings().physics_fns.add_angular_impulse)(self.ptr.ptr, impulse.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Call};
+
+    fn component() -> (PhysicsComponent, *mut std::ffi::c_void) {
+        let (_, primitive) = testing::spawn_physics_actor();
+        testing::with(|unreal| {
+            let mock = unreal.primitive_mut(primitive);
+            mock.velocity = Vec3::X;
+            mock.angular_velocity = Vec3::new(0.0, 0.0, 2.0);
+        });
+        (
+            PhysicsComponent::new(UnrealPtr::from_raw(primitive)),
+            primitive,
+        )
+    }
+
+    #[test]
+    fn angular_state_is_downloaded() {
+        testing::install();
+        let (mut physics, primitive) = component();
+        assert!(physics.is_simulating);
+        assert_eq!(physics.angular_velocity(), Vec3::new(0.0, 0.0, 2.0));
+
+        testing::with(|unreal| unreal.primitive_mut(primitive).angular_velocity = Vec3::Y);
+        physics.download_state();
+        assert_eq!(physics.angular_velocity(), Vec3::Y);
+    }
+
+    #[test]
+    fn torque_and_impulses_are_forwarded() {
+        testing::install();
+        let (mut physics, primitive) = component();
+        physics.add_torque(Vec3::Z);
+        physics.add_angular_impulse(Vec3::X);
+        physics.add_impulse(Vec3::Y);
+        physics.add_force(Vec3::ONE);
+        assert_eq!(
+            testing::calls(),
+            vec![
+                Call::AddTorque {
+                    primitive,
+                    torque: Vec3::Z,
+                },
+                Call::AddAngularImpulse {
+                    primitive,
+                    impulse: Vec3::X,
+                },
+                Call::AddImpulse {
+                    primitive,
+                    impulse: Vec3::Y,
+                },
+                Call::AddForce {
+                    primitive,
+                    force: Vec3::ONE,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn angular_velocity_is_uploaded_once() {
+        testing::install();
+        let (mut physics, primitive) = component();
+        physics.set_angular_velocity(Vec3::X);
+        physics.upload_state();
+        physics.upload_state();
+        assert_eq!(
+            testing::calls(),
+            vec![Call::SetAngularVelocity {
+                primitive,
+                angular_velocity: Vec3::X,
+            }]
+        );
+    }
+
+    #[test]
+    fn collision_response_round_trips() {
+        testing::install();
+        let (mut physics, primitive) = component();
+        let channels = CollisionChannel::WorldStatic.mask() | CollisionChannel::Pawn.mask();
+        physics.set_collision_channels(channels);
+        physics.set_object_type(CollisionChannel::PhysicsBody);
+        physics.set_collision_response(CollisionChannel::Camera, true);
+        physics.set_collision_response(CollisionChannel::Pawn, false);
+        physics.set_overlap_response(CollisionChannel::Pawn, true);
+        let expected = ffi::CollisionResponse {
+            object_type: CollisionChannel::PhysicsBody,
+            channels: CollisionChannel::WorldStatic.mask() | CollisionChannel::Camera.mask(),
+            overlap_channels: CollisionChannel::Pawn.mask(),
+        };
+
+        testing::take_calls();
+        physics.upload_state();
+        physics.upload_state();
+        let responses: Vec<_> = testing::take_calls()
+            .into_iter()
+            .filter(|call| matches!(call, Call::SetCollisionResponse { .. }))
+            .collect();
+        assert_eq!(
+            responses,
+            vec![Call::SetCollisionResponse {
+                primitive,
+                response: expected
+            }]
+        );
+
+        // A fresh component reads the response back from unreal
+        let other = PhysicsComponent::new(UnrealPtr::from_raw(primitive));
+        assert_eq!(other.collision_channels(), expected.channels);
+        assert_eq!(other.object_type(), CollisionChannel::PhysicsBody);
+        assert!(other.blocks(CollisionChannel::Camera));
+        assert!(!other.blocks(CollisionChannel::Pawn));
+        assert!(other.overlaps(CollisionChannel::Pawn));
+        assert!(!other.overlaps(CollisionChannel::Camera));
+        assert_eq!(other.overlap_channels(), CollisionChannel::Pawn.mask());
+    }
+
+    #[test]
+    fn set_simulating_toggles_unreal_and_the_flag() {
+        testing::install();
+        let (mut physics, primitive) = component();
+        testing::take_calls();
+
+        physics.set_simulating(false);
+        assert!(!physics.is_active());
+        assert_eq!(physics.velocity(), Vec3::ZERO);
+        assert!(!testing::with(|unreal| unreal
+            .primitive_mut(primitive)
+            .simulating));
+        // Already off, nothing to send
+        physics.set_simulating(false);
+        assert_eq!(
+            testing::take_calls(),
+            vec![Call::SetSimulatePhysics {
+                primitive,
+                simulate: false,
+            }]
+        );
+
+        physics.set_simulating(true);
+        assert!(physics.is_active());
+        // Turning it back on downloads the velocity again
+        assert_eq!(physics.velocity(), Vec3::X);
+        assert_eq!(
+            testing::take_calls()
+                .into_iter()
+                .filter(|call| matches!(call, Call::SetSimulatePhysics { .. }))
+                .collect::<Vec<_>>(),
+            vec![Call::SetSimulatePhysics {
+                primitive,
+                simulate: true,
+            }]
+        );
+    }
 }
@@ -0,0 +1,274 @@
+use std::marker::PhantomData;
+
+use bevy_ecs::{component::Component, entity::Entity, world::World};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use unreal_reflect::Uuid;
+
+use crate::{
+    api::{entity_for_actor, UnrealApi},
+    core::ActorPtr,
+    ffi::AActorOpaque,
+    module::ReflectionRegistry,
+};
+
+/// Components that can be written to and restored from a save game. Components holding unreal
+/// pointers, like the `ActorComponent` or `PhysicsComponent`, are never serializable because
+/// those addresses are meaningless in the next session.
+pub trait Serializable: Send + Sync {
+    fn serialize(&self, world: &World, entity: Entity) -> Option<Vec<u8>>;
+    fn deserialize(&self, world: &mut World, entity: Entity, bytes: &[u8]) -> bincode::Result<()>;
+}
+
+pub(crate) struct SerializableComponent<T>(PhantomData<fn() -> T>);
+
+impl<T> SerializableComponent<T> {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Serializable for SerializableComponent<T>
+where
+    T: Component + Serialize + DeserializeOwned,
+{
+    fn serialize(&self, world: &World, entity: Entity) -> Option<Vec<u8>> {
+        let component = world.get::<T>(entity)?;
+        match bincode::serialize(component) {
+            Ok(bytes) => Some(bytes),
+            Err(err) => {
+                log::error!(
+                    "Unable to serialize {}: {}",
+                    std::any::type_name::<T>(),
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    fn deserialize(&self, world: &mut World, entity: Entity, bytes: &[u8]) -> bincode::Result<()> {
+        let component: T = bincode::deserialize(bytes)?;
+        world.entity_mut(entity).insert(component);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedComponent {
+    uuid: Uuid,
+    data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedEntity {
+    components: Vec<SavedComponent>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedWorld {
+    entities: Vec<SavedEntity>,
+}
+
+fn serialize_components(
+    world: &World,
+    registry: &ReflectionRegistry,
+    entity: Entity,
+) -> Vec<SavedComponent> {
+    registry
+        .serializable
+        .iter()
+        .filter_map(|(uuid, serializable)| {
+            Some(SavedComponent {
+                uuid: *uuid,
+                data: serializable.serialize(world, entity)?,
+            })
+        })
+        .collect()
+}
+
+fn deserialize_components(
+    world: &mut World,
+    registry: &ReflectionRegistry,
+    entity: Entity,
+    components: Vec<SavedComponent>,
+) -> bincode::Result<()> {
+    for component in components {
+        match registry.serializable.get(&component.uuid) {
+            Some(serializable) => serializable.deserialize(world, entity, &component.data)?,
+            None => log::warn!("Skipping unknown component {}", component.uuid),
+        }
+    }
+    Ok(())
+}
+
+/// Serializes every entity that has at least one `Serializable` component. All other components
+/// are ignored.
+pub fn serialize_world(world: &World, registry: &ReflectionRegistry) -> Vec<u8> {
+    let mut entities = Vec::new();
+    for archetype in world.archetypes().iter() {
+        for &entity in archetype.entities() {
+            let components = serialize_components(world, registry, entity);
+            if !components.is_empty() {
+                entities.push(SavedEntity { components });
+            }
+        }
+    }
+    bincode::serialize(&SavedWorld { entities }).expect("Unable to serialize the world")
+}
+
+/// Spawns a new entity for every entity in `bytes`. Entity ids are not preserved, and components
+/// that are no longer registered as `Serializable` are skipped.
+pub fn deserialize_world(
+    world: &mut World,
+    bytes: &[u8],
+    registry: &ReflectionRegistry,
+) -> bincode::Result<Vec<Entity>> {
+    let saved: SavedWorld = bincode::deserialize(bytes)?;
+    let mut spawned = Vec::with_capacity(saved.entities.len());
+    for saved_entity in saved.entities {
+        let entity = world.spawn().id();
+        deserialize_components(world, registry, entity, saved_entity.components)?;
+        spawned.push(entity);
+    }
+    Ok(spawned)
+}
+
+/// The `Serializable` components of every entity with an actor, keyed by the address of that
+/// actor. Actors outlive a hot reload of the module, so the address is enough to find the same
+/// entity again after the new module registered its actors.
+#[derive(Default, Serialize, Deserialize)]
+pub struct ReloadState {
+    actors: Vec<(u64, SavedEntity)>,
+}
+
+impl ReloadState {
+    pub fn capture(world: &World, registry: &ReflectionRegistry) -> Self {
+        let actors = world
+            .get_resource::<UnrealApi>()
+            .map(|api| {
+                api.entity_to_actor
+                    .iter()
+                    .map(|(&entity, actor)| {
+                        let components = serialize_components(world, registry, entity);
+                        (actor.0 as u64, SavedEntity { components })
+                    })
+                    .filter(|(_, saved)| !saved.components.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { actors }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actors.is_empty()
+    }
+
+    pub fn actors(&self) -> impl Iterator<Item = ActorPtr> + '_ {
+        self.actors
+            .iter()
+            .map(|(actor, _)| ActorPtr(*actor as usize as *mut AActorOpaque))
+    }
+
+    /// Drops the state of all actors for which `f` returns false
+    pub fn retain_actors(&mut self, mut f: impl FnMut(ActorPtr) -> bool) {
+        self.actors
+            .retain(|(actor, _)| f(ActorPtr(*actor as usize as *mut AActorOpaque)));
+    }
+
+    /// Inserts the saved components into the entities that are currently registered for the
+    /// saved actors. Returns the entities that were restored, actors without an entity are
+    /// skipped.
+    pub fn restore(
+        self,
+        world: &mut World,
+        registry: &ReflectionRegistry,
+    ) -> bincode::Result<Vec<Entity>> {
+        let mut restored = Vec::with_capacity(self.actors.len());
+        for (actor, saved) in self.actors {
+            let actor = actor as usize as *mut AActorOpaque;
+            if let Some(entity) = entity_for_actor(world, actor) {
+                deserialize_components(world, registry, entity, saved.components)?;
+                restored.push(entity);
+            }
+        }
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::{ActorComponent, PlayerInputComponent, TransformComponent},
+        input::PlayerIndex,
+        math::{Quat, Vec3},
+    };
+
+    fn registry() -> ReflectionRegistry {
+        let mut registry = ReflectionRegistry::default();
+        registry.register_serializable::<TransformComponent>();
+        registry.register_serializable::<PlayerInputComponent>();
+        registry
+    }
+
+    #[test]
+    fn world_round_trip() {
+        let registry = registry();
+        let mut world = World::new();
+        world.spawn().insert_bundle((
+            TransformComponent {
+                position: Vec3::new(1.0, 2.0, 3.0),
+                rotation: Quat::from_rotation_z(1.0),
+                scale: Vec3::ONE,
+            },
+            PlayerInputComponent {
+                direction: Vec3::X,
+                player_index: PlayerIndex(1),
+            },
+            // Never saved, the pointer is meaningless in the next session
+            ActorComponent::default(),
+        ));
+        world.spawn().insert(TransformComponent {
+            position: Vec3::splat(5.0),
+            ..Default::default()
+        });
+        // Nothing serializable, so it is not part of the save
+        world.spawn().insert(ActorComponent::default());
+
+        let bytes = serialize_world(&world, &registry);
+        world.clear_entities();
+        let mut restored = deserialize_world(&mut world, &bytes, &registry).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(world.entities().len(), 2);
+
+        restored.sort_by_key(|&entity| world.get::<PlayerInputComponent>(entity).is_none());
+        let player = world.entity(restored[0]);
+        let transform = player.get::<TransformComponent>().unwrap();
+        assert_eq!(transform.position, Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(transform.rotation, Quat::from_rotation_z(1.0));
+        let input = player.get::<PlayerInputComponent>().unwrap();
+        assert_eq!(input.direction, Vec3::X);
+        assert_eq!(input.player_index, PlayerIndex(1));
+        assert!(player.get::<ActorComponent>().is_none());
+
+        let other = world.get::<TransformComponent>(restored[1]).unwrap();
+        assert_eq!(other.position, Vec3::splat(5.0));
+    }
+
+    #[test]
+    fn unknown_components_are_skipped() {
+        let mut world = World::new();
+        world.spawn().insert_bundle((
+            TransformComponent::default(),
+            PlayerInputComponent::default(),
+        ));
+        let bytes = serialize_world(&world, &registry());
+
+        let mut registry = ReflectionRegistry::default();
+        registry.register_serializable::<TransformComponent>();
+        let mut world = World::new();
+        let restored = deserialize_world(&mut world, &bytes, &registry).unwrap();
+        assert!(world.get::<TransformComponent>(restored[0]).is_some());
+        assert!(world.get::<PlayerInputComponent>(restored[0]).is_none());
+    }
+}
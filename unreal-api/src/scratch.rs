@@ -0,0 +1,59 @@
+use crate::{core::ActorPtr, ffi};
+
+/// Vectors that are handed out empty and keep their capacity when they are given back
+pub struct ScratchPool<T> {
+    free: Vec<Vec<T>>,
+}
+
+impl<T> Default for ScratchPool<T> {
+    fn default() -> Self {
+        Self { free: Vec::new() }
+    }
+}
+
+impl<T> ScratchPool<T> {
+    /// An empty vector, which has the capacity of a previously returned one if there is any
+    pub fn take(&mut self) -> Vec<T> {
+        self.free.pop().unwrap_or_default()
+    }
+
+    /// Clears `buffer` and keeps its allocation for the next `take`
+    pub fn give_back(&mut self, mut buffer: Vec<T>) {
+        buffer.clear();
+        self.free.push(buffer);
+    }
+}
+
+/// Reusable buffers for exchanging arrays with unreal, so hot paths do not allocate every frame.
+/// Buffers that are taken and never given back are simply freed. Used by the transform download
+/// and the collision event polling in `CoreStage::PreUpdate`.
+#[derive(Default)]
+pub struct ScratchBuffers {
+    pub actors: ScratchPool<ActorPtr>,
+    pub spatial_data: ScratchPool<ffi::SpatialData>,
+    pub collision_events: ScratchPool<ffi::CollisionEvent>,
+}
+
+// The actor pointers in the collision events are only passed back to unreal, like `ActorPtr`
+unsafe impl Send for ScratchBuffers {}
+unsafe impl Sync for ScratchBuffers {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_back_buffers_keep_their_capacity() {
+        let mut pool = ScratchPool::<u32>::default();
+        let mut buffer = pool.take();
+        buffer.extend(0..100);
+        let capacity = buffer.capacity();
+        pool.give_back(buffer);
+
+        let buffer = pool.take();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.capacity(), capacity);
+        // Nothing was given back, so this one is fresh
+        assert_eq!(pool.take().capacity(), 0);
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use bevy_ecs::{entity::Entity, world::World};
+use serde::{Deserialize, Serialize};
+use unreal_reflect::{registry::ReflectValue, Uuid};
+
+use crate::{
+    math::{Quat, QuatExt, Vec3, Vec3Ext},
+    module::ReflectionRegistry,
+    net::{NetId, NetIdRegistry},
+};
+
+/// Fields whose value changed by less than this are left out of a `Patch`
+pub const SNAPSHOT_EPSILON: f32 = 1e-4;
+
+/// The reflected field types that can be sent over the network. Entities and asset pointers only
+/// have a meaning on the machine that captured them and are never part of a snapshot.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SnapshotValue {
+    Float(f32),
+    Vector3(Vec3),
+    Bool(bool),
+    Quat(Quat),
+}
+
+impl SnapshotValue {
+    fn from_reflect(value: ReflectValue) -> Option<Self> {
+        match value {
+            ReflectValue::Float(value) => Some(SnapshotValue::Float(value)),
+            ReflectValue::Vector3(value) => Some(SnapshotValue::Vector3(value)),
+            ReflectValue::Bool(value) => Some(SnapshotValue::Bool(value)),
+            ReflectValue::Quat(value) => Some(SnapshotValue::Quat(value)),
+            _ => None,
+        }
+    }
+
+    fn into_reflect(self) -> ReflectValue {
+        match self {
+            SnapshotValue::Float(value) => ReflectValue::Float(value),
+            SnapshotValue::Vector3(value) => ReflectValue::Vector3(value),
+            SnapshotValue::Bool(value) => ReflectValue::Bool(value),
+            SnapshotValue::Quat(value) => ReflectValue::Quat(value),
+        }
+    }
+
+    fn approx_eq(self, other: Self, epsilon: f32) -> bool {
+        match (self, other) {
+            (SnapshotValue::Float(a), SnapshotValue::Float(b)) => (a - b).abs() <= epsilon,
+            (SnapshotValue::Vector3(a), SnapshotValue::Vector3(b)) => a.approx_eq(b, epsilon),
+            (SnapshotValue::Bool(a), SnapshotValue::Bool(b)) => a == b,
+            (SnapshotValue::Quat(a), SnapshotValue::Quat(b)) => a.approx_eq(b, epsilon),
+            _ => false,
+        }
+    }
+}
+
+/// Identifies a reflected field of a component. Field indices are only stable between builds of
+/// the same module, so snapshots and patches must not outlive a hot reload.
+type FieldKey = (u64, Uuid, u32);
+
+/// The reflected fields of every registered component on every entity with a `NetId`, keyed by
+/// the `NetId` because entities differ between machines.
+#[derive(Default, Debug, Clone)]
+pub struct Snapshot {
+    fields: HashMap<FieldKey, SnapshotValue>,
+}
+
+impl Snapshot {
+    pub fn capture(world: &World, registry: &ReflectionRegistry) -> Self {
+        let mut fields = HashMap::new();
+        for archetype in world.archetypes().iter() {
+            for &entity in archetype.entities() {
+                if let Some(&NetId(net_id)) = world.get::<NetId>(entity) {
+                    capture_entity(world, registry, entity, net_id, &mut fields);
+                }
+            }
+        }
+        Self { fields }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+fn capture_entity(
+    world: &World,
+    registry: &ReflectionRegistry,
+    entity: Entity,
+    net_id: u64,
+    fields: &mut HashMap<FieldKey, SnapshotValue>,
+) {
+    for (uuid, reflect) in &registry.reflect {
+        if !reflect.has_component(world, entity) {
+            continue;
+        }
+        for idx in 0..reflect.number_of_fields() {
+            let value = reflect
+                .get_field_value(world, entity, idx)
+                .and_then(SnapshotValue::from_reflect);
+            if let Some(value) = value {
+                fields.insert((net_id, *uuid, idx), value);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub net_id: u64,
+    pub component: Uuid,
+    pub field: u32,
+    pub value: SnapshotValue,
+}
+
+/// The fields that changed between two snapshots. Can be sent with `bincode`. Entities or
+/// components that were removed are not part of a patch.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Patch {
+    pub changes: Vec<FieldChange>,
+}
+
+impl Patch {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Every field of `new` that is missing in `old` or differs by more than `SNAPSHOT_EPSILON`
+pub fn diff_snapshot(old: &Snapshot, new: &Snapshot) -> Patch {
+    let changes = new
+        .fields
+        .iter()
+        .filter(|(key, value)| {
+            !old.fields
+                .get(key)
+                .is_some_and(|old| old.approx_eq(**value, SNAPSHOT_EPSILON))
+        })
+        .map(|(&(net_id, component, field), &value)| FieldChange {
+            net_id,
+            component,
+            field,
+            value,
+        })
+        .collect();
+    Patch { changes }
+}
+
+/// Writes the changes into the entities with the same `NetId`. Changes for unknown entities,
+/// components the entity does not have or fields of the wrong type are skipped. Returns the
+/// number of fields that were written.
+pub fn apply_patch(world: &mut World, registry: &ReflectionRegistry, patch: &Patch) -> usize {
+    let mut applied = 0;
+    for change in &patch.changes {
+        let entity = world
+            .get_resource::<NetIdRegistry>()
+            .and_then(|net_ids| net_ids.entity(NetId(change.net_id)));
+        let (Some(entity), Some(reflect)) = (entity, registry.reflect.get(&change.component))
+        else {
+            continue;
+        };
+        if reflect.set_field_value(world, entity, change.field, change.value.into_reflect()) {
+            applied += 1;
+        }
+    }
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec_eq, core::TransformComponent};
+    use unreal_reflect::TypeUuid;
+
+    fn replicated_world(registry: &ReflectionRegistry) -> (World, [Entity; 2]) {
+        let mut world = World::new();
+        let mut net_ids = NetIdRegistry::default();
+        let entities = [1, 2].map(|net_id| {
+            let entity = world
+                .spawn()
+                .insert_bundle((
+                    NetId(net_id),
+                    TransformComponent {
+                        position: Vec3::splat(net_id as f32),
+                        ..Default::default()
+                    },
+                ))
+                .id();
+            net_ids.register(NetId(net_id), entity);
+            entity
+        });
+        world.insert_resource(net_ids);
+        assert_eq!(Snapshot::capture(&world, registry).fields.len(), 6);
+        (world, entities)
+    }
+
+    #[test]
+    fn patch_only_moves_the_moved_entity() {
+        let mut registry = ReflectionRegistry::default();
+        registry.register::<TransformComponent>();
+        let (mut server, [moved, _]) = replicated_world(&registry);
+        let (mut client, [client_moved, client_still]) = replicated_world(&registry);
+
+        let old = Snapshot::capture(&server, &registry);
+        assert!(diff_snapshot(&old, &old).is_empty());
+        let target = Vec3::new(10.0, 20.0, 30.0);
+        server
+            .get_mut::<TransformComponent>(moved)
+            .unwrap()
+            .position = target;
+        let new = Snapshot::capture(&server, &registry);
+
+        let patch = diff_snapshot(&old, &new);
+        assert_eq!(patch.changes.len(), 1);
+        let change = &patch.changes[0];
+        assert_eq!(change.net_id, 1);
+        assert_eq!(change.component, TransformComponent::TYPE_UUID);
+        assert_eq!(change.value, SnapshotValue::Vector3(target));
+
+        let bytes = bincode::serialize(&patch).unwrap();
+        let patch: Patch = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(apply_patch(&mut client, &registry, &patch), 1);
+
+        let moved = client.get::<TransformComponent>(client_moved).unwrap();
+        assert_vec_eq!(moved.position, target);
+        assert_eq!(moved.rotation, Quat::IDENTITY);
+        assert_eq!(moved.scale, Vec3::ONE);
+        let still = client.get::<TransformComponent>(client_still).unwrap();
+        assert_vec_eq!(still.position, Vec3::splat(2.0));
+        assert!(diff_snapshot(&new, &Snapshot::capture(&client, &registry)).is_empty());
+    }
+
+    #[test]
+    fn changes_below_the_epsilon_are_skipped() {
+        let mut registry = ReflectionRegistry::default();
+        registry.register::<TransformComponent>();
+        let (mut world, [entity, _]) = replicated_world(&registry);
+        let old = Snapshot::capture(&world, &registry);
+        world
+            .get_mut::<TransformComponent>(entity)
+            .unwrap()
+            .position
+            .x += SNAPSHOT_EPSILON / 2.0;
+        assert!(diff_snapshot(&old, &Snapshot::capture(&world, &registry)).is_empty());
+    }
+}
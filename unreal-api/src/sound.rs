@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use glam::{Quat, Vec3};
 use unreal_reflect::registry::USound;
 
@@ -10,6 +12,10 @@ pub fn play_sound_at_location(
     rotation: Quat,
     settings: &SoundSettings,
 ) {
+    if sound.ptr.is_null() {
+        log::warn!("Tried to play a sound that is not set");
+        return;
+    }
     unsafe {
         (bindings().sound_fns.play_sound_at_location)(
             sound.ptr,
@@ -19,3 +25,89 @@ pub fn play_sound_at_location(
         );
     }
 }
+
+/// Plays a sound that is not spatialized, for example for UI feedback
+pub fn play_sound_2d(sound: USound, settings: &SoundSettings) {
+    if sound.ptr.is_null() {
+        log::warn!("Tried to play a sound that is not set");
+        return;
+    }
+    unsafe {
+        (bindings().sound_fns.play_sound_2d)(sound.ptr, settings);
+    }
+}
+
+/// Sound assets loaded by path, e.g. `"/Game/Sounds/Jump.Jump"`. Every path is only looked up
+/// once, including paths that don't exist.
+#[derive(Default, Debug)]
+pub struct Sounds {
+    loaded: HashMap<String, Option<USound>>,
+}
+
+impl Sounds {
+    pub fn get(&mut self, path: &str) -> Option<USound> {
+        if let Some(sound) = self.loaded.get(path) {
+            return *sound;
+        }
+        let ptr = unsafe { (bindings().sound_fns.load_sound)(path.into()) };
+        let sound = if ptr.is_null() {
+            log::error!("Unable to load sound {}", path);
+            None
+        } else {
+            Some(USound { ptr })
+        };
+        self.loaded.insert(path.to_string(), sound);
+        sound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Call};
+
+    #[test]
+    fn sounds_are_forwarded() {
+        testing::install();
+        let jump = testing::with(|unreal| {
+            let ptr = unreal.new_ptr();
+            unreal.sounds.insert("/Game/Jump.Jump".into(), ptr);
+            ptr
+        });
+        let mut sounds = Sounds::default();
+        let sound = sounds.get("/Game/Jump.Jump").unwrap();
+        assert!(sounds.get("/Game/Missing.Missing").is_none());
+
+        let settings = SoundSettings {
+            volume: 0.5,
+            pitch: 1.5,
+        };
+        let location = Vec3::new(1.0, 2.0, 3.0);
+        let rotation = Quat::from_rotation_z(1.0);
+        play_sound_at_location(sound, location, rotation, &settings);
+        play_sound_2d(sound, &settings);
+        // Not set, nothing is played
+        let unset = USound {
+            ptr: std::ptr::null_mut(),
+        };
+        play_sound_2d(unset, &settings);
+
+        assert_eq!(
+            testing::take_calls(),
+            vec![
+                Call::PlaySoundAtLocation {
+                    sound: jump,
+                    location,
+                    rotation,
+                    volume: 0.5,
+                    pitch: 1.5,
+                },
+                Call::PlaySound2d {
+                    sound: jump,
+                    volume: 0.5,
+                    pitch: 1.5,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use bevy_ecs::{
+    entity::Entity,
+    system::{Query, ResMut},
+};
+use glam::{IVec3, Vec3};
+
+use crate::core::TransformComponent;
+
+/// Buckets the positions of all entities with a `TransformComponent` into cubic cells, so that
+/// radius queries only look at the entities in nearby cells. Not inserted by default, add it with
+/// `Module::insert_resource` to opt in. It is rebuilt at the end of every frame in
+/// `CoreStage::PostUpdate`, so during a frame it contains the positions of the previous frame.
+#[derive(Debug)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<(Entity, Vec3)>>,
+}
+
+impl SpatialHash {
+    /// `cell_size` should be around the radius of the typical query
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "The cell size must be positive");
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn clear(&mut self) {
+        // Keeps the cells that were occupied since the last clear, most of them are going to be
+        // filled again by the next rebuild
+        self.cells.retain(|_, entities| {
+            let occupied = !entities.is_empty();
+            entities.clear();
+            occupied
+        });
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec3) {
+        let cell = self.cell(position);
+        self.cells.entry(cell).or_default().push((entity, position));
+    }
+
+    /// All entities within `radius` of `center`
+    pub fn query_radius(&self, center: Vec3, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let radius_squared = radius * radius;
+        let min = self.cell(center - Vec3::splat(radius));
+        let max = self.cell(center + Vec3::splat(radius));
+        let cells_in_range = (max - min + IVec3::ONE).as_vec3();
+        // Huge radii would loop over more empty cells than there are cells in total
+        let candidates: Box<dyn Iterator<Item = &Vec<(Entity, Vec3)>>> =
+            if cells_in_range.x * cells_in_range.y * cells_in_range.z > self.cells.len() as f32 {
+                Box::new(self.cells.values())
+            } else {
+                Box::new(
+                    (min.x..=max.x)
+                        .flat_map(move |x| {
+                            (min.y..=max.y).flat_map(move |y| {
+                                (min.z..=max.z).map(move |z| IVec3::new(x, y, z))
+                            })
+                        })
+                        .filter_map(|cell| self.cells.get(&cell)),
+                )
+            };
+        candidates
+            .flatten()
+            .filter(move |(_, position)| position.distance_squared(center) <= radius_squared)
+            .map(|&(entity, _)| entity)
+    }
+
+    fn cell(&self, position: Vec3) -> IVec3 {
+        (position / self.cell_size).floor().as_ivec3()
+    }
+}
+
+pub(crate) fn rebuild_spatial_hash(
+    spatial_hash: Option<ResMut<SpatialHash>>,
+    query: Query<(Entity, &TransformComponent)>,
+) {
+    if let Some(mut spatial_hash) = spatial_hash {
+        spatial_hash.clear();
+        for (entity, transform) in query.iter() {
+            spatial_hash.insert(entity, transform.position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic positions in `[-extent, extent]^3`
+    fn positions(count: usize, extent: f32) -> Vec<Vec3> {
+        let mut seed = 0x2545_f491_u32;
+        let mut next = move || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32 * 2.0 * extent - extent
+        };
+        (0..count)
+            .map(|_| Vec3::new(next(), next(), next()))
+            .collect()
+    }
+
+    #[test]
+    fn query_radius_matches_brute_force() {
+        let points = positions(500, 100.0);
+        let mut spatial_hash = SpatialHash::new(10.0);
+        for (i, &position) in points.iter().enumerate() {
+            spatial_hash.insert(Entity::from_raw(i as u32), position);
+        }
+
+        for (center, radius) in [
+            (Vec3::ZERO, 15.0),
+            (Vec3::new(-42.0, 7.5, 90.0), 25.0),
+            (Vec3::new(10.0, 10.0, 10.0), 0.0),
+            (Vec3::new(-100.0, -100.0, -100.0), 40.0),
+            // Covers more cells than are occupied, falls back to visiting all of them
+            (Vec3::ZERO, 1000.0),
+        ] {
+            let mut found: Vec<_> = spatial_hash.query_radius(center, radius).collect();
+            found.sort();
+            let expected: Vec<_> = points
+                .iter()
+                .enumerate()
+                .filter(|(_, position)| position.distance(center) <= radius)
+                .map(|(i, _)| Entity::from_raw(i as u32))
+                .collect();
+            assert_eq!(found, expected, "center {} radius {}", center, radius);
+        }
+    }
+
+    #[test]
+    fn clear_empties_all_cells() {
+        let mut spatial_hash = SpatialHash::new(1.0);
+        spatial_hash.insert(Entity::from_raw(0), Vec3::ZERO);
+        spatial_hash.insert(Entity::from_raw(1), Vec3::splat(5.0));
+        spatial_hash.clear();
+        assert_eq!(spatial_hash.query_radius(Vec3::ZERO, 100.0).count(), 0);
+
+        spatial_hash.insert(Entity::from_raw(2), Vec3::splat(-3.5));
+        assert_eq!(
+            spatial_hash
+                .query_radius(Vec3::splat(-3.0), 1.0)
+                .collect::<Vec<_>>(),
+            vec![Entity::from_raw(2)]
+        );
+    }
+}
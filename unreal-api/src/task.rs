@@ -0,0 +1,198 @@
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        mpsc::{channel, Sender},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Background threads for work that would stall the game thread, like pathfinding. Tasks never
+/// touch the `World`, systems poll their `Task` in later frames and apply the result themselves,
+/// e.g. with `Commands`.
+pub struct TaskPool {
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl Default for TaskPool {
+    /// One thread less than there are cores, so the game thread keeps a core for itself
+    fn default() -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(|count| count.get().saturating_sub(1))
+            .unwrap_or(1);
+        Self::new(threads)
+    }
+}
+
+impl TaskPool {
+    pub fn new(threads: usize) -> Self {
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..threads.max(1))
+            .map(|idx| {
+                let receiver = receiver.clone();
+                std::thread::Builder::new()
+                    .name(format!("unreal-rust task {}", idx))
+                    .spawn(move || loop {
+                        let job = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => return,
+                        };
+                        match job {
+                            Ok(job) => job(),
+                            // The pool was dropped
+                            Err(_) => return,
+                        }
+                    })
+                    .expect("Unable to spawn a task thread")
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Runs `f` on a background thread. A panic inside of `f` is reported by `Task::poll`
+    /// instead of taking down the game.
+    pub fn spawn<T, F>(&self, f: F) -> Task<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let result = Arc::new(Mutex::new(None));
+        let task = Task {
+            result: result.clone(),
+        };
+        let job = Box::new(move || {
+            let value = catch_unwind(AssertUnwindSafe(f)).map_err(|_| TaskPanicked);
+            if let Ok(mut result) = result.lock() {
+                *result = Some(value);
+            }
+        });
+        if let Some(sender) = &self.sender {
+            // Can only fail if every worker is gone, in which case the task never finishes
+            let _ = sender.send(job);
+        }
+        task
+    }
+}
+
+impl Drop for TaskPool {
+    /// Waits for the running tasks, the code of a hot reloaded module must not outlive it
+    fn drop(&mut self) {
+        self.sender = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// The task panicked before it returned a value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TaskPanicked;
+
+impl std::fmt::Display for TaskPanicked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The task panicked")
+    }
+}
+
+impl std::error::Error for TaskPanicked {}
+
+/// The result of `TaskPool::spawn`. Can be stored in a component or resource and polled every
+/// frame.
+pub struct Task<T> {
+    result: Arc<Mutex<Option<Result<T, TaskPanicked>>>>,
+}
+
+impl<T> Task<T> {
+    /// Returns the result once, `None` while the task is still running and after the result was
+    /// taken.
+    pub fn poll(&mut self) -> Option<Result<T, TaskPanicked>> {
+        self.result.lock().ok()?.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::prelude::*;
+    use std::time::Duration;
+
+    /// Polls until the task is done, the worker may take a moment to pick it up
+    fn wait_for<T>(task: &mut Task<T>) -> Result<T, TaskPanicked> {
+        for _ in 0..1000 {
+            if let Some(result) = task.poll() {
+                return result;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        panic!("The task did not finish");
+    }
+
+    #[test]
+    fn result_is_available_once_the_task_finished() {
+        let pool = TaskPool::new(1);
+        let (release, gate) = channel::<()>();
+        let mut task = pool.spawn(move || {
+            gate.recv().unwrap();
+            6 * 7
+        });
+        for _ in 0..3 {
+            assert!(task.poll().is_none());
+        }
+
+        release.send(()).unwrap();
+        assert_eq!(wait_for(&mut task), Ok(42));
+        // The result is only handed out once
+        assert!(task.poll().is_none());
+    }
+
+    #[test]
+    fn panics_are_reported() {
+        let pool = TaskPool::new(1);
+        let mut task = pool.spawn(|| -> u32 { panic!("pathfinding failed") });
+        assert_eq!(wait_for(&mut task), Err(TaskPanicked));
+    }
+
+    #[derive(Component)]
+    struct PathRequest(Task<Vec<u32>>);
+
+    #[derive(Component, Debug, PartialEq)]
+    struct Path(Vec<u32>);
+
+    fn apply_paths(mut commands: Commands, mut requests: Query<(Entity, &mut PathRequest)>) {
+        for (entity, mut request) in requests.iter_mut() {
+            if let Some(Ok(path)) = request.0.poll() {
+                commands
+                    .entity(entity)
+                    .remove::<PathRequest>()
+                    .insert(Path(path));
+            }
+        }
+    }
+
+    #[test]
+    fn systems_apply_finished_tasks() {
+        let pool = TaskPool::new(1);
+        let mut world = World::new();
+        let entity = world
+            .spawn()
+            .insert(PathRequest(pool.spawn(|| vec![1, 2, 3])))
+            .id();
+        let mut stage = SystemStage::single_threaded().with_system(apply_paths);
+        for _ in 0..1000 {
+            stage.run(&mut world);
+            if world.get::<Path>(entity).is_some() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(world.get::<Path>(entity), Some(&Path(vec![1, 2, 3])));
+        assert!(world.get::<PathRequest>(entity).is_none());
+    }
+}
@@ -0,0 +1,1397 @@
+//! A stand-in for the unreal side of `UnrealBindings`, so that code which calls into unreal can be
+//! tested without the engine. Each test thread has its own `MockUnreal`, which holds the actors
+//! that unreal knows about and records the calls that change them.
+//!
+//! ```ignore
+//! testing::install();
+//! let actor = testing::spawn_actor();
+//! // ... run the code under test
+//! assert!(testing::calls().contains(&Call::DestroyActor { actor }));
+//! ```
+use std::{
+    alloc::{alloc, Layout},
+    cell::RefCell,
+    collections::HashMap,
+    ffi::c_void,
+    os::raw::c_char,
+    sync::Once,
+};
+
+use crate::{
+    core::from_ffi_uuid,
+    ffi::{self, AActorOpaque, UPrimtiveOpaque},
+    math::{Quat, Vec2, Vec3},
+    module::BINDINGS,
+};
+use unreal_reflect::Uuid;
+
+pub type Ptr = *mut c_void;
+
+/// An actor in the mocked unreal world
+#[derive(Debug, Clone)]
+pub struct MockActor {
+    pub name: String,
+    pub class_name: String,
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+    pub moveable: bool,
+    /// The primitive that is returned as the root component
+    pub root: Option<Ptr>,
+    pub tags: Vec<String>,
+    pub net_guid: Option<u64>,
+    pub net_role: ffi::NetRole,
+    /// Origin and half size of the bounding box
+    pub bounds: (Vec3, Vec3),
+    pub sockets: HashMap<String, (Vec3, Quat)>,
+    pub hidden: bool,
+}
+
+impl Default for MockActor {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            class_name: String::new(),
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+            moveable: true,
+            root: None,
+            tags: Vec::new(),
+            net_guid: None,
+            net_role: ffi::NetRole::Authority,
+            bounds: (Vec3::ZERO, Vec3::ZERO),
+            sockets: HashMap::new(),
+            hidden: false,
+        }
+    }
+}
+
+/// A primitive component in the mocked unreal world
+#[derive(Clone, Copy)]
+pub struct MockPrimitive {
+    pub simulating: bool,
+    pub velocity: Vec3,
+    pub angular_velocity: Vec3,
+    pub collision: ffi::CollisionResponse,
+    pub shape: ffi::CollisionShape,
+}
+
+impl Default for MockPrimitive {
+    fn default() -> Self {
+        Self {
+            simulating: false,
+            velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            collision: ffi::CollisionResponse::default(),
+            shape: crate::physics::CollisionShape::Capsule {
+                half_height: 90.0,
+                radius: 40.0,
+            }
+            .into(),
+        }
+    }
+}
+
+/// A call into unreal that changed something, in the order they were made
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    Log {
+        level: ffi::LogLevel,
+        message: String,
+    },
+    GetSpatialData {
+        actor: Ptr,
+    },
+    GetSpatialDataBulk {
+        len: usize,
+    },
+    SetSpatialData {
+        actor: Ptr,
+        position: Vec3,
+        rotation: Quat,
+        scale: Vec3,
+    },
+    SetEntityForActor {
+        actor: Ptr,
+        entity: u64,
+    },
+    SpawnActor {
+        actor: Ptr,
+        position: Vec3,
+    },
+    DestroyActor {
+        actor: Ptr,
+    },
+    SetOwner {
+        actor: Ptr,
+        owner: Ptr,
+    },
+    SetViewTarget {
+        actor: Ptr,
+    },
+    SetActorHidden {
+        actor: Ptr,
+        hidden: bool,
+    },
+    AttachToActor {
+        child: Ptr,
+        parent: Ptr,
+        socket: Option<String>,
+    },
+    DetachFromActor {
+        actor: Ptr,
+    },
+    SetActorLocationTeleport {
+        actor: Ptr,
+        position: Vec3,
+        rotation: Quat,
+    },
+    SetMaterialScalar {
+        actor: Ptr,
+        name: String,
+        value: f32,
+    },
+    SetMaterialVector {
+        actor: Ptr,
+        name: String,
+        value: Vec3,
+    },
+    SetVelocity {
+        primitive: Ptr,
+        velocity: Vec3,
+    },
+    SetAngularVelocity {
+        primitive: Ptr,
+        angular_velocity: Vec3,
+    },
+    SetSimulatePhysics {
+        primitive: Ptr,
+        simulate: bool,
+    },
+    SetCollisionResponse {
+        primitive: Ptr,
+        response: ffi::CollisionResponse,
+    },
+    AddForce {
+        primitive: Ptr,
+        force: Vec3,
+    },
+    AddImpulse {
+        primitive: Ptr,
+        impulse: Vec3,
+    },
+    AddTorque {
+        primitive: Ptr,
+        torque: Vec3,
+    },
+    AddAngularImpulse {
+        primitive: Ptr,
+        impulse: Vec3,
+    },
+    OnComponentChanged {
+        actor: Ptr,
+        uuid: Uuid,
+    },
+    OnComponentsChangedBulk {
+        changes: Vec<(Ptr, Uuid)>,
+    },
+    SetMouseCaptured {
+        captured: bool,
+    },
+    SetCameraProjection {
+        actor: Ptr,
+        projection: ffi::CameraProjection,
+    },
+    DebugDrawLine {
+        start: Vec3,
+        end: Vec3,
+        color: ffi::Color,
+        thickness: f32,
+        duration: f32,
+    },
+    DebugDrawSphere {
+        center: Vec3,
+        radius: f32,
+        color: ffi::Color,
+        duration: f32,
+    },
+    DebugDrawBox {
+        center: Vec3,
+        extent: Vec3,
+        rotation: Quat,
+        color: ffi::Color,
+        duration: f32,
+    },
+    PlaySoundAtLocation {
+        sound: Ptr,
+        location: Vec3,
+        rotation: Quat,
+        volume: f32,
+        pitch: f32,
+    },
+    PlaySound2d {
+        sound: Ptr,
+        volume: f32,
+        pitch: f32,
+    },
+}
+
+/// Returns the hit of a trace from `start` to `end`, if any
+pub type TraceFn = Box<dyn Fn(Vec3, Vec3) -> Option<ffi::HitResult>>;
+/// Returns the screen position of a world position, `None` if it is behind the camera
+pub type ProjectFn = Box<dyn Fn(Vec3) -> Option<Vec2>>;
+
+/// The state of the mocked unreal world of the current thread
+pub struct MockUnreal {
+    pub actors: HashMap<Ptr, MockActor>,
+    pub primitives: HashMap<Ptr, MockPrimitive>,
+    pub calls: Vec<Call>,
+    /// Actions that are held down, by local player and name
+    pub actions: HashMap<(u32, String), bool>,
+    pub axes: HashMap<(u32, String), f32>,
+    pub gamepads: HashMap<u32, ffi::GamepadState>,
+    pub mouse_delta: Vec2,
+    pub mouse: ffi::MouseState,
+    pub viewport: (u32, u32, f32),
+    pub time_dilation: f32,
+    pub config: String,
+    /// Drained by `poll_collision_events`
+    pub collision_events: Vec<ffi::CollisionEvent>,
+    pub names: Vec<String>,
+    pub classes: HashMap<String, Ptr>,
+    pub curves: HashMap<String, Vec<ffi::CurveKey>>,
+    pub sounds: HashMap<String, Ptr>,
+    /// Used for `raycast` and `line_trace`
+    pub raycast: Option<TraceFn>,
+    /// Used for `sweep` and `sweep_by_channel`
+    pub sweep: Option<TraceFn>,
+    pub overlaps: Vec<Ptr>,
+    pub project: Option<ProjectFn>,
+    /// Spawning an actor returns null, like unreal does when the spawn is blocked
+    pub fail_spawns: bool,
+    next_ptr: usize,
+}
+
+impl Default for MockUnreal {
+    fn default() -> Self {
+        Self {
+            actors: HashMap::new(),
+            primitives: HashMap::new(),
+            calls: Vec::new(),
+            actions: HashMap::new(),
+            axes: HashMap::new(),
+            gamepads: HashMap::new(),
+            mouse_delta: Vec2::ZERO,
+            mouse: ffi::MouseState::default(),
+            viewport: (0, 0, 1.0),
+            time_dilation: 1.0,
+            config: String::new(),
+            collision_events: Vec::new(),
+            names: Vec::new(),
+            classes: HashMap::new(),
+            curves: HashMap::new(),
+            sounds: HashMap::new(),
+            raycast: None,
+            sweep: None,
+            overlaps: Vec::new(),
+            project: None,
+            fail_spawns: false,
+            next_ptr: 0x1000,
+        }
+    }
+}
+
+impl MockUnreal {
+    /// A unique fake pointer, it is never dereferenced
+    pub fn new_ptr(&mut self) -> Ptr {
+        self.next_ptr += 0x10;
+        self.next_ptr as Ptr
+    }
+
+    pub fn spawn_actor(&mut self, actor: MockActor) -> Ptr {
+        let ptr = self.new_ptr();
+        self.actors.insert(ptr, actor);
+        ptr
+    }
+
+    /// Adds a primitive and makes it the root component of `actor`
+    pub fn add_primitive(&mut self, actor: Ptr, primitive: MockPrimitive) -> Ptr {
+        let ptr = self.new_ptr();
+        self.primitives.insert(ptr, primitive);
+        self.actor_mut(actor).root = Some(ptr);
+        ptr
+    }
+
+    pub fn actor_mut(&mut self, actor: Ptr) -> &mut MockActor {
+        self.actors.get_mut(&actor).expect("Unknown mock actor")
+    }
+
+    pub fn primitive_mut(&mut self, primitive: Ptr) -> &mut MockPrimitive {
+        self.primitives
+            .get_mut(&primitive)
+            .expect("Unknown mock primitive")
+    }
+
+    fn actor(&self, actor: *const c_void) -> Option<&MockActor> {
+        self.actors.get(&(actor as Ptr))
+    }
+
+    fn primitive(&self, primitive: *const c_void) -> Option<&MockPrimitive> {
+        self.primitives.get(&(primitive as Ptr))
+    }
+}
+
+thread_local! {
+    static UNREAL: RefCell<MockUnreal> = RefCell::new(MockUnreal::default());
+}
+
+/// Installs the mock bindings and resets the mocked world of the current thread. The bindings are
+/// process wide and only installed once, so they can not be mixed with the real bindings.
+pub fn install() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| unsafe { BINDINGS = Some(mock_bindings()) });
+    with(|unreal| *unreal = MockUnreal::default());
+}
+
+pub fn with<R>(f: impl FnOnce(&mut MockUnreal) -> R) -> R {
+    UNREAL.with(|unreal| f(&mut unreal.borrow_mut()))
+}
+
+/// Adds an actor at the origin
+pub fn spawn_actor() -> Ptr {
+    with(|unreal| unreal.spawn_actor(MockActor::default()))
+}
+
+/// Adds an actor with a simulating primitive as its root component, returns both
+pub fn spawn_physics_actor() -> (Ptr, Ptr) {
+    with(|unreal| {
+        let actor = unreal.spawn_actor(MockActor::default());
+        let primitive = unreal.add_primitive(
+            actor,
+            MockPrimitive {
+                simulating: true,
+                ..Default::default()
+            },
+        );
+        (actor, primitive)
+    })
+}
+
+pub fn calls() -> Vec<Call> {
+    with(|unreal| unreal.calls.clone())
+}
+
+/// Returns the recorded calls and forgets them
+pub fn take_calls() -> Vec<Call> {
+    with(|unreal| std::mem::take(&mut unreal.calls))
+}
+
+fn record(call: Call) {
+    with(|unreal| unreal.calls.push(call));
+}
+
+unsafe fn read_str<'a>(ptr: *const c_char, len: usize) -> &'a str {
+    if len == 0 {
+        return "";
+    }
+    std::str::from_utf8(std::slice::from_raw_parts(ptr as *const u8, len))
+        .expect("Rust passed a string that is not UTF-8")
+}
+
+/// Allocates like `AllocateFns::allocate`, so that Rust can free the string
+unsafe fn write_str(s: &str, out: *mut ffi::RustAlloc) {
+    if s.is_empty() {
+        return;
+    }
+    let ptr = alloc(Layout::from_size_align(s.len(), 1).unwrap());
+    std::ptr::copy_nonoverlapping(s.as_ptr(), ptr, s.len());
+    *out = ffi::RustAlloc {
+        ptr,
+        size: s.len(),
+        align: 1,
+    };
+}
+
+fn mock_bindings() -> ffi::UnrealBindings {
+    ffi::UnrealBindings {
+        actor_fns: ffi::ActorFns {
+            get_spatial_data,
+            get_spatial_data_bulk: Some(get_spatial_data_bulk),
+            set_spatial_data,
+            set_entity_for_actor,
+            get_actor_components,
+            register_actor_on_overlap,
+            register_actor_on_hit,
+            get_root_component,
+            get_registered_classes,
+            get_class,
+            set_view_target,
+            get_actor_name,
+            set_owner,
+            is_moveable,
+            destroy_actor,
+            poll_collision_events,
+            get_actor_class_name,
+            get_actor_tags,
+            get_actor_net_guid,
+            get_actor_net_role,
+            get_actor_bounds,
+            get_socket_transform,
+            set_actor_hidden,
+            is_actor_hidden,
+            attach_to_actor,
+            detach_from_actor,
+            set_actor_location_teleport,
+            set_material_scalar,
+            set_material_vector,
+        },
+        physics_fns: ffi::PhysicsFns {
+            get_velocity,
+            set_velocity,
+            is_simulating,
+            add_force,
+            add_impulse,
+            line_trace,
+            get_bounding_box_extent,
+            sweep,
+            sweep_multi,
+            overlap_multi,
+            get_collision_shape,
+            raycast,
+            sweep_by_channel,
+            get_angular_velocity,
+            set_angular_velocity,
+            add_torque,
+            add_angular_impulse,
+            get_collision_response,
+            set_collision_response,
+            set_simulate_physics,
+        },
+        log,
+        iterate_actors,
+        get_action_state,
+        get_axis_value,
+        spawn_actor: spawn_builtin_actor,
+        spawn_actor_from_class,
+        load_class,
+        load_curve_keys,
+        register_name,
+        on_component_changed,
+        on_components_changed_bulk: Some(on_components_changed_bulk),
+        get_mouse_delta,
+        get_mouse_state,
+        set_mouse_captured,
+        project_world_to_screen,
+        deproject_screen_to_world,
+        get_viewport_size,
+        get_config_blob,
+        get_time_dilation,
+        get_gamepad_state,
+        set_camera_projection,
+        debug_draw_line,
+        debug_draw_sphere,
+        debug_draw_box,
+        visual_log_segment,
+        visual_log_capsule,
+        visual_log_location,
+        editor_component_fns: ffi::EditorComponentFns {
+            get_editor_components,
+            get_editor_component_quat,
+            get_editor_component_vector,
+            get_editor_component_bool,
+            get_editor_component_float,
+            get_editor_component_uobject,
+        },
+        sound_fns: ffi::SoundFns {
+            play_sound_at_location,
+            play_sound_2d,
+            load_sound,
+        },
+    }
+}
+
+extern "C" fn get_spatial_data(
+    actor: *const AActorOpaque,
+    position: &mut ffi::Vector3,
+    rotation: &mut ffi::Quaternion,
+    scale: &mut ffi::Vector3,
+) {
+    record(Call::GetSpatialData {
+        actor: actor as Ptr,
+    });
+    with(|unreal| {
+        if let Some(actor) = unreal.actor(actor) {
+            *position = actor.position.into();
+            *rotation = actor.rotation.into();
+            *scale = actor.scale.into();
+        }
+    });
+}
+
+unsafe extern "C" fn get_spatial_data_bulk(
+    actors: *const *const AActorOpaque,
+    out: *mut ffi::SpatialData,
+    len: usize,
+) {
+    record(Call::GetSpatialDataBulk { len });
+    if len == 0 {
+        return;
+    }
+    let actors = std::slice::from_raw_parts(actors, len);
+    let out = std::slice::from_raw_parts_mut(out, len);
+    with(|unreal| {
+        for (&actor, out) in actors.iter().zip(out) {
+            if let Some(actor) = unreal.actor(actor) {
+                *out = ffi::SpatialData {
+                    position: actor.position.into(),
+                    rotation: actor.rotation.into(),
+                    scale: actor.scale.into(),
+                };
+            }
+        }
+    });
+}
+
+extern "C" fn set_spatial_data(
+    actor: *mut AActorOpaque,
+    position: ffi::Vector3,
+    rotation: ffi::Quaternion,
+    scale: ffi::Vector3,
+) {
+    record(Call::SetSpatialData {
+        actor,
+        position: position.into(),
+        rotation: rotation.into(),
+        scale: scale.into(),
+    });
+    with(|unreal| {
+        if let Some(mock) = unreal.actors.get_mut(&actor) {
+            mock.position = position.into();
+            mock.rotation = rotation.into();
+            mock.scale = scale.into();
+        }
+    });
+}
+
+unsafe extern "C" fn set_entity_for_actor(actor: *mut AActorOpaque, entity: ffi::Entity) {
+    record(Call::SetEntityForActor {
+        actor,
+        entity: entity.id,
+    });
+}
+
+unsafe extern "C" fn get_actor_components(
+    _actor: *const AActorOpaque,
+    _data: *mut ffi::ActorComponentPtr,
+    len: &mut usize,
+) {
+    *len = 0;
+}
+
+unsafe extern "C" fn register_actor_on_overlap(_actor: *mut AActorOpaque) {}
+
+unsafe extern "C" fn register_actor_on_hit(_actor: *mut AActorOpaque) {}
+
+unsafe extern "C" fn get_root_component(
+    actor: *const AActorOpaque,
+    data: *mut ffi::ActorComponentPtr,
+) {
+    let root = with(|unreal| unreal.actor(actor).and_then(|actor| actor.root));
+    *data = ffi::ActorComponentPtr {
+        ty: ffi::ActorComponentType::Primitive,
+        ptr: root.unwrap_or(std::ptr::null_mut()),
+    };
+}
+
+unsafe extern "C" fn get_registered_classes(
+    _classes: *mut *mut ffi::UClassOpague,
+    len: *mut usize,
+) {
+    *len = 0;
+}
+
+unsafe extern "C" fn get_class(_actor: *const AActorOpaque) -> *mut ffi::UClassOpague {
+    std::ptr::null_mut()
+}
+
+unsafe extern "C" fn set_view_target(actor: *const AActorOpaque) {
+    record(Call::SetViewTarget {
+        actor: actor as Ptr,
+    });
+}
+
+unsafe extern "C" fn get_actor_name(actor: *const AActorOpaque, data: *mut ffi::RustAlloc) {
+    let name = with(|unreal| unreal.actor(actor).map(|actor| actor.name.clone()));
+    write_str(&name.unwrap_or_default(), data);
+}
+
+unsafe extern "C" fn set_owner(actor: *mut AActorOpaque, new_owner: *const AActorOpaque) {
+    record(Call::SetOwner {
+        actor,
+        owner: new_owner as Ptr,
+    });
+}
+
+unsafe extern "C" fn is_moveable(actor: *const AActorOpaque) -> u32 {
+    with(|unreal| unreal.actor(actor).is_some_and(|actor| actor.moveable)) as u32
+}
+
+unsafe extern "C" fn destroy_actor(actor: *const AActorOpaque) {
+    record(Call::DestroyActor {
+        actor: actor as Ptr,
+    });
+    with(|unreal| unreal.actors.remove(&(actor as Ptr)));
+}
+
+unsafe extern "C" fn poll_collision_events(events: *mut ffi::CollisionEvent, len: *mut usize) {
+    with(|unreal| {
+        if events.is_null() {
+            *len = unreal.collision_events.len();
+            return;
+        }
+        let count = (*len).min(unreal.collision_events.len());
+        for (idx, event) in unreal.collision_events.drain(..count).enumerate() {
+            *events.add(idx) = event;
+        }
+        *len = count;
+    });
+}
+
+unsafe extern "C" fn get_actor_class_name(actor: *const AActorOpaque, data: *mut ffi::RustAlloc) {
+    let name = with(|unreal| unreal.actor(actor).map(|actor| actor.class_name.clone()));
+    write_str(&name.unwrap_or_default(), data);
+}
+
+unsafe extern "C" fn get_actor_tags(
+    actor: *const AActorOpaque,
+    data: *mut ffi::RustAlloc,
+    len: *mut usize,
+) {
+    let tags = with(|unreal| unreal.actor(actor).map(|actor| actor.tags.clone()));
+    let tags = tags.unwrap_or_default();
+    if data.is_null() {
+        *len = tags.len();
+        return;
+    }
+    let count = (*len).min(tags.len());
+    for (idx, tag) in tags.iter().take(count).enumerate() {
+        write_str(tag, data.add(idx));
+    }
+    *len = count;
+}
+
+unsafe extern "C" fn get_actor_net_guid(actor: *const AActorOpaque, out: *mut u64) -> u32 {
+    match with(|unreal| unreal.actor(actor).and_then(|actor| actor.net_guid)) {
+        Some(guid) => {
+            *out = guid;
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe extern "C" fn get_actor_net_role(actor: *const AActorOpaque) -> ffi::NetRole {
+    with(|unreal| unreal.actor(actor).map(|actor| actor.net_role))
+        .unwrap_or(ffi::NetRole::Authority)
+}
+
+unsafe extern "C" fn get_actor_bounds(
+    actor: *const AActorOpaque,
+    origin: *mut ffi::Vector3,
+    extent: *mut ffi::Vector3,
+) {
+    if let Some((bounds_origin, bounds_extent)) =
+        with(|unreal| unreal.actor(actor).map(|actor| actor.bounds))
+    {
+        *origin = bounds_origin.into();
+        *extent = bounds_extent.into();
+    }
+}
+
+unsafe extern "C" fn get_socket_transform(
+    actor: *const AActorOpaque,
+    name: *const c_char,
+    len: usize,
+    out: *mut ffi::SpatialData,
+) -> u32 {
+    let name = read_str(name, len);
+    let socket = with(|unreal| {
+        unreal
+            .actor(actor)
+            .and_then(|actor| actor.sockets.get(name).copied())
+    });
+    match socket {
+        Some((position, rotation)) => {
+            *out = ffi::SpatialData {
+                position: position.into(),
+                rotation: rotation.into(),
+                scale: Vec3::ONE.into(),
+            };
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe extern "C" fn set_actor_hidden(actor: *mut AActorOpaque, hidden: u32) {
+    record(Call::SetActorHidden {
+        actor,
+        hidden: hidden != 0,
+    });
+    with(|unreal| {
+        if let Some(mock) = unreal.actors.get_mut(&actor) {
+            mock.hidden = hidden != 0;
+        }
+    });
+}
+
+unsafe extern "C" fn is_actor_hidden(actor: *const AActorOpaque) -> u32 {
+    with(|unreal| unreal.actor(actor).is_some_and(|actor| actor.hidden)) as u32
+}
+
+unsafe extern "C" fn attach_to_actor(
+    child: *mut AActorOpaque,
+    parent: *mut AActorOpaque,
+    socket: *const c_char,
+    len: usize,
+) {
+    let socket = (len > 0).then(|| read_str(socket, len).to_string());
+    record(Call::AttachToActor {
+        child,
+        parent,
+        socket,
+    });
+}
+
+unsafe extern "C" fn detach_from_actor(actor: *mut AActorOpaque) {
+    record(Call::DetachFromActor { actor });
+}
+
+unsafe extern "C" fn set_actor_location_teleport(
+    actor: *mut AActorOpaque,
+    position: ffi::Vector3,
+    rotation: ffi::Quaternion,
+) {
+    record(Call::SetActorLocationTeleport {
+        actor,
+        position: position.into(),
+        rotation: rotation.into(),
+    });
+}
+
+unsafe extern "C" fn set_material_scalar(
+    actor: *mut AActorOpaque,
+    name: *const c_char,
+    len: usize,
+    value: f32,
+) {
+    record(Call::SetMaterialScalar {
+        actor,
+        name: read_str(name, len).to_string(),
+        value,
+    });
+}
+
+unsafe extern "C" fn set_material_vector(
+    actor: *mut AActorOpaque,
+    name: *const c_char,
+    len: usize,
+    value: ffi::Vector3,
+) {
+    record(Call::SetMaterialVector {
+        actor,
+        name: read_str(name, len).to_string(),
+        value: value.into(),
+    });
+}
+
+unsafe extern "C" fn get_velocity(primitive: *const UPrimtiveOpaque) -> ffi::Vector3 {
+    with(|unreal| unreal.primitive(primitive).map(|p| p.velocity))
+        .unwrap_or_default()
+        .into()
+}
+
+unsafe extern "C" fn set_velocity(primitive: *mut UPrimtiveOpaque, velocity: ffi::Vector3) {
+    record(Call::SetVelocity {
+        primitive,
+        velocity: velocity.into(),
+    });
+    with(|unreal| {
+        if let Some(mock) = unreal.primitives.get_mut(&primitive) {
+            mock.velocity = velocity.into();
+        }
+    });
+}
+
+unsafe extern "C" fn is_simulating(primitive: *const UPrimtiveOpaque) -> u32 {
+    with(|unreal| unreal.primitive(primitive).is_some_and(|p| p.simulating)) as u32
+}
+
+unsafe extern "C" fn add_force(primitive: *mut UPrimtiveOpaque, force: ffi::Vector3) {
+    record(Call::AddForce {
+        primitive,
+        force: force.into(),
+    });
+}
+
+unsafe extern "C" fn add_impulse(primitive: *mut UPrimtiveOpaque, impulse: ffi::Vector3) {
+    record(Call::AddImpulse {
+        primitive,
+        impulse: impulse.into(),
+    });
+}
+
+unsafe extern "C" fn line_trace(
+    start: ffi::Vector3,
+    end: ffi::Vector3,
+    _params: ffi::LineTraceParams,
+    result: &mut ffi::HitResult,
+) -> u32 {
+    trace(|unreal| unreal.raycast.as_ref(), start, end, result)
+}
+
+unsafe extern "C" fn raycast(
+    start: ffi::Vector3,
+    end: ffi::Vector3,
+    _params: ffi::TraceParams,
+    result: &mut ffi::HitResult,
+) -> u32 {
+    trace(|unreal| unreal.raycast.as_ref(), start, end, result)
+}
+
+fn trace(
+    get: impl FnOnce(&MockUnreal) -> Option<&TraceFn>,
+    start: ffi::Vector3,
+    end: ffi::Vector3,
+    result: &mut ffi::HitResult,
+) -> u32 {
+    let hit = with(|unreal| get(unreal).and_then(|trace| trace(start.into(), end.into())));
+    match hit {
+        Some(hit) => {
+            *result = hit;
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe extern "C" fn get_bounding_box_extent(_primitive: *const UPrimtiveOpaque) -> ffi::Vector3 {
+    ffi::Vector3::default()
+}
+
+unsafe extern "C" fn sweep(
+    start: ffi::Vector3,
+    end: ffi::Vector3,
+    _rotation: ffi::Quaternion,
+    _params: ffi::LineTraceParams,
+    _collision_shape: ffi::CollisionShape,
+    result: &mut ffi::HitResult,
+) -> u32 {
+    trace(|unreal| unreal.sweep.as_ref(), start, end, result)
+}
+
+unsafe extern "C" fn sweep_by_channel(
+    start: ffi::Vector3,
+    end: ffi::Vector3,
+    _rotation: ffi::Quaternion,
+    _params: ffi::TraceParams,
+    _collision_shape: ffi::CollisionShape,
+    result: &mut ffi::HitResult,
+) -> u32 {
+    trace(|unreal| unreal.sweep.as_ref(), start, end, result)
+}
+
+unsafe extern "C" fn sweep_multi(
+    _start: ffi::Vector3,
+    _end: ffi::Vector3,
+    _rotation: ffi::Quaternion,
+    _params: ffi::LineTraceParams,
+    _collision_shape: ffi::CollisionShape,
+    _max_results: usize,
+    _results: *mut ffi::HitResult,
+) -> u32 {
+    0
+}
+
+unsafe extern "C" fn overlap_multi(
+    _collision_shape: ffi::CollisionShape,
+    _position: ffi::Vector3,
+    _rotation: ffi::Quaternion,
+    _params: ffi::TraceParams,
+    max_results: usize,
+    results: *mut ffi::OverlapResult,
+) -> usize {
+    with(|unreal| {
+        let count = max_results.min(unreal.overlaps.len());
+        for (idx, &actor) in unreal.overlaps.iter().take(count).enumerate() {
+            results.add(idx).write(ffi::OverlapResult {
+                actor,
+                primtive: std::ptr::null_mut(),
+            });
+        }
+        count
+    })
+}
+
+unsafe extern "C" fn get_collision_shape(
+    primitive: *const UPrimtiveOpaque,
+    shape: *mut ffi::CollisionShape,
+) -> u32 {
+    match with(|unreal| unreal.primitive(primitive).map(|p| p.shape)) {
+        Some(mock) => {
+            *shape = mock;
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe extern "C" fn get_angular_velocity(primitive: *const UPrimtiveOpaque) -> ffi::Vector3 {
+    with(|unreal| unreal.primitive(primitive).map(|p| p.angular_velocity))
+        .unwrap_or_default()
+        .into()
+}
+
+unsafe extern "C" fn set_angular_velocity(
+    primitive: *mut UPrimtiveOpaque,
+    angular_velocity: ffi::Vector3,
+) {
+    record(Call::SetAngularVelocity {
+        primitive,
+        angular_velocity: angular_velocity.into(),
+    });
+    with(|unreal| {
+        if let Some(mock) = unreal.primitives.get_mut(&primitive) {
+            mock.angular_velocity = angular_velocity.into();
+        }
+    });
+}
+
+unsafe extern "C" fn add_torque(primitive: *mut UPrimtiveOpaque, torque: ffi::Vector3) {
+    record(Call::AddTorque {
+        primitive,
+        torque: torque.into(),
+    });
+}
+
+unsafe extern "C" fn add_angular_impulse(primitive: *mut UPrimtiveOpaque, impulse: ffi::Vector3) {
+    record(Call::AddAngularImpulse {
+        primitive,
+        impulse: impulse.into(),
+    });
+}
+
+unsafe extern "C" fn get_collision_response(
+    primitive: *const UPrimtiveOpaque,
+    response: *mut ffi::CollisionResponse,
+) {
+    if let Some(collision) = with(|unreal| unreal.primitive(primitive).map(|p| p.collision)) {
+        *response = collision;
+    }
+}
+
+unsafe extern "C" fn set_collision_response(
+    primitive: *mut UPrimtiveOpaque,
+    response: ffi::CollisionResponse,
+) {
+    record(Call::SetCollisionResponse {
+        primitive,
+        response,
+    });
+    with(|unreal| {
+        if let Some(mock) = unreal.primitives.get_mut(&primitive) {
+            mock.collision = response;
+        }
+    });
+}
+
+unsafe extern "C" fn set_simulate_physics(primitive: *mut UPrimtiveOpaque, simulate: u32) {
+    record(Call::SetSimulatePhysics {
+        primitive,
+        simulate: simulate != 0,
+    });
+    with(|unreal| {
+        if let Some(mock) = unreal.primitives.get_mut(&primitive) {
+            mock.simulating = simulate != 0;
+        }
+    });
+}
+
+extern "C" fn log(level: ffi::LogLevel, s: *const c_char, len: i32) {
+    let message = unsafe { read_str(s, len.max(0) as usize) }.to_string();
+    record(Call::Log { level, message });
+}
+
+unsafe extern "C" fn iterate_actors(array: *mut *mut AActorOpaque, len: *mut u64) {
+    with(|unreal| {
+        let count = (*len as usize).min(unreal.actors.len());
+        for (idx, &actor) in unreal.actors.keys().take(count).enumerate() {
+            *array.add(idx) = actor;
+        }
+        *len = count as u64;
+    });
+}
+
+unsafe extern "C" fn get_action_state(
+    player_index: u32,
+    name: *const c_char,
+    len: usize,
+    _state: ffi::ActionState,
+    out: *mut u32,
+) {
+    let name = read_str(name, len).to_string();
+    *out = with(|unreal| {
+        unreal
+            .actions
+            .get(&(player_index, name))
+            .copied()
+            .unwrap_or(false)
+    }) as u32;
+}
+
+unsafe extern "C" fn get_axis_value(
+    player_index: u32,
+    name: *const c_char,
+    len: usize,
+    value: &mut f32,
+) {
+    let name = read_str(name, len).to_string();
+    *value = with(|unreal| unreal.axes.get(&(player_index, name)).copied()).unwrap_or(0.0);
+}
+
+unsafe extern "C" fn spawn_builtin_actor(
+    _actor_class: ffi::ActorClass,
+    position: ffi::Vector3,
+    rotation: ffi::Quaternion,
+    scale: ffi::Vector3,
+) -> *mut AActorOpaque {
+    spawn_at(position, rotation, scale)
+}
+
+unsafe extern "C" fn spawn_actor_from_class(
+    _class: *const ffi::UClassOpague,
+    position: ffi::Vector3,
+    rotation: ffi::Quaternion,
+    scale: ffi::Vector3,
+) -> *mut AActorOpaque {
+    spawn_at(position, rotation, scale)
+}
+
+fn spawn_at(
+    position: ffi::Vector3,
+    rotation: ffi::Quaternion,
+    scale: ffi::Vector3,
+) -> *mut AActorOpaque {
+    let actor = with(|unreal| {
+        if unreal.fail_spawns {
+            return std::ptr::null_mut();
+        }
+        unreal.spawn_actor(MockActor {
+            position: position.into(),
+            rotation: rotation.into(),
+            scale: scale.into(),
+            ..Default::default()
+        })
+    });
+    if !actor.is_null() {
+        record(Call::SpawnActor {
+            actor,
+            position: position.into(),
+        });
+    }
+    actor
+}
+
+unsafe extern "C" fn load_class(path: ffi::Utf8Str) -> *mut ffi::UClassOpague {
+    let path = read_str(path.ptr, path.len);
+    with(|unreal| unreal.classes.get(path).copied()).unwrap_or(std::ptr::null_mut())
+}
+
+unsafe extern "C" fn load_curve_keys(
+    path: ffi::Utf8Str,
+    keys: *mut ffi::CurveKey,
+    len: *mut usize,
+) -> u32 {
+    let path = read_str(path.ptr, path.len);
+    let Some(curve) = with(|unreal| unreal.curves.get(path).cloned()) else {
+        return 0;
+    };
+    if keys.is_null() {
+        *len = curve.len();
+    } else {
+        let count = (*len).min(curve.len());
+        std::ptr::copy_nonoverlapping(curve.as_ptr(), keys, count);
+        *len = count;
+    }
+    1
+}
+
+unsafe extern "C" fn register_name(name: *const c_char, len: usize) -> u32 {
+    let name = read_str(name, len);
+    with(|unreal| {
+        // Case insensitive like unreal
+        match unreal
+            .names
+            .iter()
+            .position(|existing| existing.eq_ignore_ascii_case(name))
+        {
+            Some(id) => id as u32,
+            None => {
+                unreal.names.push(name.to_string());
+                unreal.names.len() as u32 - 1
+            }
+        }
+    })
+}
+
+unsafe extern "C" fn on_component_changed(actor: *const AActorOpaque, uuid: ffi::Uuid) {
+    record(Call::OnComponentChanged {
+        actor: actor as Ptr,
+        uuid: from_ffi_uuid(uuid),
+    });
+}
+
+unsafe extern "C" fn on_components_changed_bulk(changes: *const ffi::ComponentChange, len: usize) {
+    let changes = if len == 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(changes, len)
+            .iter()
+            .map(|change| (change.actor as Ptr, from_ffi_uuid(change.uuid)))
+            .collect()
+    };
+    record(Call::OnComponentsChangedBulk { changes });
+}
+
+unsafe extern "C" fn get_mouse_delta(x: &mut f32, y: &mut f32) {
+    let delta = with(|unreal| unreal.mouse_delta);
+    *x = delta.x;
+    *y = delta.y;
+}
+
+unsafe extern "C" fn get_mouse_state(state: *mut ffi::MouseState) {
+    *state = with(|unreal| unreal.mouse);
+}
+
+unsafe extern "C" fn set_mouse_captured(captured: u32) {
+    record(Call::SetMouseCaptured {
+        captured: captured != 0,
+    });
+}
+
+unsafe extern "C" fn project_world_to_screen(
+    world: ffi::Vector3,
+    screen: *mut ffi::Vector2,
+) -> u32 {
+    let projected = with(|unreal| {
+        unreal
+            .project
+            .as_ref()
+            .and_then(|project| project(world.into()))
+    });
+    match projected {
+        Some(projected) => {
+            *screen = projected.into();
+            1
+        }
+        None => 0,
+    }
+}
+
+unsafe extern "C" fn deproject_screen_to_world(
+    _screen: ffi::Vector2,
+    _origin: *mut ffi::Vector3,
+    _direction: *mut ffi::Vector3,
+) -> u32 {
+    0
+}
+
+unsafe extern "C" fn get_viewport_size(width: *mut u32, height: *mut u32, dpi_scale: *mut f32) {
+    let (w, h, scale) = with(|unreal| unreal.viewport);
+    *width = w;
+    *height = h;
+    *dpi_scale = scale;
+}
+
+unsafe extern "C" fn get_config_blob(data: *mut ffi::RustAlloc) {
+    let config = with(|unreal| unreal.config.clone());
+    write_str(&config, data);
+}
+
+unsafe extern "C" fn get_time_dilation() -> f32 {
+    with(|unreal| unreal.time_dilation)
+}
+
+unsafe extern "C" fn get_gamepad_state(index: u32, state: *mut ffi::GamepadState) {
+    *state = with(|unreal| unreal.gamepads.get(&index).copied()).unwrap_or_default();
+}
+
+unsafe extern "C" fn set_camera_projection(
+    actor: *mut AActorOpaque,
+    projection: ffi::CameraProjection,
+) {
+    record(Call::SetCameraProjection { actor, projection });
+}
+
+unsafe extern "C" fn debug_draw_line(
+    start: ffi::Vector3,
+    end: ffi::Vector3,
+    color: ffi::Color,
+    thickness: f32,
+    duration: f32,
+) {
+    record(Call::DebugDrawLine {
+        start: start.into(),
+        end: end.into(),
+        color,
+        thickness,
+        duration,
+    });
+}
+
+unsafe extern "C" fn debug_draw_sphere(
+    center: ffi::Vector3,
+    radius: f32,
+    color: ffi::Color,
+    duration: f32,
+) {
+    record(Call::DebugDrawSphere {
+        center: center.into(),
+        radius,
+        color,
+        duration,
+    });
+}
+
+unsafe extern "C" fn debug_draw_box(
+    center: ffi::Vector3,
+    extent: ffi::Vector3,
+    rotation: ffi::Quaternion,
+    color: ffi::Color,
+    duration: f32,
+) {
+    record(Call::DebugDrawBox {
+        center: center.into(),
+        extent: extent.into(),
+        rotation: rotation.into(),
+        color,
+        duration,
+    });
+}
+
+unsafe extern "C" fn visual_log_segment(
+    _owner: *const AActorOpaque,
+    _start: ffi::Vector3,
+    _end: ffi::Vector3,
+    _color: ffi::Color,
+) {
+}
+
+unsafe extern "C" fn visual_log_capsule(
+    _category: ffi::Utf8Str,
+    _owner: *const AActorOpaque,
+    _position: ffi::Vector3,
+    _rotation: ffi::Quaternion,
+    _half_height: f32,
+    _radius: f32,
+    _color: ffi::Color,
+) {
+}
+
+unsafe extern "C" fn visual_log_location(
+    _category: ffi::Utf8Str,
+    _owner: *const AActorOpaque,
+    _position: ffi::Vector3,
+    _radius: f32,
+    _color: ffi::Color,
+) {
+}
+
+unsafe extern "C" fn get_editor_components(
+    _actor: *const AActorOpaque,
+    _data: *mut ffi::Uuid,
+    len: *mut usize,
+) -> u32 {
+    *len = 0;
+    1
+}
+
+unsafe extern "C" fn get_editor_component_quat(
+    _actor: *const AActorOpaque,
+    _uuid: ffi::Uuid,
+    _field: ffi::Utf8Str,
+    _out: *mut ffi::Quaternion,
+) -> u32 {
+    0
+}
+
+unsafe extern "C" fn get_editor_component_vector(
+    _actor: *const AActorOpaque,
+    _uuid: ffi::Uuid,
+    _field: ffi::Utf8Str,
+    _out: *mut ffi::Vector3,
+) -> u32 {
+    0
+}
+
+unsafe extern "C" fn get_editor_component_bool(
+    _actor: *const AActorOpaque,
+    _uuid: ffi::Uuid,
+    _field: ffi::Utf8Str,
+    _out: *mut u32,
+) -> u32 {
+    0
+}
+
+unsafe extern "C" fn get_editor_component_float(
+    _actor: *const AActorOpaque,
+    _uuid: ffi::Uuid,
+    _field: ffi::Utf8Str,
+    _out: *mut f32,
+) -> u32 {
+    0
+}
+
+unsafe extern "C" fn get_editor_component_uobject(
+    _actor: *const AActorOpaque,
+    _uuid: ffi::Uuid,
+    _field: ffi::Utf8Str,
+    _ty: ffi::UObjectType,
+    _out: *mut *mut ffi::UObjectOpague,
+) -> u32 {
+    0
+}
+
+unsafe extern "C" fn play_sound_at_location(
+    sound: *const ffi::USoundBaseOpague,
+    location: ffi::Vector3,
+    rotation: ffi::Quaternion,
+    settings: *const ffi::SoundSettings,
+) {
+    let settings = &*settings;
+    record(Call::PlaySoundAtLocation {
+        sound: sound as Ptr,
+        location: location.into(),
+        rotation: rotation.into(),
+        volume: settings.volume,
+        pitch: settings.pitch,
+    });
+}
+
+unsafe extern "C" fn play_sound_2d(
+    sound: *const ffi::USoundBaseOpague,
+    settings: *const ffi::SoundSettings,
+) {
+    let settings = &*settings;
+    record(Call::PlaySound2d {
+        sound: sound as Ptr,
+        volume: settings.volume,
+        pitch: settings.pitch,
+    });
+}
+
+unsafe extern "C" fn load_sound(path: ffi::Utf8Str) -> *mut ffi::USoundBaseOpague {
+    let path = read_str(path.ptr, path.len);
+    with(|unreal| unreal.sounds.get(path).copied()).unwrap_or(std::ptr::null_mut())
+}
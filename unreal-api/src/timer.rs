@@ -0,0 +1,191 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use bevy_ecs::system::{Res, ResMut};
+
+use crate::core::Frame;
+
+/// Counts down from `duration` seconds. Repeating timers start over once they finish and keep
+/// the time that overshot the end.
+#[derive(Debug, Clone, Default)]
+pub struct Timer {
+    duration: f32,
+    elapsed: f32,
+    repeating: bool,
+    finished: bool,
+    times_finished: u32,
+}
+
+impl Timer {
+    pub fn from_seconds(duration: f32, repeating: bool) -> Self {
+        Self {
+            duration: duration.max(0.0),
+            repeating,
+            ..Default::default()
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32) -> &Self {
+        if self.finished && !self.repeating {
+            self.times_finished = 0;
+            return self;
+        }
+        self.elapsed += dt;
+        if self.elapsed < self.duration {
+            self.finished = false;
+            self.times_finished = 0;
+            return self;
+        }
+
+        self.finished = true;
+        if !self.repeating {
+            self.elapsed = self.duration;
+            self.times_finished = 1;
+        } else if self.duration > 0.0 {
+            // A single large `dt` can finish a repeating timer multiple times
+            self.times_finished = (self.elapsed / self.duration) as u32;
+            self.elapsed %= self.duration;
+        } else {
+            self.times_finished = 1;
+            self.elapsed = 0.0;
+        }
+        self
+    }
+
+    /// One shot timers stay finished until they are reset. Repeating timers are only finished
+    /// in the tick in which they wrapped around.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// True only in the tick in which the timer finished
+    pub fn just_finished(&self) -> bool {
+        self.times_finished > 0
+    }
+
+    /// How often the timer finished in the last tick. Can be more than 1 for repeating timers.
+    pub fn times_finished(&self) -> u32 {
+        self.times_finished
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn remaining(&self) -> f32 {
+        self.duration - self.elapsed
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.finished = false;
+        self.times_finished = 0;
+    }
+}
+
+/// Named timers that are ticked with `Frame::dt` in `CoreStage::PreUpdate`, so systems in
+/// `CoreStage::Update` see `just_finished` for exactly one frame. They stop while the game is
+/// paused, see `run_if_running`.
+#[derive(Default, Debug)]
+pub struct Timers {
+    timers: HashMap<Cow<'static, str>, Timer>,
+}
+
+impl Timers {
+    /// Replaces any timer with the same name
+    pub fn insert(&mut self, name: impl Into<Cow<'static, str>>, timer: Timer) {
+        self.timers.insert(name.into(), timer);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Timer> {
+        self.timers.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Timer> {
+        self.timers.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Timer> {
+        self.timers.get_mut(name)
+    }
+
+    /// False if there is no timer with that name
+    pub fn finished(&self, name: &str) -> bool {
+        self.get(name).is_some_and(Timer::finished)
+    }
+
+    /// False if there is no timer with that name
+    pub fn just_finished(&self, name: &str) -> bool {
+        self.get(name).is_some_and(Timer::just_finished)
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        for timer in self.timers.values_mut() {
+            timer.tick(dt);
+        }
+    }
+}
+
+pub(crate) fn tick_timers(frame: Res<Frame>, mut timers: ResMut<Timers>) {
+    timers.tick(frame.dt);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_shot() {
+        let mut timer = Timer::from_seconds(1.0, false);
+        assert!(!timer.tick(0.6).finished());
+        assert!(timer.tick(0.6).just_finished());
+        assert!(timer.finished());
+        assert_eq!(timer.remaining(), 0.0);
+
+        // Stays finished, but only just finished once
+        assert!(!timer.tick(0.6).just_finished());
+        assert!(timer.finished());
+
+        timer.reset();
+        assert!(!timer.finished());
+        assert_eq!(timer.elapsed(), 0.0);
+    }
+
+    #[test]
+    fn repeating_keeps_the_overshoot() {
+        let mut timer = Timer::from_seconds(1.0, true);
+        assert!(!timer.tick(0.75).just_finished());
+        assert!(timer.tick(0.5).just_finished());
+        assert_eq!(timer.times_finished(), 1);
+        assert!((timer.elapsed() - 0.25).abs() < 1e-6);
+
+        assert!(!timer.tick(0.5).finished());
+        assert!(timer.tick(0.25).just_finished());
+    }
+
+    #[test]
+    fn repeating_finishes_multiple_times_in_one_tick() {
+        let mut timer = Timer::from_seconds(0.5, true);
+        timer.tick(1.6);
+        assert!(timer.just_finished());
+        assert_eq!(timer.times_finished(), 3);
+        assert!((timer.elapsed() - 0.1).abs() < 1e-5);
+
+        timer.tick(0.1);
+        assert_eq!(timer.times_finished(), 0);
+    }
+
+    #[test]
+    fn named_timers() {
+        let mut timers = Timers::default();
+        timers.insert("spawn", Timer::from_seconds(1.0, false));
+        timers.tick(1.0);
+        assert!(timers.just_finished("spawn"));
+        assert!(!timers.finished("unknown"));
+        assert!(timers.remove("spawn").is_some());
+        assert!(!timers.finished("spawn"));
+    }
+}
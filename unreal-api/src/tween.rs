@@ -0,0 +1,225 @@
+use std::{f32::consts::PI, marker::PhantomData};
+
+use bevy_ecs::{
+    component::{Component, TableStorage},
+    entity::Entity,
+    schedule::SystemSet,
+    system::{Commands, Query, Res},
+};
+
+use crate::{
+    core::{CoreStage, Frame},
+    math::Vec3,
+    module::Module,
+    plugin::Plugin,
+};
+
+/// Values that a `Tween` can interpolate
+pub trait Tweenable: Copy + Send + Sync + 'static {
+    fn interpolate(self, end: Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn interpolate(self, end: Self, t: f32) -> Self {
+        self + (end - self) * t
+    }
+}
+
+impl Tweenable for Vec3 {
+    fn interpolate(self, end: Self, t: f32) -> Self {
+        self.lerp(end, t)
+    }
+}
+
+/// Maps the linear progress of a tween in `[0, 1]` to the progress of the value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Starts slowly, speeds up and slows down again towards the end
+    EaseInOut,
+    /// Bounces towards the end with decreasing amplitude, like a ball that is dropped. Never
+    /// leaves `[0, 1]`.
+    Bounce,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => 0.5 - 0.5 * (PI * t).cos(),
+            Easing::Bounce => bounce_out(t),
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// Animates a value from `start` to `end` over `duration` seconds and writes it into the
+/// component `C` of the same entity with `apply`, e.g. the scale of the `TransformComponent` for
+/// a scale punch. The tween is removed after it wrote `end`. Needs a `TweenPlugin<T, C>`.
+pub struct Tween<T, C> {
+    pub start: T,
+    pub end: T,
+    pub duration: f32,
+    pub easing: Easing,
+    pub elapsed: f32,
+    pub apply: fn(&mut C, T),
+}
+
+impl<T: Tweenable, C> Tween<T, C> {
+    pub fn new(start: T, end: T, duration: f32, easing: Easing, apply: fn(&mut C, T)) -> Self {
+        Self {
+            start,
+            end,
+            duration: duration.max(0.0),
+            easing,
+            elapsed: 0.0,
+            apply,
+        }
+    }
+
+    /// The linear progress in `[0, 1]`, a tween with a duration of 0 is always finished
+    pub fn progress(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed / self.duration).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// The eased value at the current progress
+    pub fn value(&self) -> T {
+        self.start
+            .interpolate(self.end, self.easing.apply(self.progress()))
+    }
+}
+
+// Implemented by hand because the derive needs a uuid, which a generic component can not have
+impl<T, C> Component for Tween<T, C>
+where
+    T: Send + Sync + 'static,
+    C: Send + Sync + 'static,
+{
+    type Storage = TableStorage;
+}
+
+/// Advances all `Tween<T, C>`s by `Frame::dt` in `CoreStage::LateUpdate`, so that the values
+/// are written before transforms are uploaded in `CoreStage::PostUpdate`
+pub struct TweenPlugin<T, C>(PhantomData<fn() -> (T, C)>);
+
+impl<T, C> Default for TweenPlugin<T, C> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, C> Plugin for TweenPlugin<T, C>
+where
+    T: Tweenable,
+    C: Component,
+{
+    fn build(&self, module: &mut Module) {
+        module.add_system_set_to_stage(
+            CoreStage::LateUpdate,
+            SystemSet::new().with_system(advance_tweens::<T, C>),
+        );
+    }
+}
+
+fn advance_tweens<T, C>(
+    mut commands: Commands,
+    frame: Res<Frame>,
+    mut query: Query<(Entity, &mut Tween<T, C>, &mut C)>,
+) where
+    T: Tweenable,
+    C: Component,
+{
+    for (entity, mut tween, mut target) in query.iter_mut() {
+        tween.elapsed += frame.dt;
+        (tween.apply)(&mut target, tween.value());
+        if tween.is_finished() {
+            commands.entity(entity).remove::<Tween<T, C>>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec_eq, core::TransformComponent};
+    use bevy_ecs::{
+        schedule::{Stage, SystemStage},
+        world::World,
+    };
+
+    #[test]
+    fn easings_start_and_end_in_place() {
+        for easing in [Easing::Linear, Easing::EaseInOut, Easing::Bounce] {
+            assert!(easing.apply(0.0).abs() < 1e-6, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{:?}", easing);
+            assert_eq!(easing.apply(2.0), easing.apply(1.0));
+        }
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-6);
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+        for step in 0..=100 {
+            let value = Easing::Bounce.apply(step as f32 / 100.0);
+            assert!((0.0..=1.0).contains(&value), "{} at {}", value, step);
+        }
+    }
+
+    #[test]
+    fn tween_writes_eased_values_and_removes_itself() {
+        let mut world = World::new();
+        world.insert_resource(Frame {
+            dt: 0.25,
+            ..Default::default()
+        });
+        let tween = Tween::new(
+            Vec3::ZERO,
+            Vec3::X * 10.0,
+            0.5,
+            Easing::EaseInOut,
+            |transform: &mut TransformComponent, position| transform.position = position,
+        );
+        let entity = world
+            .spawn()
+            .insert_bundle((TransformComponent::default(), tween))
+            .id();
+        let mut stage =
+            SystemStage::single_threaded().with_system(advance_tweens::<Vec3, TransformComponent>);
+
+        stage.run(&mut world);
+        let position = world.get::<TransformComponent>(entity).unwrap().position;
+        assert_vec_eq!(position, Vec3::X * 5.0, 1e-5);
+        assert!(world
+            .get::<Tween<Vec3, TransformComponent>>(entity)
+            .is_some());
+
+        stage.run(&mut world);
+        let position = world.get::<TransformComponent>(entity).unwrap().position;
+        assert_vec_eq!(position, Vec3::X * 10.0);
+        assert!(world
+            .get::<Tween<Vec3, TransformComponent>>(entity)
+            .is_none());
+    }
+}
@@ -18,6 +18,10 @@ fn main() {
         .include_item("ActorEndOverlap")
         .include_item("ActorHitEvent")
         .include_item("ActorDestroyEvent")
+        .include_item("GamepadAxis")
+        .include_item("GamepadButton")
+        .include_item("Rotator")
+        .include_item("ComponentChange")
         .with_pragma_once(true)
         //.with_config(Config {
         //    structure: StructConfig  {
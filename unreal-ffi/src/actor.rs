@@ -4,6 +4,48 @@ use crate::{
     AActorOpaque, ActorComponentPtr, Entity, Quaternion, RustAlloc, UClassOpague, Vector3,
 };
 
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CollisionPhase {
+    Begin = 0,
+    End = 1,
+    /// A blocking hit, the only phase with impact data
+    Hit = 2,
+}
+
+/// The local role of a replicated actor. Actors that are not replicated are `Authority`.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NetRole {
+    Authority = 0,
+    SimulatedProxy = 1,
+    AutonomousProxy = 2,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CollisionEvent {
+    pub self_actor: *mut AActorOpaque,
+    pub other: *mut AActorOpaque,
+    pub phase: CollisionPhase,
+    /// Velocity of `self_actor` relative to `other` at the time of the hit. Zero for overlaps.
+    pub impact_velocity: Vector3,
+    /// Zero for overlaps
+    pub impact_point: Vector3,
+}
+
+impl Default for CollisionEvent {
+    fn default() -> Self {
+        Self {
+            self_actor: std::ptr::null_mut(),
+            other: std::ptr::null_mut(),
+            phase: CollisionPhase::Begin,
+            impact_velocity: Vector3::default(),
+            impact_point: Vector3::default(),
+        }
+    }
+}
+
 pub type GetSpatialDataFn = extern "C" fn(
     actor: *const AActorOpaque,
     position: &mut Vector3,
@@ -11,6 +53,14 @@ pub type GetSpatialDataFn = extern "C" fn(
     scale: &mut Vector3,
 );
 
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone)]
+pub struct SpatialData {
+    pub position: Vector3,
+    pub rotation: Quaternion,
+    pub scale: Vector3,
+}
+
 pub type LogFn = extern "C" fn(*const c_char, i32);
 
 pub type SetSpatialDataFn = extern "C" fn(
@@ -37,6 +87,60 @@ pub type IsMoveableFn = unsafe extern "C" fn(actor: *const AActorOpaque) -> u32;
 
 pub type GetActorNameFn = unsafe extern "C" fn(actor: *const AActorOpaque, data: *mut RustAlloc);
 
+/// Writes the name of the actor's `UClass`, e.g. `BP_Enemy_C` for blueprints
+pub type GetActorClassNameFn =
+    unsafe extern "C" fn(actor: *const AActorOpaque, data: *mut RustAlloc);
+
+/// Like `RetrieveUuids`, the first call with a null `data` writes the number of tags into `len`.
+/// The second call allocates a string for each of the first `len` tags.
+pub type GetActorTagsFn =
+    unsafe extern "C" fn(actor: *const AActorOpaque, data: *mut RustAlloc, len: *mut usize);
+
+/// Returns 0 if the actor has no network GUID, e.g. because it is not replicated
+pub type GetActorNetGuidFn = unsafe extern "C" fn(actor: *const AActorOpaque, out: *mut u64) -> u32;
+
+pub type GetActorNetRoleFn = unsafe extern "C" fn(actor: *const AActorOpaque) -> NetRole;
+
+/// The world space bounding box of all colliding components of the actor
+pub type GetActorBoundsFn =
+    unsafe extern "C" fn(actor: *const AActorOpaque, origin: *mut Vector3, extent: *mut Vector3);
+
+/// Writes the world transform of the socket or bone `name` into `out`. Returns 1 if any scene
+/// component of the actor has that socket, 0 otherwise.
+pub type GetSocketTransformFn = unsafe extern "C" fn(
+    actor: *const AActorOpaque,
+    name: *const c_char,
+    len: usize,
+    out: *mut SpatialData,
+) -> u32;
+
+pub type SetActorHiddenFn = unsafe extern "C" fn(actor: *mut AActorOpaque, hidden: u32);
+pub type IsActorHiddenFn = unsafe extern "C" fn(actor: *const AActorOpaque) -> u32;
+
+/// Moves the actor without sweeping, and physics treats the move as a teleport instead of
+/// deriving a velocity from it
+pub type SetActorLocationTeleportFn =
+    unsafe extern "C" fn(actor: *mut AActorOpaque, position: Vector3, rotation: Quaternion);
+
+/// Attaches the root component of `child` to the root component of `parent`, at the socket `name`
+/// if `len` is not 0. The world transform of `child` is kept.
+pub type AttachToActorFn = unsafe extern "C" fn(
+    child: *mut AActorOpaque,
+    parent: *mut AActorOpaque,
+    socket: *const c_char,
+    len: usize,
+);
+/// Does nothing if `actor` is not attached, the world transform is kept
+pub type DetachFromActorFn = unsafe extern "C" fn(actor: *mut AActorOpaque);
+
+/// Sets the parameter `name` on the materials of all primitive components of the actor. Unreal
+/// creates dynamic material instances for them on first use.
+pub type SetMaterialScalarFn =
+    unsafe extern "C" fn(actor: *mut AActorOpaque, name: *const c_char, len: usize, value: f32);
+/// Same as `SetMaterialScalarFn` for vector parameters, the alpha is always 1
+pub type SetMaterialVectorFn =
+    unsafe extern "C" fn(actor: *mut AActorOpaque, name: *const c_char, len: usize, value: Vector3);
+
 pub type SetOwnerFn =
     unsafe extern "C" fn(actor: *mut AActorOpaque, new_owner: *const AActorOpaque);
 
@@ -47,6 +151,10 @@ pub type SetViewTargetFn = unsafe extern "C" fn(actor: *const AActorOpaque);
 
 pub type DestroyActorFn = unsafe extern "C" fn(actor: *const AActorOpaque);
 
+/// If `events` is null, `len` will be set to the number of pending events. Otherwise up to `len`
+/// events are written and removed from the queue, and `len` is set to the number of written events.
+pub type PollCollisionEventsFn = unsafe extern "C" fn(events: *mut CollisionEvent, len: *mut usize);
+
 extern "C" {
     pub fn RegisterActorOnHit(actor: *mut AActorOpaque);
     pub fn RegisterActorOnOverlap(actor: *mut AActorOpaque);
@@ -66,6 +174,11 @@ extern "C" {
         rotation: &mut Quaternion,
         scale: &mut Vector3,
     );
+    pub fn GetSpatialDataBulk(
+        actors: *const *const AActorOpaque,
+        out: *mut SpatialData,
+        len: usize,
+    );
     pub fn SetEntityForActor(name: *mut AActorOpaque, entity: Entity);
 
     pub fn GetActorComponents(
@@ -84,14 +197,64 @@ extern "C" {
 
     pub fn GetActorName(actor: *const AActorOpaque, data: *mut RustAlloc);
 
+    pub fn GetActorClassName(actor: *const AActorOpaque, data: *mut RustAlloc);
+
+    pub fn GetActorTags(actor: *const AActorOpaque, data: *mut RustAlloc, len: *mut usize);
+
+    pub fn GetActorNetGuid(actor: *const AActorOpaque, out: *mut u64) -> u32;
+
+    pub fn GetActorNetRole(actor: *const AActorOpaque) -> NetRole;
+
+    pub fn GetActorBounds(actor: *const AActorOpaque, origin: *mut Vector3, extent: *mut Vector3);
+
+    pub fn GetSocketTransform(
+        actor: *const AActorOpaque,
+        name: *const c_char,
+        len: usize,
+        out: *mut SpatialData,
+    ) -> u32;
+
     pub fn DestroyActor(actor: *const AActorOpaque);
 
+    pub fn SetActorHidden(actor: *mut AActorOpaque, hidden: u32);
+    pub fn IsActorHidden(actor: *const AActorOpaque) -> u32;
+
+    pub fn AttachToActor(
+        child: *mut AActorOpaque,
+        parent: *mut AActorOpaque,
+        socket: *const c_char,
+        len: usize,
+    );
+    pub fn DetachFromActor(actor: *mut AActorOpaque);
+
+    pub fn SetActorLocationTeleport(
+        actor: *mut AActorOpaque,
+        position: Vector3,
+        rotation: Quaternion,
+    );
+
+    pub fn SetMaterialScalar(actor: *mut AActorOpaque, name: *const c_char, len: usize, value: f32);
+    pub fn SetMaterialVector(
+        actor: *mut AActorOpaque,
+        name: *const c_char,
+        len: usize,
+        value: Vector3,
+    );
+
     pub fn SetViewTarget(actor: *const AActorOpaque);
+
+    pub fn PollCollisionEvents(events: *mut CollisionEvent, len: *mut usize);
 }
 
 #[repr(C)]
 pub struct ActorFns {
     pub get_spatial_data: GetSpatialDataFn,
+    /// Writes the transform of `actors[i]` into `out[i]` for all `len` actors, which saves a call
+    /// per actor compared to `get_spatial_data`. Null if the engine plugin predates the bulk api.
+    // Not a type alias because cbindgen does not resolve aliases inside of an `Option`
+    pub get_spatial_data_bulk: Option<
+        unsafe extern "C" fn(actors: *const *const AActorOpaque, out: *mut SpatialData, len: usize),
+    >,
     pub set_spatial_data: SetSpatialDataFn,
     pub set_entity_for_actor: SetEntityForActorFn,
     pub get_actor_components: GetActorComponentsFn,
@@ -105,4 +268,18 @@ pub struct ActorFns {
     pub set_owner: SetOwnerFn,
     pub is_moveable: IsMoveableFn,
     pub destroy_actor: DestroyActorFn,
+    pub poll_collision_events: PollCollisionEventsFn,
+    pub get_actor_class_name: GetActorClassNameFn,
+    pub get_actor_tags: GetActorTagsFn,
+    pub get_actor_net_guid: GetActorNetGuidFn,
+    pub get_actor_net_role: GetActorNetRoleFn,
+    pub get_actor_bounds: GetActorBoundsFn,
+    pub get_socket_transform: GetSocketTransformFn,
+    pub set_actor_hidden: SetActorHiddenFn,
+    pub is_actor_hidden: IsActorHiddenFn,
+    pub attach_to_actor: AttachToActorFn,
+    pub detach_from_actor: DetachFromActorFn,
+    pub set_actor_location_teleport: SetActorLocationTeleportFn,
+    pub set_material_scalar: SetMaterialScalarFn,
+    pub set_material_vector: SetMaterialVectorFn,
 }
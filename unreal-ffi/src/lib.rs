@@ -1,4 +1,4 @@
-use glam::{Quat, Vec3};
+use glam::{EulerRot, Quat, Vec2, Vec3};
 use std::{ffi::c_void, os::raw::c_char};
 pub mod actor;
 pub mod physics;
@@ -9,10 +9,18 @@ pub use physics::*;
 pub use sound::*;
 
 #[repr(u8)]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum ResultCode {
     Success = 0,
     Panic = 1,
+    /// A pointer was null or a value was out of range
+    InvalidArgument = 2,
+    /// Called before the module was loaded
+    NotInitialized = 3,
+    /// The actor has no entity
+    ActorNotFound = 4,
+    /// No component is registered with that UUID
+    NotFound = 5,
 }
 #[repr(u32)]
 #[derive(Copy, Clone)]
@@ -35,6 +43,14 @@ impl<'a> From<&'a str> for Utf8Str {
     }
 }
 
+/// A key of a float curve asset
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone)]
+pub struct CurveKey {
+    pub time: f32,
+    pub value: f32,
+}
+
 #[repr(C)]
 #[derive(Default, Debug, Copy, Clone)]
 pub struct Quaternion {
@@ -43,13 +59,21 @@ pub struct Quaternion {
     z: f32,
     w: f32,
 }
+/// Mirrors `FRotator`, in degrees. Uses the same convention as `unreal_api::math::QuatExt`.
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub struct Rotator {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+}
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct Entity {
     pub id: u64,
 }
 #[repr(C)]
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -79,6 +103,13 @@ impl Color {
     };
 }
 
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Vector2 {
+    pub x: f32,
+    pub y: f32,
+}
+
 #[repr(C)]
 #[derive(Default, Debug, Copy, Clone)]
 pub struct Vector3 {
@@ -110,6 +141,41 @@ impl From<Quaternion> for Quat {
     }
 }
 
+impl From<Rotator> for Quat {
+    fn from(val: Rotator) -> Self {
+        // Unreal rotates around Y and X in the opposite direction of the right hand rule
+        Quat::from_euler(
+            EulerRot::ZYX,
+            val.yaw.to_radians(),
+            -val.pitch.to_radians(),
+            -val.roll.to_radians(),
+        )
+    }
+}
+
+impl From<Quat> for Rotator {
+    fn from(v: Quat) -> Self {
+        let (yaw, pitch, roll) = v.to_euler(EulerRot::ZYX);
+        Rotator {
+            pitch: -pitch.to_degrees(),
+            yaw: yaw.to_degrees(),
+            roll: -roll.to_degrees(),
+        }
+    }
+}
+
+impl From<Vector2> for Vec2 {
+    fn from(val: Vector2) -> Self {
+        Vec2::new(val.x, val.y)
+    }
+}
+
+impl From<Vec2> for Vector2 {
+    fn from(v: Vec2) -> Self {
+        Vector2 { x: v.x, y: v.y }
+    }
+}
+
 impl From<Vector3> for Vec3 {
     fn from(val: Vector3) -> Self {
         Vec3::new(val.x, val.y, val.z)
@@ -135,18 +201,98 @@ pub type UClassOpague = c_void;
 pub type UObjectOpague = c_void;
 pub type USoundBaseOpague = c_void;
 
-pub type LogFn = extern "C" fn(*const c_char, i32);
+/// Maps to the unreal log verbosity of the same name
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LogLevel {
+    Display,
+    Warning,
+    Error,
+}
+
+/// `s` is UTF-8 with `len` bytes and is not null terminated
+pub type LogFn = extern "C" fn(level: LogLevel, s: *const c_char, len: i32);
 pub type IterateActorsFn = unsafe extern "C" fn(array: *mut *mut AActorOpaque, len: *mut u64);
-pub type GetActionStateFn =
-    unsafe extern "C" fn(name: *const c_char, len: usize, state: ActionState, out: *mut u32);
-pub type GetAxisValueFn = unsafe extern "C" fn(name: *const c_char, len: usize, value: &mut f32);
+/// `player_index` is the index of the local player, 0 for single player games
+pub type GetActionStateFn = unsafe extern "C" fn(
+    player_index: u32,
+    name: *const c_char,
+    len: usize,
+    state: ActionState,
+    out: *mut u32,
+);
+pub type GetAxisValueFn =
+    unsafe extern "C" fn(player_index: u32, name: *const c_char, len: usize, value: &mut f32);
 pub type SpawnActorFn = unsafe extern "C" fn(
     actor_class: ActorClass,
     position: Vector3,
     rotation: Quaternion,
     scale: Vector3,
 ) -> *mut AActorOpaque;
+/// Tells unreal that the component `uuid` of the entity of `actor` changed this frame
+pub type OnComponentChangedFn = unsafe extern "C" fn(actor: *const AActorOpaque, uuid: Uuid);
+
+/// One change that is reported by `UnrealBindings::on_components_changed_bulk`
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ComponentChange {
+    pub actor: *const AActorOpaque,
+    pub uuid: Uuid,
+}
+/// Spawns an actor of any class, for example a blueprint class returned by `LoadClassFn`
+pub type SpawnActorFromClassFn = unsafe extern "C" fn(
+    class: *const UClassOpague,
+    position: Vector3,
+    rotation: Quaternion,
+    scale: Vector3,
+) -> *mut AActorOpaque;
+/// Loads the class at `path`, e.g. `/Game/BP_Enemy.BP_Enemy_C`. Returns null if there is no
+/// actor class at `path`. Loaded classes are never garbage collected.
+pub type LoadClassFn = unsafe extern "C" fn(path: Utf8Str) -> *mut UClassOpague;
+/// Copies the keys of the `UCurveFloat` at `path` into `keys`, sorted by time. Call it with a null
+/// `keys` to get the number of keys in `len`. Returns 0 if there is no float curve at `path`.
+pub type LoadCurveKeysFn =
+    unsafe extern "C" fn(path: Utf8Str, keys: *mut CurveKey, len: *mut usize) -> u32;
+/// Adds `name` to the name table of unreal if necessary and returns its id. The same string
+/// always returns the same id.
+pub type RegisterNameFn = unsafe extern "C" fn(name: *const c_char, len: usize) -> u32;
 pub type GetMouseDeltaFn = unsafe extern "C" fn(x: &mut f32, y: &mut f32);
+pub type GetMouseStateFn = unsafe extern "C" fn(state: *mut MouseState);
+/// Hides the cursor and locks it to the viewport while `captured` is 1
+pub type SetMouseCapturedFn = unsafe extern "C" fn(captured: u32);
+/// Uses the camera of the first local player. Returns 0 if `world` is behind the camera.
+pub type ProjectWorldToScreenFn = unsafe extern "C" fn(world: Vector3, screen: *mut Vector2) -> u32;
+/// `direction` is normalized. Returns 0 if there is no local player.
+pub type DeprojectScreenToWorldFn =
+    unsafe extern "C" fn(screen: Vector2, origin: *mut Vector3, direction: *mut Vector3) -> u32;
+/// The effective time dilation of the world, 1 is normal speed. The `dt` that is passed to
+/// `TickFn` is already scaled by it.
+pub type GetTimeDilationFn = unsafe extern "C" fn() -> f32;
+/// The size of the game viewport in pixels. Writes 0 for the size if there is no viewport or it is
+/// minimized, `dpi_scale` is 1 in that case.
+pub type GetViewportSizeFn =
+    unsafe extern "C" fn(width: *mut u32, height: *mut u32, dpi_scale: *mut f32);
+/// Allocates the contents of the game config into `data`. Leaves `data` empty if there is no
+/// config.
+pub type GetConfigBlobFn = unsafe extern "C" fn(data: *mut RustAlloc);
+/// Fills `state` for the gamepad of the local player at `index`. `connected` is set to 0 if there
+/// is no such player or if no gamepad is attached at all.
+pub type GetGamepadStateFn = unsafe extern "C" fn(index: u32, state: *mut GamepadState);
+/// Only has an effect on camera actors
+pub type SetCameraProjectionFn =
+    unsafe extern "C" fn(actor: *mut AActorOpaque, projection: CameraProjection);
+/// A `duration` <= 0 only draws for a single frame
+pub type DebugDrawLineFn =
+    unsafe extern "C" fn(start: Vector3, end: Vector3, color: Color, thickness: f32, duration: f32);
+pub type DebugDrawSphereFn =
+    unsafe extern "C" fn(center: Vector3, radius: f32, color: Color, duration: f32);
+pub type DebugDrawBoxFn = unsafe extern "C" fn(
+    center: Vector3,
+    extent: Vector3,
+    rotation: Quaternion,
+    color: Color,
+    duration: f32,
+);
 pub type VisualLogSegmentFn =
     unsafe extern "C" fn(owner: *const AActorOpaque, start: Vector3, end: Vector3, color: Color);
 pub type VisualLogCapsuleFn = unsafe extern "C" fn(
@@ -169,17 +315,56 @@ pub type VisualLogLocationFn = unsafe extern "C" fn(
 
 extern "C" {
     pub fn TickActor(actor: *mut AActorOpaque, dt: f32);
-    pub fn Log(s: *const c_char, len: i32);
+    pub fn Log(level: LogLevel, s: *const c_char, len: i32);
     pub fn IterateActors(array: *mut *mut AActorOpaque, len: *mut u64);
-    pub fn GetActionState(name: *const c_char, len: usize, state: ActionState, out: *mut u32);
-    pub fn GetAxisValue(name: *const c_char, len: usize, value: &mut f32);
+    pub fn GetActionState(
+        player_index: u32,
+        name: *const c_char,
+        len: usize,
+        state: ActionState,
+        out: *mut u32,
+    );
+    pub fn GetAxisValue(player_index: u32, name: *const c_char, len: usize, value: &mut f32);
     pub fn SpawnActor(
         actor_class: ActorClass,
         position: Vector3,
         rotation: Quaternion,
         scale: Vector3,
     ) -> *mut AActorOpaque;
+    pub fn SpawnActorFromClass(
+        class: *const UClassOpague,
+        position: Vector3,
+        rotation: Quaternion,
+        scale: Vector3,
+    ) -> *mut AActorOpaque;
+    pub fn LoadClass(path: Utf8Str) -> *mut UClassOpague;
+    pub fn LoadCurveKeys(path: Utf8Str, keys: *mut CurveKey, len: *mut usize) -> u32;
+    pub fn RegisterName(name: *const c_char, len: usize) -> u32;
+    pub fn OnComponentChanged(actor: *const AActorOpaque, uuid: Uuid);
+    pub fn OnComponentsChangedBulk(changes: *const ComponentChange, len: usize);
     pub fn GetMouseDelta(x: &mut f32, y: &mut f32);
+    pub fn GetMouseState(state: *mut MouseState);
+    pub fn SetMouseCaptured(captured: u32);
+    pub fn ProjectWorldToScreen(world: Vector3, screen: *mut Vector2) -> u32;
+    pub fn GetViewportSize(width: *mut u32, height: *mut u32, dpi_scale: *mut f32);
+    pub fn GetConfigBlob(data: *mut RustAlloc);
+    pub fn GetTimeDilation() -> f32;
+    pub fn DeprojectScreenToWorld(
+        screen: Vector2,
+        origin: *mut Vector3,
+        direction: *mut Vector3,
+    ) -> u32;
+    pub fn GetGamepadState(index: u32, state: *mut GamepadState);
+    pub fn SetCameraProjection(actor: *mut AActorOpaque, projection: CameraProjection);
+    pub fn DebugDrawLine(start: Vector3, end: Vector3, color: Color, thickness: f32, duration: f32);
+    pub fn DebugDrawSphere(center: Vector3, radius: f32, color: Color, duration: f32);
+    pub fn DebugDrawBox(
+        center: Vector3,
+        extent: Vector3,
+        rotation: Quaternion,
+        color: Color,
+        duration: f32,
+    );
 
     pub fn VisualLogSegment(owner: *const AActorOpaque, start: Vector3, end: Vector3, color: Color);
     pub fn VisualLogCapsule(
@@ -209,7 +394,29 @@ pub struct UnrealBindings {
     pub get_action_state: GetActionStateFn,
     pub get_axis_value: GetAxisValueFn,
     pub spawn_actor: SpawnActorFn,
+    pub spawn_actor_from_class: SpawnActorFromClassFn,
+    pub load_class: LoadClassFn,
+    pub load_curve_keys: LoadCurveKeysFn,
+    pub register_name: RegisterNameFn,
+    pub on_component_changed: OnComponentChangedFn,
+    /// Same as `on_component_changed` for all `len` changes of a frame at once. Null if the
+    /// engine plugin predates the bulk api.
+    // Not a type alias because cbindgen does not resolve aliases inside of an `Option`
+    pub on_components_changed_bulk:
+        Option<unsafe extern "C" fn(changes: *const ComponentChange, len: usize)>,
     pub get_mouse_delta: GetMouseDeltaFn,
+    pub get_mouse_state: GetMouseStateFn,
+    pub set_mouse_captured: SetMouseCapturedFn,
+    pub project_world_to_screen: ProjectWorldToScreenFn,
+    pub deproject_screen_to_world: DeprojectScreenToWorldFn,
+    pub get_viewport_size: GetViewportSizeFn,
+    pub get_config_blob: GetConfigBlobFn,
+    pub get_time_dilation: GetTimeDilationFn,
+    pub get_gamepad_state: GetGamepadStateFn,
+    pub set_camera_projection: SetCameraProjectionFn,
+    pub debug_draw_line: DebugDrawLineFn,
+    pub debug_draw_sphere: DebugDrawSphereFn,
+    pub debug_draw_box: DebugDrawBoxFn,
     pub visual_log_segment: VisualLogSegmentFn,
     pub visual_log_capsule: VisualLogCapsuleFn,
     pub visual_log_location: VisualLogLocationFn,
@@ -227,7 +434,95 @@ pub enum ActionState {
     Held = 2,
 }
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftX = 0,
+    LeftY = 1,
+    RightX = 2,
+    RightY = 3,
+    LeftTrigger = 4,
+    RightTrigger = 5,
+}
+
+/// The value of each variant is the bit index inside `GamepadState::buttons`
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    FaceBottom = 0,
+    FaceRight = 1,
+    FaceLeft = 2,
+    FaceTop = 3,
+    LeftShoulder = 4,
+    RightShoulder = 5,
+    LeftThumb = 6,
+    RightThumb = 7,
+    Start = 8,
+    Back = 9,
+    DPadUp = 10,
+    DPadDown = 11,
+    DPadLeft = 12,
+    DPadRight = 13,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct GamepadState {
+    pub connected: u32,
+    pub left_x: f32,
+    pub left_y: f32,
+    pub right_x: f32,
+    pub right_y: f32,
+    pub left_trigger: f32,
+    pub right_trigger: f32,
+    pub buttons: u32,
+}
+
+impl GamepadState {
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        match axis {
+            GamepadAxis::LeftX => self.left_x,
+            GamepadAxis::LeftY => self.left_y,
+            GamepadAxis::RightX => self.right_x,
+            GamepadAxis::RightY => self.right_y,
+            GamepadAxis::LeftTrigger => self.left_trigger,
+            GamepadAxis::RightTrigger => self.right_trigger,
+        }
+    }
+
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons & (1 << button as u32) != 0
+    }
+}
+
+/// `position` is the cursor position in viewport pixels, and is only valid if `has_position` is
+/// 1. `scroll` is the mouse wheel movement of the current frame.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MouseState {
+    pub has_position: u32,
+    pub position: Vector2,
+    pub scroll: f32,
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ProjectionMode {
+    Perspective = 0,
+    Orthographic = 1,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraProjection {
+    pub mode: ProjectionMode,
+    /// Horizontal field of view in degrees, only used for `ProjectionMode::Perspective`
+    pub fov: f32,
+    /// Width of the view in world units, only used for `ProjectionMode::Orthographic`
+    pub ortho_width: f32,
+}
+
+#[repr(u32)]
+#[derive(Debug, Copy, Clone)]
 pub enum ActorClass {
     RustActor = 0,
     CameraActor = 1,
@@ -274,8 +569,18 @@ pub struct Uuid {
 pub type EntryUnrealBindingsFn =
     unsafe extern "C" fn(bindings: UnrealBindings, rust_bindings: *mut RustBindings) -> u32;
 pub type BeginPlayFn = unsafe extern "C" fn() -> ResultCode;
+pub type EndPlayFn = unsafe extern "C" fn() -> ResultCode;
 pub type TickFn = unsafe extern "C" fn(dt: f32) -> ResultCode;
 pub type RetrieveUuids = unsafe extern "C" fn(ptr: *mut Uuid, len: *mut usize);
+/// Called twice, first with `info.fields` set to null, which only writes the name and the number
+/// of fields into `info`. The second call fills `info.fields` with up to `info.len` fields.
+/// Returns 0 if `uuid` is not a registered component.
+pub type RetrieveComponentInfo = unsafe extern "C" fn(uuid: Uuid, info: *mut ComponentInfo) -> u32;
+/// Called before a hot reload. The first call with a null `data` writes the size of the state
+/// into `len`, the second call copies up to `len` bytes into `data`.
+pub type PrepareReloadFn = unsafe extern "C" fn(data: *mut u8, len: *mut usize) -> ResultCode;
+/// Called on the newly loaded module with the state from `PrepareReloadFn`
+pub type FinishReloadFn = unsafe extern "C" fn(data: *const u8, len: usize) -> ResultCode;
 pub type GetVelocityRustFn =
     unsafe extern "C" fn(actor: *const AActorOpaque, velocity: &mut Vector3);
 
@@ -319,14 +624,29 @@ pub struct ActorDestroyEvent {
 #[repr(C)]
 pub struct RustBindings {
     pub retrieve_uuids: RetrieveUuids,
+    pub retrieve_component_info: RetrieveComponentInfo,
+    pub prepare_reload: PrepareReloadFn,
+    pub finish_reload: FinishReloadFn,
     pub tick: TickFn,
     pub begin_play: BeginPlayFn,
+    pub end_play: EndPlayFn,
     pub unreal_event: UnrealEventFn,
+    pub unregister_actor: UnregisterActorFn,
+    pub remove_component: RemoveComponentFn,
+    pub add_component: AddComponentFn,
     pub reflection_fns: ReflectionFns,
     pub allocate_fns: AllocateFns,
 }
 
 pub type UnrealEventFn = unsafe extern "C" fn(ty: *const EventType, data: *const c_void);
+pub type UnregisterActorFn = unsafe extern "C" fn(actor: *mut AActorOpaque) -> ResultCode;
+/// Removes the component `uuid` from the entity of `actor`. Succeeds if the entity does not have
+/// the component.
+pub type RemoveComponentFn =
+    unsafe extern "C" fn(actor: *mut AActorOpaque, uuid: Uuid) -> ResultCode;
+/// Inserts the default value of the component `uuid` into the entity of `actor`, replacing the
+/// existing one. Returns `InvalidArgument` if the component was registered without a default.
+pub type AddComponentFn = unsafe extern "C" fn(actor: *mut AActorOpaque, uuid: Uuid) -> ResultCode;
 
 #[repr(u32)]
 pub enum ReflectionType {
@@ -337,6 +657,20 @@ pub enum ReflectionType {
     UClass,
     USound,
     Composite,
+    Entity,
+}
+
+#[repr(C)]
+pub struct ComponentField {
+    pub name: Utf8Str,
+    pub ty: ReflectionType,
+}
+
+#[repr(C)]
+pub struct ComponentInfo {
+    pub name: Utf8Str,
+    pub fields: *mut ComponentField,
+    pub len: usize,
 }
 
 pub type NumberOfFieldsFn = unsafe extern "C" fn(uuid: Uuid, out: *mut u32) -> u32;
@@ -495,3 +829,74 @@ pub struct EditorComponentFns {
     pub get_editor_component_float: GetEditorComponentFloatFn,
     pub get_editor_component_uobject: GetEditorComponentUObjectFn,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::{align_of, size_of};
+
+    #[test]
+    fn camera_projection_layout() {
+        // Has to match `CameraProjection` in Bindings.h
+        assert_eq!(size_of::<ProjectionMode>(), 4);
+        assert_eq!(size_of::<CameraProjection>(), 12);
+        assert_eq!(align_of::<CameraProjection>(), 4);
+
+        let projection = CameraProjection {
+            mode: ProjectionMode::Orthographic,
+            fov: 90.0,
+            ortho_width: 512.0,
+        };
+        let base = &projection as *const CameraProjection as usize;
+        assert_eq!(std::ptr::addr_of!(projection.mode) as usize - base, 0);
+        assert_eq!(std::ptr::addr_of!(projection.fov) as usize - base, 4);
+        assert_eq!(
+            std::ptr::addr_of!(projection.ortho_width) as usize - base,
+            8
+        );
+        let words: [u32; 3] = unsafe { std::mem::transmute(projection) };
+        assert_eq!(words, [1, 90.0f32.to_bits(), 512.0f32.to_bits()]);
+    }
+
+    #[test]
+    fn vector2_round_trip() {
+        assert_eq!(size_of::<Vector2>(), 8);
+        let v = Vec2::new(3.0, -4.0);
+        let ffi: Vector2 = v.into();
+        assert_eq!((ffi.x, ffi.y), (3.0, -4.0));
+        assert_eq!(Vec2::from(ffi), v);
+
+        let normalized = v.normalize();
+        assert!((normalized.length() - 1.0).abs() < 1e-6);
+        assert!(normalized.abs_diff_eq(Vec2::new(0.6, -0.8), 1e-6));
+        assert_eq!(Vec2::ZERO.normalize_or_zero(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn rotator_round_trip() {
+        for &(pitch, yaw, roll) in &[
+            (0.0, 0.0, 0.0),
+            (30.0, -45.0, 10.0),
+            (-60.0, 170.0, -120.0),
+            (85.0, 20.0, 5.0),
+        ] {
+            let rotator = Rotator { pitch, yaw, roll };
+            let back = Rotator::from(Quat::from(rotator));
+            assert!((back.pitch - pitch).abs() < 1e-3, "{:?}", back);
+            assert!((back.yaw - yaw).abs() < 1e-3, "{:?}", back);
+            assert!((back.roll - roll).abs() < 1e-3, "{:?}", back);
+        }
+
+        // Positive yaw turns +X towards +Y, positive pitch looks up
+        let yaw = Quat::from(Rotator {
+            yaw: 90.0,
+            ..Default::default()
+        });
+        assert!((yaw * Vec3::X).abs_diff_eq(Vec3::Y, 1e-6));
+        let pitch = Quat::from(Rotator {
+            pitch: 90.0,
+            ..Default::default()
+        });
+        assert!((pitch * Vec3::X).abs_diff_eq(Vec3::Z, 1e-6));
+    }
+}
@@ -61,6 +61,46 @@ pub struct LineTraceParams {
     pub ignored_actors_len: usize,
 }
 
+/// Mirrors the common `ECollisionChannel`s in unreal
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CollisionChannel {
+    WorldStatic = 0,
+    WorldDynamic = 1,
+    Pawn = 2,
+    #[default]
+    Visibility = 3,
+    Camera = 4,
+    PhysicsBody = 5,
+    Vehicle = 6,
+    Destructible = 7,
+}
+
+impl CollisionChannel {
+    /// The bit of this channel in `CollisionResponse::channels` and `overlap_channels`
+    pub fn mask(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// `channels` has a bit set for every `CollisionChannel` that the primitive blocks and
+/// `overlap_channels` for every channel that it overlaps. Blocking wins if a channel is in both,
+/// channels in neither are ignored.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CollisionResponse {
+    pub object_type: CollisionChannel,
+    pub channels: u32,
+    pub overlap_channels: u32,
+}
+
+#[repr(C)]
+pub struct TraceParams {
+    pub ignored_actors: *const *mut AActorOpaque,
+    pub ignored_actors_len: usize,
+    pub channel: CollisionChannel,
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct OverlapResult {
@@ -89,6 +129,8 @@ pub struct HitResult {
     pub impact_location: Vector3,
     pub pentration_depth: f32,
     pub start_penetrating: u32,
+    /// Time of impact along a sweep in `[0, 1]`
+    pub time: f32,
 }
 
 impl Default for HitResult {
@@ -103,6 +145,7 @@ impl Default for HitResult {
             impact_normal: Default::default(),
             pentration_depth: Default::default(),
             start_penetrating: Default::default(),
+            time: Default::default(),
         }
     }
 }
@@ -112,17 +155,37 @@ pub type GetVelocityFn = unsafe extern "C" fn(primitive: *const UPrimtiveOpaque)
 pub type SetVelocityFn = unsafe extern "C" fn(primitive: *mut UPrimtiveOpaque, velocity: Vector3);
 
 pub type IsSimulatingFn = unsafe extern "C" fn(primitive: *const UPrimtiveOpaque) -> u32;
+pub type SetSimulatePhysicsFn =
+    unsafe extern "C" fn(primitive: *mut UPrimtiveOpaque, simulate: u32);
 
 pub type AddForceFn = unsafe extern "C" fn(actor: *mut UPrimtiveOpaque, force: Vector3);
 
 pub type AddImpulseFn = unsafe extern "C" fn(actor: *mut UPrimtiveOpaque, force: Vector3);
 
+/// Angular velocities are in radians per second
+pub type GetAngularVelocityFn = unsafe extern "C" fn(primitive: *const UPrimtiveOpaque) -> Vector3;
+
+pub type SetAngularVelocityFn =
+    unsafe extern "C" fn(primitive: *mut UPrimtiveOpaque, angular_velocity: Vector3);
+
+pub type AddTorqueFn = unsafe extern "C" fn(primitive: *mut UPrimtiveOpaque, torque: Vector3);
+
+pub type AddAngularImpulseFn =
+    unsafe extern "C" fn(primitive: *mut UPrimtiveOpaque, impulse: Vector3);
+
 pub type LineTraceFn = unsafe extern "C" fn(
     start: Vector3,
     end: Vector3,
     params: LineTraceParams,
     result: &mut HitResult,
 ) -> u32;
+pub type RaycastFn = unsafe extern "C" fn(
+    start: Vector3,
+    end: Vector3,
+    params: TraceParams,
+    result: &mut HitResult,
+) -> u32;
+
 pub type GetBoundingBoxExtentFn =
     unsafe extern "C" fn(primitive: *const UPrimtiveOpaque) -> Vector3;
 
@@ -135,14 +198,31 @@ pub type SweepFn = unsafe extern "C" fn(
     result: &mut HitResult,
 ) -> u32;
 
+pub type SweepByChannelFn = unsafe extern "C" fn(
+    start: Vector3,
+    end: Vector3,
+    rotation: Quaternion,
+    params: TraceParams,
+    collision_shape: CollisionShape,
+    result: &mut HitResult,
+) -> u32;
+
+/// Writes up to `max_results` overlaps with `params.channel` into `results` and returns how many
+/// were written. An actor shows up once for every overlapping primitive.
 pub type OverlapMultiFn = unsafe extern "C" fn(
     collision_shape: CollisionShape,
     position: Vector3,
     rotation: Quaternion,
-    params: LineTraceParams,
+    params: TraceParams,
     max_results: usize,
-    result: *mut *mut OverlapResult,
-) -> u32;
+    results: *mut OverlapResult,
+) -> usize;
+
+pub type GetCollisionResponseFn =
+    unsafe extern "C" fn(primitive: *const UPrimtiveOpaque, response: *mut CollisionResponse);
+
+pub type SetCollisionResponseFn =
+    unsafe extern "C" fn(primitive: *mut UPrimtiveOpaque, response: CollisionResponse);
 
 pub type GetCollisionShapeFn =
     unsafe extern "C" fn(primitive: *const UPrimtiveOpaque, shape: *mut CollisionShape) -> u32;
@@ -163,11 +243,20 @@ extern "C" {
     pub fn SetVelocity(primitive: *mut UPrimtiveOpaque, velocity: Vector3);
 
     pub fn IsSimulating(primitive: *const UPrimtiveOpaque) -> u32;
+    pub fn SetSimulatePhysics(primitive: *mut UPrimtiveOpaque, simulate: u32);
 
     pub fn AddForce(actor: *mut UPrimtiveOpaque, force: Vector3);
 
     pub fn AddImpulse(actor: *mut UPrimtiveOpaque, force: Vector3);
 
+    pub fn GetAngularVelocity(primitive: *const UPrimtiveOpaque) -> Vector3;
+
+    pub fn SetAngularVelocity(primitive: *mut UPrimtiveOpaque, angular_velocity: Vector3);
+
+    pub fn AddTorque(primitive: *mut UPrimtiveOpaque, torque: Vector3);
+
+    pub fn AddAngularImpulse(primitive: *mut UPrimtiveOpaque, impulse: Vector3);
+
     pub fn LineTrace(
         start: Vector3,
         end: Vector3,
@@ -175,6 +264,13 @@ extern "C" {
         result: &mut HitResult,
     ) -> u32;
 
+    pub fn Raycast(
+        start: Vector3,
+        end: Vector3,
+        params: TraceParams,
+        result: &mut HitResult,
+    ) -> u32;
+
     pub fn GetBoundingBoxExtent(primitive: *const UPrimtiveOpaque) -> Vector3;
 
     pub fn Sweep(
@@ -186,6 +282,15 @@ extern "C" {
         result: &mut HitResult,
     ) -> u32;
 
+    pub fn SweepByChannel(
+        start: Vector3,
+        end: Vector3,
+        rotation: Quaternion,
+        params: TraceParams,
+        collision_shape: CollisionShape,
+        result: &mut HitResult,
+    ) -> u32;
+
     pub fn SweepMulti(
         start: Vector3,
         end: Vector3,
@@ -200,12 +305,19 @@ extern "C" {
         collision_shape: CollisionShape,
         position: Vector3,
         rotation: Quaternion,
-        params: LineTraceParams,
+        params: TraceParams,
         max_results: usize,
-        result: *mut *mut OverlapResult,
-    ) -> u32;
+        results: *mut OverlapResult,
+    ) -> usize;
 
     pub fn GetCollisionShape(primitive: *const UPrimtiveOpaque, shape: *mut CollisionShape) -> u32;
+
+    pub fn GetCollisionResponse(
+        primitive: *const UPrimtiveOpaque,
+        response: *mut CollisionResponse,
+    );
+
+    pub fn SetCollisionResponse(primitive: *mut UPrimtiveOpaque, response: CollisionResponse);
 }
 
 #[repr(C)]
@@ -221,4 +333,13 @@ pub struct PhysicsFns {
     pub sweep_multi: SweepMultiFn,
     pub overlap_multi: OverlapMultiFn,
     pub get_collision_shape: GetCollisionShapeFn,
+    pub raycast: RaycastFn,
+    pub sweep_by_channel: SweepByChannelFn,
+    pub get_angular_velocity: GetAngularVelocityFn,
+    pub set_angular_velocity: SetAngularVelocityFn,
+    pub add_torque: AddTorqueFn,
+    pub add_angular_impulse: AddAngularImpulseFn,
+    pub get_collision_response: GetCollisionResponseFn,
+    pub set_collision_response: SetCollisionResponseFn,
+    pub set_simulate_physics: SetSimulatePhysicsFn,
 }
@@ -1,4 +1,4 @@
-use crate::{Quaternion, USoundBaseOpague, Vector3};
+use crate::{Quaternion, USoundBaseOpague, Utf8Str, Vector3};
 
 #[repr(C)]
 pub struct SoundSettings {
@@ -21,6 +21,8 @@ extern "C" {
         rotation: Quaternion,
         settings: *const SoundSettings,
     );
+    pub fn PlaySound2d(sound: *const USoundBaseOpague, settings: *const SoundSettings);
+    pub fn LoadSound(path: Utf8Str) -> *mut USoundBaseOpague;
 }
 pub type PlaySoundAtLocationFn = unsafe extern "C" fn(
     sound: *const USoundBaseOpague,
@@ -29,7 +31,14 @@ pub type PlaySoundAtLocationFn = unsafe extern "C" fn(
     settings: *const SoundSettings,
 );
 
+pub type PlaySound2dFn =
+    unsafe extern "C" fn(sound: *const USoundBaseOpague, settings: *const SoundSettings);
+/// Returns null if there is no sound asset at `path`. Loaded sounds are never garbage collected.
+pub type LoadSoundFn = unsafe extern "C" fn(path: Utf8Str) -> *mut USoundBaseOpague;
+
 #[repr(C)]
 pub struct SoundFns {
     pub play_sound_at_location: PlaySoundAtLocationFn,
+    pub play_sound_2d: PlaySound2dFn,
+    pub load_sound: LoadSoundFn,
 }
@@ -16,6 +16,7 @@ pub struct USound {
 unsafe impl Send for USound {}
 unsafe impl Sync for USound {}
 
+#[derive(Debug, Copy, Clone)]
 pub enum ReflectValue {
     Float(f32),
     Vector3(Vec3),
@@ -23,9 +24,11 @@ pub enum ReflectValue {
     Quat(Quat),
     UClass(UClass),
     USound(USound),
+    Entity(Entity),
     Composite,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ReflectType {
     Float,
     Vector3,
@@ -33,6 +36,7 @@ pub enum ReflectType {
     Quat,
     UClass,
     USound,
+    Entity,
     Composite,
 }
 
@@ -53,6 +57,21 @@ pub trait ReflectDyn {
     fn get_field_value(&self, _world: &World, _entity: Entity, _idx: u32) -> Option<ReflectValue> {
         None
     }
+    /// Returns false if the entity did not have the component
+    fn remove_component(&self, _world: &mut World, _entity: Entity) -> bool {
+        false
+    }
+    /// Returns false without changing the component if the field does not exist or `value` has
+    /// the wrong type
+    fn set_field_value(
+        &self,
+        _world: &mut World,
+        _entity: Entity,
+        _idx: u32,
+        _value: ReflectValue,
+    ) -> bool {
+        false
+    }
     fn get_value(&self) -> ReflectValue;
 }
 
@@ -67,6 +86,13 @@ impl ReflectDyn for UClass {
 }
 impl ReflectStatic for UClass {
     const TYPE: ReflectType = ReflectType::UClass;
+
+    fn from_reflect_value(value: ReflectValue) -> Option<Self> {
+        match value {
+            ReflectValue::UClass(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 impl ReflectDyn for USound {
     fn name(&self) -> &'static str {
@@ -80,6 +106,13 @@ impl ReflectDyn for USound {
 
 impl ReflectStatic for USound {
     const TYPE: ReflectType = ReflectType::USound;
+
+    fn from_reflect_value(value: ReflectValue) -> Option<Self> {
+        match value {
+            ReflectValue::USound(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 
 impl ReflectDyn for Vec3 {
@@ -94,6 +127,13 @@ impl ReflectDyn for Vec3 {
 
 impl ReflectStatic for Vec3 {
     const TYPE: ReflectType = ReflectType::Vector3;
+
+    fn from_reflect_value(value: ReflectValue) -> Option<Self> {
+        match value {
+            ReflectValue::Vector3(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 
 impl ReflectDyn for Quat {
@@ -107,6 +147,13 @@ impl ReflectDyn for Quat {
 }
 impl ReflectStatic for Quat {
     const TYPE: ReflectType = ReflectType::Quat;
+
+    fn from_reflect_value(value: ReflectValue) -> Option<Self> {
+        match value {
+            ReflectValue::Quat(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 
 impl ReflectDyn for f32 {
@@ -121,6 +168,13 @@ impl ReflectDyn for f32 {
 
 impl ReflectStatic for f32 {
     const TYPE: ReflectType = ReflectType::Float;
+
+    fn from_reflect_value(value: ReflectValue) -> Option<Self> {
+        match value {
+            ReflectValue::Float(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 impl ReflectDyn for bool {
     fn name(&self) -> &'static str {
@@ -134,8 +188,44 @@ impl ReflectDyn for bool {
 
 impl ReflectStatic for bool {
     const TYPE: ReflectType = ReflectType::Bool;
+
+    fn from_reflect_value(value: ReflectValue) -> Option<Self> {
+        match value {
+            ReflectValue::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl ReflectDyn for Entity {
+    fn name(&self) -> &'static str {
+        "Entity"
+    }
+
+    fn get_value(&self) -> ReflectValue {
+        ReflectValue::Entity(*self)
+    }
+}
+
+impl ReflectStatic for Entity {
+    const TYPE: ReflectType = ReflectType::Entity;
+
+    fn from_reflect_value(value: ReflectValue) -> Option<Self> {
+        match value {
+            ReflectValue::Entity(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 
 pub trait ReflectStatic {
     const TYPE: ReflectType;
+
+    /// `None` if `value` holds a different type
+    fn from_reflect_value(_value: ReflectValue) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
@@ -7,10 +7,10 @@ use unreal_api::registry::USound;
 use unreal_api::sound::{play_sound_at_location, SoundSettings};
 use unreal_api::Component;
 use unreal_api::{
-    core::{ActorComponent, ActorPtr, CoreStage, ParentComponent, TransformComponent},
+    core::{ActorComponent, ActorPtr, CoreStage, Frame, ParentComponent, TransformComponent},
     ffi::{self, UClassOpague},
     input::Input,
-    math::{Quat, Vec3},
+    math::{damp, Quat, Vec3},
     module::{bindings, InitUserModule, Module, UserModule},
     register_components,
 };
@@ -55,6 +55,40 @@ impl CameraMode {
         };
     }
 }
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Projection {
+    /// `fov` is the horizontal field of view in degrees
+    Perspective {
+        fov: f32,
+    },
+    Orthographic {
+        width: f32,
+    },
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self::Perspective { fov: 90.0 }
+    }
+}
+
+impl From<Projection> for ffi::CameraProjection {
+    fn from(val: Projection) -> Self {
+        match val {
+            Projection::Perspective { fov } => ffi::CameraProjection {
+                mode: ffi::ProjectionMode::Perspective,
+                fov,
+                ortho_width: 0.0,
+            },
+            Projection::Orthographic { width } => ffi::CameraProjection {
+                mode: ffi::ProjectionMode::Orthographic,
+                fov: 0.0,
+                ortho_width: width,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Component)]
 #[uuid = "b6addc7d-03b1-4b06-9328-f26c71997ee6"]
 #[reflect(editor)]
@@ -81,6 +115,12 @@ pub struct CameraComponent {
     pub current_y: f32,
     #[reflect(skip)]
     pub mode: CameraMode,
+    #[reflect(skip)]
+    pub projection: Projection,
+    /// Time in seconds it takes `current_x/current_y` to cover ~63% of the way to `x/y`.
+    /// 0 snaps instantly.
+    pub camera_smoothing: f32,
+    pub smooth_rotation: bool,
 }
 
 pub struct PlayerInput;
@@ -227,26 +267,38 @@ fn update_controller_view(
         }
     }
 }
-fn rotate_camera(mut query: Query<(&mut TransformComponent, &mut CameraComponent)>) {
-    fn lerp(start: f32, end: f32, t: f32) -> f32 {
-        start * (1.0 - t) + end * t
-    }
-    let mut x = 0.0;
-    let mut y = 0.0;
-
+fn rotate_camera(input: Res<Input>, mut query: Query<&mut CameraComponent>) {
+    let delta = input.mouse_delta();
     let max_angle = 85.0f32.to_radians();
-    unsafe {
-        (bindings().get_mouse_delta)(&mut x, &mut y);
-    }
 
-    for (mut spatial, mut cam) in query.iter_mut() {
+    for mut cam in query.iter_mut() {
         let speed = 0.05;
-        cam.x += x * speed;
-        cam.y = f32::clamp(cam.y + y * speed, -max_angle, max_angle);
+        cam.x = wrap_angle(cam.x + delta.x * speed);
+        cam.y = f32::clamp(cam.y + delta.y * speed, -max_angle, max_angle);
+    }
+}
 
-        let smooth = 0.4;
-        cam.current_x = lerp(cam.current_x, cam.x, smooth);
-        cam.current_y = lerp(cam.current_y, cam.y, smooth);
+/// Wraps `angle` into `[-PI, PI)`
+fn wrap_angle(angle: f32) -> f32 {
+    use std::f32::consts::{PI, TAU};
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+fn smooth_camera(
+    frame: Res<Frame>,
+    mut query: Query<(&mut TransformComponent, &mut CameraComponent)>,
+) {
+    for (mut spatial, mut cam) in query.iter_mut() {
+        if cam.smooth_rotation && cam.camera_smoothing > 0.0 {
+            let rate = 1.0 / cam.camera_smoothing;
+            // Take the short way around when the yaw crosses +-180 degrees
+            let target_x = cam.current_x + wrap_angle(cam.x - cam.current_x);
+            cam.current_x = wrap_angle(damp(cam.current_x, target_x, rate, frame.dt));
+            cam.current_y = damp(cam.current_y, cam.y, rate, frame.dt);
+        } else {
+            cam.current_x = wrap_angle(cam.x);
+            cam.current_y = cam.y;
+        }
 
         spatial.rotation =
             Quat::from_rotation_z(cam.current_x) * Quat::from_rotation_y(-cam.current_y);
@@ -278,7 +330,11 @@ fn spawn_camera(
                 ActorComponent {
                     actor: ActorPtr(actor),
                 },
-                CameraComponent::default(),
+                CameraComponent {
+                    camera_smoothing: 0.03,
+                    smooth_rotation: true,
+                    ..Default::default()
+                },
                 ParentComponent { parent: entity },
             ));
         }
@@ -308,6 +364,16 @@ fn update_camera(
     }
 }
 
+fn upload_camera_projection(
+    query: Query<(&ActorComponent, &CameraComponent), Changed<CameraComponent>>,
+) {
+    for (actor, camera) in query.iter() {
+        unsafe {
+            (bindings().set_camera_projection)(actor.actor.0, camera.projection.into());
+        }
+    }
+}
+
 pub struct MyModule;
 
 impl InitUserModule for MyModule {
@@ -325,6 +391,7 @@ impl UserModule for MyModule {
             => module
 
         };
+        module.register_default_component::<CameraComponent>();
 
         module
             .add_plugin(MovementPlugin)
@@ -341,11 +408,145 @@ impl UserModule for MyModule {
                     .with_system(spawn_camera)
                     .with_system(update_controller_view)
                     .with_system(rotate_camera)
-                    .with_system(update_camera.after(rotate_camera))
+                    .with_system(smooth_camera.after(rotate_camera))
+                    .with_system(update_camera.after(smooth_camera))
                     .with_system(toggle_camera)
                     .with_system(play_sound_on_hit),
+            )
+            .add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                SystemSet::new().with_system(upload_camera_projection),
             );
     }
 }
 
 unreal_api::implement_unreal_module!(MyModule);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::schedule::SystemStage;
+    use unreal_api::{
+        api::entity_for_actor,
+        core::{ActorSpawnedEvent, UnrealCore},
+        testing::{self, Call},
+        TypeUuid, Uuid,
+    };
+
+    #[test]
+    fn projection_is_uploaded() {
+        testing::install();
+        let actor = ActorPtr(testing::spawn_actor());
+        let mut world = World::new();
+        world.spawn().insert_bundle((
+            ActorComponent { actor },
+            CameraComponent {
+                projection: Projection::Orthographic { width: 512.0 },
+                ..Default::default()
+            },
+        ));
+        let mut stage = SystemStage::single_threaded().with_system(upload_camera_projection);
+        stage.run(&mut world);
+        // Only uploaded again after the component changed
+        world.clear_trackers();
+        stage.run(&mut world);
+
+        assert_eq!(
+            testing::calls(),
+            vec![Call::SetCameraProjection {
+                actor: actor.0,
+                projection: ffi::CameraProjection {
+                    mode: ffi::ProjectionMode::Orthographic,
+                    fov: 0.0,
+                    ortho_width: 512.0,
+                },
+            }]
+        );
+    }
+
+    fn run_smoothing(camera: CameraComponent, frames: usize) -> CameraComponent {
+        let mut world = World::new();
+        world.insert_resource(Frame {
+            dt: 1.0 / 60.0,
+            ..Default::default()
+        });
+        let entity = world
+            .spawn()
+            .insert_bundle((TransformComponent::default(), camera))
+            .id();
+        let mut stage = SystemStage::single_threaded().with_system(smooth_camera);
+        for _ in 0..frames {
+            stage.run(&mut world);
+        }
+        world
+            .entity_mut(entity)
+            .remove::<CameraComponent>()
+            .unwrap()
+    }
+
+    #[test]
+    fn camera_smoothing_converges() {
+        let camera = run_smoothing(
+            CameraComponent {
+                x: 1.0,
+                y: 0.5,
+                camera_smoothing: 0.1,
+                smooth_rotation: true,
+                ..Default::default()
+            },
+            1,
+        );
+        // Moves towards the target, but not all the way
+        assert!(camera.current_x > 0.0 && camera.current_x < 1.0);
+
+        let camera = run_smoothing(camera, 120);
+        assert!((camera.current_x - 1.0).abs() < 1e-3);
+        assert!((camera.current_y - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn camera_smoothing_wraps_yaw() {
+        use std::f32::consts::PI;
+        let camera = run_smoothing(
+            CameraComponent {
+                x: PI - 0.1,
+                current_x: -PI + 0.1,
+                camera_smoothing: 0.1,
+                smooth_rotation: true,
+                ..Default::default()
+            },
+            1,
+        );
+        // Takes the short way across +-180 degrees instead of turning through 0
+        assert!(camera.current_x.abs() > PI - 0.1);
+    }
+
+    #[test]
+    fn camera_is_added_by_uuid() {
+        testing::install();
+        let mut core = UnrealCore::new(&MyModule);
+        core.begin_play(&MyModule);
+        let actor = testing::spawn_actor();
+        core.world_mut().send_event(ActorSpawnedEvent {
+            actor: ActorPtr(actor),
+        });
+        core.tick(1.0 / 60.0);
+
+        let uuid = CameraComponent::TYPE_UUID;
+        assert_eq!(core.add_component(actor, uuid), ffi::ResultCode::Success);
+        let entity = entity_for_actor(core.world(), actor).unwrap();
+        let camera = core.world().get::<CameraComponent>(entity).unwrap();
+        assert_eq!((camera.x, camera.y), (0.0, 0.0));
+        assert_eq!(camera.camera_smoothing, 0.0);
+        assert!(matches!(camera.projection, Projection::Perspective { .. }));
+
+        assert_eq!(
+            core.add_component(actor, Uuid::nil()),
+            ffi::ResultCode::NotFound
+        );
+        assert_eq!(
+            core.add_component(testing::spawn_actor(), uuid),
+            ffi::ResultCode::ActorNotFound
+        );
+    }
+}